@@ -1,5 +1,5 @@
 // Comprehensive pretty print demonstration
-use sqlexpr_rust::parse;
+use sqlexpr_rust::{parse, pretty};
 
 fn main() {
     println!("SQL Expression Parser - Pretty Print Feature Demo");
@@ -26,11 +26,14 @@ fn main() {
         println!("Test Case {}: {}", i + 1, name);
         println!("{}", "=".repeat(60));
         match parse(expr) {
-            Ok(_) => {
+            Ok(ast) => {
                 if std::env::var("SQLEXPR_PRETTY").is_err() {
                     println!("Expression: {}", expr);
                     println!("Status: ✓ Parsed successfully");
                     println!("(Set SQLEXPR_PRETTY=true to see AST details)\n");
+                } else {
+                    println!("Input: {}", expr);
+                    println!("AST:\n{}", pretty::tree_string(&ast));
                 }
             }
             Err(e) => println!("✗ Error: {}\n", e),
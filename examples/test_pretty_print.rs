@@ -1,5 +1,5 @@
 // Test pretty printing functionality
-use sqlexpr_rust::parse;
+use sqlexpr_rust::{parse, pretty};
 
 fn main() {
     println!("Testing Pretty Print Feature");
@@ -15,11 +15,19 @@ fn main() {
     ];
 
     println!("Note: Set SQLEXPR_PRETTY=true environment variable to enable pretty printing\n");
-    
+    let pretty_print = std::env::var("SQLEXPR_PRETTY")
+        .map(|v| v.to_lowercase() == "true")
+        .unwrap_or(false);
+
     for expr in examples {
         println!("Expression: {}", expr);
         match parse(expr) {
-            Ok(_) => println!("✓ Parsed successfully\n"),
+            Ok(ast) => {
+                if pretty_print {
+                    println!("AST:\n{}", pretty::tree_string(&ast));
+                }
+                println!("✓ Parsed successfully\n");
+            }
             Err(e) => println!("✗ Error: {}\n", e),
         }
     }
@@ -58,7 +58,7 @@ fn main() {
     println!("------------------------");
     println!("Parse error: Parse error: Unterminated string literal near position 20 in:");
     println!("  name LIKE '%test");
-    println!("");
+    println!();
     println!("thread 'test_like_operator' panicked at:");
     println!("assertion `left == right` failed: Expected LIKE expression");
     println!("  left: false");
@@ -4,33 +4,49 @@
 //! The design enforces type safety at the grammar level: all top-level expressions
 //! must be boolean, while arithmetic/value expressions can only appear as operands
 //! to relational operators.
+//!
+//! Every node records the `Span` of the input it was parsed from.  Spans are
+//! ignored by the `Display` implementations.
 
 use std::fmt;
 
+use crate::collation::Collation;
+use crate::decimal::Decimal;
+use crate::lexer::Lexer;
+use crate::pattern::{LikePattern, RegexPattern, RegexSyntax};
+use crate::span::Span;
+use crate::temporal::{Date, Interval, Timestamp};
+
 // ============================================================================
 // BOOLEAN EXPRESSION HIERARCHY (Top Level - Always boolean)
 // ============================================================================
 
 /// Root expression type - must evaluate to boolean
-#[derive(Debug, Clone, PartialEq)]
+///
+/// `Clone`, `PartialEq` and `Debug` are implemented by hand, to walk chains such
+/// as `a OR b OR c` with a loop (see [`BooleanExpr::depth`]); they behave as the
+/// derived implementations would.
 pub enum BooleanExpr {
     /// Logical OR operation (lowest precedence)
-    Or(Box<BooleanExpr>, Box<BooleanExpr>),
+    Or(Box<BooleanExpr>, Box<BooleanExpr>, Span),
 
     /// Logical AND operation
-    And(Box<BooleanExpr>, Box<BooleanExpr>),
+    And(Box<BooleanExpr>, Box<BooleanExpr>, Span),
 
     /// Logical NOT operation
-    Not(Box<BooleanExpr>),
+    Not(Box<BooleanExpr>, Span),
 
     /// Boolean literal (TRUE or FALSE)
-    Literal(bool),
+    Literal(bool, Span),
 
     /// Variable reference (type checked at runtime)
-    Variable(String),
+    Variable(String, Span),
+
+    /// Path into the map or list bound to a variable, e.g. `flags.enabled`
+    Path(String, Vec<PathSegment>, Span),
 
     /// Relational expression (comparisons that produce boolean results)
-    Relational(RelationalExpr),
+    Relational(Box<RelationalExpr>),
 }
 
 // ============================================================================
@@ -45,6 +61,7 @@ pub enum RelationalExpr {
         left: ValueExpr,
         op: EqualityOp,
         right: ValueExpr,
+        span: Span,
     },
 
     /// Simple comparison: >, >=, <, <=
@@ -52,14 +69,25 @@ pub enum RelationalExpr {
         left: ValueExpr,
         op: ComparisonOp,
         right: ValueExpr,
+        span: Span,
     },
 
-    /// LIKE pattern matching
+    /// LIKE pattern matching against a pattern compiled at parse time; ILIKE
+    /// when `case_insensitive` is set
     Like {
         expr: ValueExpr,
-        pattern: String,
-        escape: Option<String>,
+        pattern: LikePattern,
         negated: bool,
+        case_insensitive: bool,
+        span: Span,
+    },
+
+    /// REGEXP or SIMILAR TO matching against a pattern compiled at parse time
+    Regex {
+        expr: ValueExpr,
+        pattern: RegexPattern,
+        negated: bool,
+        span: Span,
     },
 
     /// BETWEEN range check
@@ -68,19 +96,47 @@ pub enum RelationalExpr {
         lower: ValueExpr,
         upper: ValueExpr,
         negated: bool,
+        span: Span,
     },
 
-    /// IN list membership
+    /// IN list membership, e.g. `region IN (home_region, 'global')`
     In {
         expr: ValueExpr,
-        values: Vec<ValueLiteral>,
+        values: Vec<ValueExpr>,
         negated: bool,
+        span: Span,
+    },
+
+    /// IN membership in the list or map a value expression evaluates to,
+    /// e.g. `'vip' IN tags`
+    InCollection {
+        expr: ValueExpr,
+        collection: ValueExpr,
+        negated: bool,
+        span: Span,
+    },
+
+    /// CONTAINS membership, e.g. `tags CONTAINS 'vip'`; maps contain their keys
+    Contains {
+        collection: ValueExpr,
+        value: ValueExpr,
+        negated: bool,
+        span: Span,
     },
 
     /// IS NULL / IS NOT NULL
     IsNull {
         expr: ValueExpr,
         negated: bool,
+        span: Span,
+    },
+
+    /// A predicate ending in `COLLATE name`, which compares its strings under
+    /// that collation instead of the one in the evaluation options
+    Collated {
+        expr: Box<RelationalExpr>,
+        collation: Collation,
+        span: Span,
     },
 }
 
@@ -100,39 +156,95 @@ pub enum ComparisonOp {
     LessOrEqual,       // <=
 }
 
+/// Quantifier of a comparison against every element of a list or map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantifier {
+    Any,               // ANY(...)
+    All,               // ALL(...)
+}
+
 // ============================================================================
 // VALUE EXPRESSION HIERARCHY (Operands only - numeric/string values)
 // ============================================================================
 
 /// Value expressions - can only appear as operands to relational operators
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Like [`BooleanExpr`], implements `Clone`, `PartialEq` and `Debug` by hand to
+/// walk chains such as `a + b - c` with a loop.
 pub enum ValueExpr {
     /// Binary addition
-    Add(Box<ValueExpr>, Box<ValueExpr>),
+    Add(Box<ValueExpr>, Box<ValueExpr>, Span),
 
     /// Binary subtraction
-    Subtract(Box<ValueExpr>, Box<ValueExpr>),
+    Subtract(Box<ValueExpr>, Box<ValueExpr>, Span),
 
     /// Binary multiplication
-    Multiply(Box<ValueExpr>, Box<ValueExpr>),
+    Multiply(Box<ValueExpr>, Box<ValueExpr>, Span),
 
     /// Binary division
-    Divide(Box<ValueExpr>, Box<ValueExpr>),
+    Divide(Box<ValueExpr>, Box<ValueExpr>, Span),
 
     /// Binary modulo
-    Modulo(Box<ValueExpr>, Box<ValueExpr>),
+    Modulo(Box<ValueExpr>, Box<ValueExpr>, Span),
+
+    /// String concatenation (`||`)
+    Concat(Box<ValueExpr>, Box<ValueExpr>, Span),
 
     /// Unary plus
-    UnaryPlus(Box<ValueExpr>),
+    UnaryPlus(Box<ValueExpr>, Span),
 
     /// Unary minus (negation)
-    UnaryMinus(Box<ValueExpr>),
+    UnaryMinus(Box<ValueExpr>, Span),
 
     /// Literal value
-    Literal(ValueLiteral),
+    Literal(ValueLiteral, Span),
 
     /// Variable reference
-    Variable(String),
+    Variable(String, Span),
+
+    /// Path into the map or list bound to a variable, e.g. `order.customer.tier`
+    /// or `items[0].sku`
+    Path(String, Vec<PathSegment>, Span),
+
+    /// Parameter placeholder, bound separately from variables
+    Parameter(Parameter, Span),
+
+    /// Scalar function call, e.g. UPPER(name)
+    FunctionCall {
+        name: String,
+        args: Vec<ValueExpr>,
+        span: Span,
+    },
+
+    /// `ANY(scores)` or `ALL(scores)`: the elements of a list, or the values of
+    /// a map.  Only allowed as one operand of =, <>, <, <=, > or >=.
+    Quantified(Quantifier, Box<ValueExpr>, Span),
+
+    /// `CASE WHEN condition THEN result ... [ELSE result] END`: the result of
+    /// the first branch whose condition is true, else the ELSE result or NULL.
+    /// A simple CASE, `CASE operand WHEN value THEN result ... END`, has an
+    /// operand, evaluated once and compared with each WHEN value by `=`.
+    Case {
+        operand: Option<Box<ValueExpr>>,
+        branches: Vec<CaseBranch>,
+        else_result: Option<Box<ValueExpr>>,
+        span: Span,
+    },
+}
+
+/// One `WHEN ... THEN result` branch of a CASE expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseBranch {
+    pub when: CaseWhen,
+    pub result: ValueExpr,
+}
+
+/// What a CASE branch tests: a condition in a searched CASE, or a value to
+/// compare the operand with in a simple CASE
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaseWhen {
+    Condition(BooleanExpr),
+    Value(ValueExpr),
 }
 
 /// Literal values
@@ -144,6 +256,19 @@ pub enum ValueLiteral {
     /// Floating point literal
     Float(f64),
 
+    /// Exact decimal literal, read from numbers such as `0.10` when decimal
+    /// literals are enabled in the parser options
+    Decimal(Decimal),
+
+    /// Typed date literal, `DATE '2024-01-31'`
+    Date(Date),
+
+    /// Typed timestamp literal, `TIMESTAMP '2024-01-31 10:00:00+02:00'`
+    Timestamp(Timestamp),
+
+    /// Typed interval literal, `INTERVAL '7 days'` or `INTERVAL '7' DAY`
+    Interval(Interval),
+
     /// String literal
     String(String),
 
@@ -154,6 +279,592 @@ pub enum ValueLiteral {
     Boolean(bool),
 }
 
+/// Parameter placeholder
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Parameter {
+    /// `?`, numbered from 1 in order of appearance
+    Positional(usize),
+
+    /// `:name`
+    Named(String),
+}
+
+/// One step of a variable path
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// Map entry, `.name` or `['name']`
+    Field(String),
+
+    /// List element, `[0]`; the first element is 0
+    Index(usize),
+}
+
+// ============================================================================
+// SOURCE SPANS
+// ============================================================================
+
+impl BooleanExpr {
+    /// The range of the input this expression was parsed from
+    pub fn span(&self) -> Span {
+        match self {
+            BooleanExpr::Or(_, _, span)
+            | BooleanExpr::And(_, _, span)
+            | BooleanExpr::Not(_, span)
+            | BooleanExpr::Literal(_, span)
+            | BooleanExpr::Variable(_, span)
+            | BooleanExpr::Path(_, _, span) => *span,
+            BooleanExpr::Relational(rel) => rel.span(),
+        }
+    }
+
+    /// Number of levels in the expression tree.
+    ///
+    /// Variables, paths, literals and parameters are one level deep, and every operator
+    /// or function call is one level deeper than its deepest operand.  A chain of
+    /// operators of the same precedence, such as `a OR b OR c` or `a + b - c`,
+    /// is one level however long it is: each link is only a level above its
+    /// right operand.  This is the depth limited by [`ParserOptions::max_depth`];
+    /// it is computed without recursion, so it is safe to call on trees of any
+    /// depth.
+    ///
+    /// # Examples
+    /// ```
+    /// use sqlexpr_rust::parse;
+    ///
+    /// assert_eq!(parse("flag").unwrap().depth(), 1);
+    /// assert_eq!(parse("NOT (a > 1)").unwrap().depth(), 3);
+    /// assert_eq!(parse("a + b + c > 0").unwrap().depth(), 3);
+    /// assert_eq!(parse("a + (b + c) > 0").unwrap().depth(), 4);
+    /// ```
+    ///
+    /// [`ParserOptions::max_depth`]: crate::ParserOptions::max_depth
+    pub fn depth(&self) -> usize {
+        enum Node<'a> {
+            Boolean(&'a BooleanExpr),
+            Relational(&'a RelationalExpr),
+            Value(&'a ValueExpr),
+        }
+
+        let mut deepest = 0;
+        let mut pending = vec![(Node::Boolean(self), 1)];
+        while let Some((node, depth)) = pending.pop() {
+            deepest = deepest.max(depth);
+            match node {
+                Node::Boolean(expr @ (BooleanExpr::Or(left, right, _) | BooleanExpr::And(left, right, _))) => {
+                    // The links of a chain such as `a OR b OR c` share a level
+                    let chained = expr.as_logical().is_some_and(|(op, ..)| left.continues_chain(op));
+                    pending.push((Node::Boolean(left), if chained { depth } else { depth + 1 }));
+                    pending.push((Node::Boolean(right), depth + 1));
+                }
+                Node::Boolean(BooleanExpr::Not(inner, _)) => pending.push((Node::Boolean(inner), depth + 1)),
+                Node::Boolean(BooleanExpr::Literal(..) | BooleanExpr::Variable(..) | BooleanExpr::Path(..)) => {}
+                // The relational expression is this level of the tree
+                Node::Boolean(BooleanExpr::Relational(rel)) => pending.push((Node::Relational(rel), depth)),
+                Node::Relational(rel) => {
+                    let operands: &[&ValueExpr] = match rel {
+                        RelationalExpr::Equality { left, right, .. }
+                        | RelationalExpr::Comparison { left, right, .. } => &[left, right],
+                        RelationalExpr::Between { expr, lower, upper, .. } => &[expr, lower, upper],
+                        RelationalExpr::InCollection { expr: left, collection: right, .. }
+                        | RelationalExpr::Contains { collection: left, value: right, .. } => &[left, right],
+                        RelationalExpr::In { expr, values, .. } => {
+                            pending.extend(values.iter().map(|value| (Node::Value(value), depth + 1)));
+                            &[expr]
+                        }
+                        RelationalExpr::Like { expr, .. }
+                        | RelationalExpr::Regex { expr, .. }
+                        | RelationalExpr::IsNull { expr, .. } => &[expr],
+                        // The collation annotates the predicate without adding a level
+                        RelationalExpr::Collated { expr, .. } => {
+                            pending.push((Node::Relational(expr), depth));
+                            &[]
+                        }
+                    };
+                    pending.extend(operands.iter().map(|operand| (Node::Value(operand), depth + 1)));
+                }
+                Node::Value(
+                    expr @ (ValueExpr::Add(left, right, _)
+                    | ValueExpr::Subtract(left, right, _)
+                    | ValueExpr::Multiply(left, right, _)
+                    | ValueExpr::Divide(left, right, _)
+                    | ValueExpr::Modulo(left, right, _)
+                    | ValueExpr::Concat(left, right, _)),
+                ) => {
+                    // The links of a chain such as `a + b - c` share a level
+                    let chained = expr.as_binary().is_some_and(|(op, ..)| left.continues_chain(op));
+                    pending.push((Node::Value(left), if chained { depth } else { depth + 1 }));
+                    pending.push((Node::Value(right), depth + 1));
+                }
+                Node::Value(
+                    ValueExpr::UnaryPlus(inner, _) | ValueExpr::UnaryMinus(inner, _) | ValueExpr::Quantified(_, inner, _),
+                ) => {
+                    pending.push((Node::Value(inner), depth + 1));
+                }
+                Node::Value(ValueExpr::FunctionCall { args, .. }) => {
+                    pending.extend(args.iter().map(|arg| (Node::Value(arg), depth + 1)));
+                }
+                Node::Value(ValueExpr::Case { operand, branches, else_result, .. }) => {
+                    pending.extend(operand.iter().map(|operand| (Node::Value(operand), depth + 1)));
+                    for branch in branches {
+                        pending.push(match &branch.when {
+                            CaseWhen::Condition(condition) => (Node::Boolean(condition), depth + 1),
+                            CaseWhen::Value(value) => (Node::Value(value), depth + 1),
+                        });
+                        pending.push((Node::Value(&branch.result), depth + 1));
+                    }
+                    pending.extend(else_result.iter().map(|result| (Node::Value(result), depth + 1)));
+                }
+                Node::Value(
+                    ValueExpr::Literal(..) | ValueExpr::Variable(..) | ValueExpr::Path(..) | ValueExpr::Parameter(..),
+                ) => {}
+            }
+        }
+        deepest
+    }
+}
+
+impl RelationalExpr {
+    /// The range of the input this expression was parsed from
+    pub fn span(&self) -> Span {
+        match self {
+            RelationalExpr::Equality { span, .. }
+            | RelationalExpr::Comparison { span, .. }
+            | RelationalExpr::Like { span, .. }
+            | RelationalExpr::Regex { span, .. }
+            | RelationalExpr::Between { span, .. }
+            | RelationalExpr::In { span, .. }
+            | RelationalExpr::InCollection { span, .. }
+            | RelationalExpr::Contains { span, .. }
+            | RelationalExpr::IsNull { span, .. }
+            | RelationalExpr::Collated { span, .. } => *span,
+        }
+    }
+}
+
+impl ValueExpr {
+    /// The range of the input this expression was parsed from
+    pub fn span(&self) -> Span {
+        match self {
+            ValueExpr::Add(_, _, span)
+            | ValueExpr::Subtract(_, _, span)
+            | ValueExpr::Multiply(_, _, span)
+            | ValueExpr::Divide(_, _, span)
+            | ValueExpr::Modulo(_, _, span)
+            | ValueExpr::Concat(_, _, span)
+            | ValueExpr::UnaryPlus(_, span)
+            | ValueExpr::UnaryMinus(_, span)
+            | ValueExpr::Literal(_, span)
+            | ValueExpr::Variable(_, span)
+            | ValueExpr::Path(_, _, span)
+            | ValueExpr::Parameter(_, span)
+            | ValueExpr::Quantified(_, _, span)
+            | ValueExpr::FunctionCall { span, .. }
+            | ValueExpr::Case { span, .. } => *span,
+        }
+    }
+}
+
+// ============================================================================
+// CHAINS OF BINARY OPERATORS
+// ============================================================================
+
+/// Operator of an AND or OR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LogicalOp {
+    And,
+    Or,
+}
+
+/// Operator of a binary value expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    Concat,
+}
+
+impl BinaryOp {
+    /// Operators of the same precedence chain left to right without parentheses
+    fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Concat => 0,
+            BinaryOp::Add | BinaryOp::Subtract => 1,
+            BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Modulo => 2,
+        }
+    }
+}
+
+impl BooleanExpr {
+    /// Build an AND or OR
+    pub(crate) fn logical(op: LogicalOp, left: BooleanExpr, right: BooleanExpr, span: Span) -> BooleanExpr {
+        match op {
+            LogicalOp::And => BooleanExpr::And(Box::new(left), Box::new(right), span),
+            LogicalOp::Or => BooleanExpr::Or(Box::new(left), Box::new(right), span),
+        }
+    }
+
+    /// The operator and operands of an AND or OR
+    pub(crate) fn as_logical(&self) -> Option<(LogicalOp, &BooleanExpr, &BooleanExpr, Span)> {
+        match self {
+            BooleanExpr::And(left, right, span) => Some((LogicalOp::And, left, right, *span)),
+            BooleanExpr::Or(left, right, span) => Some((LogicalOp::Or, left, right, *span)),
+            _ => None,
+        }
+    }
+
+    /// Whether `op` with this expression as its left operand continues a chain,
+    /// like the second OR of `a OR b OR c`
+    pub(crate) fn continues_chain(&self, op: LogicalOp) -> bool {
+        self.as_logical().is_some_and(|(left_op, ..)| left_op == op)
+    }
+
+    /// Split the ANDs and ORs down the left side of this expression into the
+    /// first operand and the links after it, innermost first.
+    ///
+    /// `a OR b OR c` parses as `((a OR b) OR c)`, nested as deep as the chain is
+    /// long, so the walks over the tree follow chains with a loop; they only
+    /// recurse into right operands, which are nested a level deeper.
+    pub(crate) fn chain(&self) -> (&BooleanExpr, Vec<(LogicalOp, &BooleanExpr, Span)>) {
+        let mut first = self;
+        let mut links = Vec::new();
+        while let Some((op, left, right, span)) = first.as_logical() {
+            links.push((op, right, span));
+            first = left;
+        }
+        links.reverse();
+        (first, links)
+    }
+}
+
+impl ValueExpr {
+    /// Build a binary operation
+    pub(crate) fn binary(op: BinaryOp, left: ValueExpr, right: ValueExpr, span: Span) -> ValueExpr {
+        let (left, right) = (Box::new(left), Box::new(right));
+        match op {
+            BinaryOp::Add => ValueExpr::Add(left, right, span),
+            BinaryOp::Subtract => ValueExpr::Subtract(left, right, span),
+            BinaryOp::Multiply => ValueExpr::Multiply(left, right, span),
+            BinaryOp::Divide => ValueExpr::Divide(left, right, span),
+            BinaryOp::Modulo => ValueExpr::Modulo(left, right, span),
+            BinaryOp::Concat => ValueExpr::Concat(left, right, span),
+        }
+    }
+
+    /// The operator and operands of a binary operation
+    pub(crate) fn as_binary(&self) -> Option<(BinaryOp, &ValueExpr, &ValueExpr, Span)> {
+        let (op, left, right, span) = match self {
+            ValueExpr::Add(left, right, span) => (BinaryOp::Add, left, right, span),
+            ValueExpr::Subtract(left, right, span) => (BinaryOp::Subtract, left, right, span),
+            ValueExpr::Multiply(left, right, span) => (BinaryOp::Multiply, left, right, span),
+            ValueExpr::Divide(left, right, span) => (BinaryOp::Divide, left, right, span),
+            ValueExpr::Modulo(left, right, span) => (BinaryOp::Modulo, left, right, span),
+            ValueExpr::Concat(left, right, span) => (BinaryOp::Concat, left, right, span),
+            _ => return None,
+        };
+        Some((op, left, right, *span))
+    }
+
+    /// Whether `op` with this expression as its left operand continues a chain
+    /// of operators of the same precedence, like the `-` of `a + b - c`
+    pub(crate) fn continues_chain(&self, op: BinaryOp) -> bool {
+        self.as_binary().is_some_and(|(left_op, ..)| left_op.precedence() == op.precedence())
+    }
+
+    /// Split the binary operations down the left side of this expression into
+    /// the first operand and the links after it, innermost first, as
+    /// [`BooleanExpr::chain`] does for ANDs and ORs
+    pub(crate) fn chain(&self) -> (&ValueExpr, Vec<(BinaryOp, &ValueExpr, Span)>) {
+        let mut first = self;
+        let mut links = Vec::new();
+        while let Some((op, left, right, span)) = first.as_binary() {
+            links.push((op, right, span));
+            first = left;
+        }
+        links.reverse();
+        (first, links)
+    }
+}
+
+// ============================================================================
+// CLONE, EQUALITY AND DEBUG IMPLEMENTATIONS
+// ============================================================================
+
+impl Drop for BooleanExpr {
+    /// Unlink a chain before dropping it, so dropping does not recurse once per link
+    fn drop(&mut self) {
+        let Some(mut left) = self.take_left() else { return };
+        while let Some(next) = left.take_left() {
+            left = next;
+        }
+    }
+}
+
+impl BooleanExpr {
+    /// Take the left operand of an AND or OR, leaving a literal in its place
+    fn take_left(&mut self) -> Option<BooleanExpr> {
+        match self {
+            BooleanExpr::Or(left, ..) | BooleanExpr::And(left, ..) => {
+                Some(std::mem::replace(left.as_mut(), BooleanExpr::Literal(false, Span::default())))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Clone for BooleanExpr {
+    fn clone(&self) -> Self {
+        match self {
+            BooleanExpr::Or(..) | BooleanExpr::And(..) => {
+                let (first, links) = self.chain();
+                links.into_iter().fold(first.clone(), |left, (op, right, span)| {
+                    BooleanExpr::logical(op, left, right.clone(), span)
+                })
+            }
+            BooleanExpr::Not(inner, span) => BooleanExpr::Not(inner.clone(), *span),
+            BooleanExpr::Literal(b, span) => BooleanExpr::Literal(*b, *span),
+            BooleanExpr::Variable(name, span) => BooleanExpr::Variable(name.clone(), *span),
+            BooleanExpr::Path(root, segments, span) => BooleanExpr::Path(root.clone(), segments.clone(), *span),
+            BooleanExpr::Relational(rel) => BooleanExpr::Relational(rel.clone()),
+        }
+    }
+}
+
+impl PartialEq for BooleanExpr {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BooleanExpr::Or(..) | BooleanExpr::And(..), _) => {
+                let (first, links) = self.chain();
+                let (other_first, other_links) = other.chain();
+                links == other_links && first == other_first
+            }
+            (BooleanExpr::Not(a, a_span), BooleanExpr::Not(b, b_span)) => a == b && a_span == b_span,
+            (BooleanExpr::Literal(a, a_span), BooleanExpr::Literal(b, b_span)) => a == b && a_span == b_span,
+            (BooleanExpr::Variable(a, a_span), BooleanExpr::Variable(b, b_span)) => a == b && a_span == b_span,
+            (BooleanExpr::Path(a_root, a, a_span), BooleanExpr::Path(b_root, b, b_span)) => {
+                a_root == b_root && a == b && a_span == b_span
+            }
+            (BooleanExpr::Relational(a), BooleanExpr::Relational(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for BooleanExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BooleanExpr::Or(..) | BooleanExpr::And(..) => {
+                let (first, links) = self.chain();
+                debug_chain(f, first, &links)
+            }
+            BooleanExpr::Not(inner, span) => f.debug_tuple("Not").field(inner).field(span).finish(),
+            BooleanExpr::Literal(b, span) => f.debug_tuple("Literal").field(b).field(span).finish(),
+            BooleanExpr::Variable(name, span) => f.debug_tuple("Variable").field(name).field(span).finish(),
+            BooleanExpr::Path(root, segments, span) => {
+                f.debug_tuple("Path").field(root).field(segments).field(span).finish()
+            }
+            BooleanExpr::Relational(rel) => f.debug_tuple("Relational").field(rel).finish(),
+        }
+    }
+}
+
+impl Drop for ValueExpr {
+    /// Unlink a chain before dropping it, as [`BooleanExpr`] does
+    fn drop(&mut self) {
+        let Some(mut left) = self.take_left() else { return };
+        while let Some(next) = left.take_left() {
+            left = next;
+        }
+    }
+}
+
+impl ValueExpr {
+    /// Take the left operand of a binary operation, leaving NULL in its place
+    fn take_left(&mut self) -> Option<ValueExpr> {
+        match self {
+            ValueExpr::Add(left, ..)
+            | ValueExpr::Subtract(left, ..)
+            | ValueExpr::Multiply(left, ..)
+            | ValueExpr::Divide(left, ..)
+            | ValueExpr::Modulo(left, ..)
+            | ValueExpr::Concat(left, ..) => {
+                Some(std::mem::replace(left.as_mut(), ValueExpr::Literal(ValueLiteral::Null, Span::default())))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Clone for ValueExpr {
+    fn clone(&self) -> Self {
+        match self {
+            ValueExpr::Add(..)
+            | ValueExpr::Subtract(..)
+            | ValueExpr::Multiply(..)
+            | ValueExpr::Divide(..)
+            | ValueExpr::Modulo(..)
+            | ValueExpr::Concat(..) => {
+                let (first, links) = self.chain();
+                links.into_iter().fold(first.clone(), |left, (op, right, span)| {
+                    ValueExpr::binary(op, left, right.clone(), span)
+                })
+            }
+            ValueExpr::UnaryPlus(inner, span) => ValueExpr::UnaryPlus(inner.clone(), *span),
+            ValueExpr::UnaryMinus(inner, span) => ValueExpr::UnaryMinus(inner.clone(), *span),
+            ValueExpr::Literal(lit, span) => ValueExpr::Literal(lit.clone(), *span),
+            ValueExpr::Variable(name, span) => ValueExpr::Variable(name.clone(), *span),
+            ValueExpr::Path(root, segments, span) => ValueExpr::Path(root.clone(), segments.clone(), *span),
+            ValueExpr::Parameter(param, span) => ValueExpr::Parameter(param.clone(), *span),
+            ValueExpr::FunctionCall { name, args, span } => ValueExpr::FunctionCall {
+                name: name.clone(),
+                args: args.clone(),
+                span: *span,
+            },
+            ValueExpr::Quantified(quantifier, collection, span) => {
+                ValueExpr::Quantified(*quantifier, collection.clone(), *span)
+            }
+            ValueExpr::Case { operand, branches, else_result, span } => ValueExpr::Case {
+                operand: operand.clone(),
+                branches: branches.clone(),
+                else_result: else_result.clone(),
+                span: *span,
+            },
+        }
+    }
+}
+
+impl PartialEq for ValueExpr {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                ValueExpr::Add(..)
+                | ValueExpr::Subtract(..)
+                | ValueExpr::Multiply(..)
+                | ValueExpr::Divide(..)
+                | ValueExpr::Modulo(..)
+                | ValueExpr::Concat(..),
+                _,
+            ) => {
+                let (first, links) = self.chain();
+                let (other_first, other_links) = other.chain();
+                links == other_links && first == other_first
+            }
+            (ValueExpr::UnaryPlus(a, a_span), ValueExpr::UnaryPlus(b, b_span))
+            | (ValueExpr::UnaryMinus(a, a_span), ValueExpr::UnaryMinus(b, b_span)) => a == b && a_span == b_span,
+            (ValueExpr::Literal(a, a_span), ValueExpr::Literal(b, b_span)) => a == b && a_span == b_span,
+            (ValueExpr::Variable(a, a_span), ValueExpr::Variable(b, b_span)) => a == b && a_span == b_span,
+            (ValueExpr::Path(a_root, a, a_span), ValueExpr::Path(b_root, b, b_span)) => {
+                a_root == b_root && a == b && a_span == b_span
+            }
+            (ValueExpr::Parameter(a, a_span), ValueExpr::Parameter(b, b_span)) => a == b && a_span == b_span,
+            (
+                ValueExpr::FunctionCall { name: a_name, args: a, span: a_span },
+                ValueExpr::FunctionCall { name: b_name, args: b, span: b_span },
+            ) => a_name == b_name && a == b && a_span == b_span,
+            (ValueExpr::Quantified(a_quantifier, a, a_span), ValueExpr::Quantified(b_quantifier, b, b_span)) => {
+                a_quantifier == b_quantifier && a == b && a_span == b_span
+            }
+            (
+                ValueExpr::Case { operand: a_operand, branches: a, else_result: a_else, span: a_span },
+                ValueExpr::Case { operand: b_operand, branches: b, else_result: b_else, span: b_span },
+            ) => a_operand == b_operand && a == b && a_else == b_else && a_span == b_span,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for ValueExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueExpr::Add(..)
+            | ValueExpr::Subtract(..)
+            | ValueExpr::Multiply(..)
+            | ValueExpr::Divide(..)
+            | ValueExpr::Modulo(..)
+            | ValueExpr::Concat(..) => {
+                let (first, links) = self.chain();
+                debug_chain(f, first, &links)
+            }
+            ValueExpr::UnaryPlus(inner, span) => f.debug_tuple("UnaryPlus").field(inner).field(span).finish(),
+            ValueExpr::UnaryMinus(inner, span) => f.debug_tuple("UnaryMinus").field(inner).field(span).finish(),
+            ValueExpr::Literal(lit, span) => f.debug_tuple("Literal").field(lit).field(span).finish(),
+            ValueExpr::Variable(name, span) => f.debug_tuple("Variable").field(name).field(span).finish(),
+            ValueExpr::Path(root, segments, span) => {
+                f.debug_tuple("Path").field(root).field(segments).field(span).finish()
+            }
+            ValueExpr::Parameter(param, span) => f.debug_tuple("Parameter").field(param).field(span).finish(),
+            ValueExpr::FunctionCall { name, args, span } => f.debug_struct("FunctionCall")
+                .field("name", name)
+                .field("args", args)
+                .field("span", span)
+                .finish(),
+            ValueExpr::Quantified(quantifier, collection, span) => {
+                f.debug_tuple("Quantified").field(quantifier).field(collection).field(span).finish()
+            }
+            ValueExpr::Case { operand, branches, else_result, span } => f.debug_struct("Case")
+                .field("operand", operand)
+                .field("branches", branches)
+                .field("else_result", else_result)
+                .field("span", span)
+                .finish(),
+        }
+    }
+}
+
+/// Write a chain split by `chain` as the derived `Debug` would write the nested
+/// nodes, `Or(Or(a, b, span), c, span)`, or in the alternate form with each
+/// node's fields on lines of their own, indented four spaces further
+fn debug_chain<T: fmt::Debug, Op: fmt::Debug>(f: &mut fmt::Formatter<'_>, first: &T, links: &[(Op, &T, Span)])
+    -> fmt::Result
+{
+    use fmt::Write;
+
+    if !f.alternate() {
+        for (op, ..) in links.iter().rev() {
+            write!(f, "{:?}(", op)?;
+        }
+        write!(f, "{:?}", first)?;
+        return links.iter().try_for_each(|(_, right, span)| write!(f, ", {:?}, {:?})", right, span));
+    }
+
+    let mut out = Indented { f, width: 0, on_newline: false };
+    for (level, (op, ..)) in links.iter().rev().enumerate() {
+        out.width = 4 * level;
+        writeln!(out, "{:?}(", op)?;
+    }
+    out.width = 4 * links.len();
+    write!(out, "{:#?}", first)?;
+    for (level, (_, right, span)) in (0..links.len()).rev().zip(links) {
+        out.width = 4 * (level + 1);
+        write!(out, ",\n{:#?},\n{:#?},\n", right, span)?;
+        out.width = 4 * level;
+        write!(out, ")")?;
+    }
+    Ok(())
+}
+
+/// Writer that indents every line by `width` spaces
+struct Indented<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    width: usize,
+    on_newline: bool,
+}
+
+impl fmt::Write for Indented<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for line in s.split_inclusive('\n') {
+            if self.on_newline {
+                write!(self.f, "{:1$}", "", self.width)?;
+            }
+            self.on_newline = line.ends_with('\n');
+            self.f.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
 // ============================================================================
 // DISPLAY IMPLEMENTATIONS
 // ============================================================================
@@ -161,11 +872,15 @@ pub enum ValueLiteral {
 impl fmt::Display for BooleanExpr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            BooleanExpr::Or(left, right) => write!(f, "({} OR {})", left, right),
-            BooleanExpr::And(left, right) => write!(f, "({} AND {})", left, right),
-            BooleanExpr::Not(expr) => write!(f, "NOT {}", expr),
-            BooleanExpr::Literal(b) => write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
-            BooleanExpr::Variable(name) => write!(f, "{}", name),
+            BooleanExpr::Or(..) | BooleanExpr::And(..) => {
+                let (first, links) = self.chain();
+                write!(f, "{}{}", "(".repeat(links.len()), first)?;
+                links.iter().try_for_each(|(op, right, _)| write!(f, " {} {})", op, right))
+            }
+            BooleanExpr::Not(expr, _) => write!(f, "NOT {}", expr),
+            BooleanExpr::Literal(b, _) => write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
+            BooleanExpr::Variable(name, _) => write!(f, "{}", name),
+            BooleanExpr::Path(root, segments, _) => write_path(f, root, segments),
             BooleanExpr::Relational(rel) => write!(f, "{}", rel),
         }
     }
@@ -174,31 +889,49 @@ impl fmt::Display for BooleanExpr {
 impl fmt::Display for RelationalExpr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            RelationalExpr::Equality { left, op, right } => {
+            RelationalExpr::Equality { left, op, right, .. } => {
                 write!(f, "{} {} {}", left, op, right)
             }
-            RelationalExpr::Comparison { left, op, right } => {
+            RelationalExpr::Comparison { left, op, right, .. } => {
                 write!(f, "{} {} {}", left, op, right)
             }
-            RelationalExpr::Like { expr, pattern, escape, negated } => {
+            RelationalExpr::Like { expr, pattern, negated, case_insensitive, .. } => {
+                let operator = if *case_insensitive { "ILIKE" } else { "LIKE" };
+                let text = pattern.pattern().replace('\'', "''");
                 if *negated {
-                    write!(f, "{} NOT LIKE '{}'", expr, pattern)?;
+                    write!(f, "{} NOT {} '{}'", expr, operator, text)?;
                 } else {
-                    write!(f, "{} LIKE '{}'", expr, pattern)?;
+                    write!(f, "{} {} '{}'", expr, operator, text)?;
                 }
-                if let Some(esc) = escape {
-                    write!(f, " ESCAPE '{}'", esc)?;
+                if let Some(esc) = pattern.escape() {
+                    write!(f, " ESCAPE '{}'", esc.replace('\'', "''"))?;
                 }
                 Ok(())
             }
-            RelationalExpr::Between { expr, lower, upper, negated } => {
+            RelationalExpr::Regex { expr, pattern, negated, .. } => {
+                let operator = match pattern.syntax() {
+                    RegexSyntax::Regexp => "REGEXP",
+                    RegexSyntax::SimilarTo => "SIMILAR TO",
+                };
+                let text = pattern.pattern().replace('\'', "''");
+                if *negated {
+                    write!(f, "{} NOT {} '{}'", expr, operator, text)?;
+                } else {
+                    write!(f, "{} {} '{}'", expr, operator, text)?;
+                }
+                if let Some(esc) = pattern.escape() {
+                    write!(f, " ESCAPE '{}'", esc.replace('\'', "''"))?;
+                }
+                Ok(())
+            }
+            RelationalExpr::Between { expr, lower, upper, negated, .. } => {
                 if *negated {
                     write!(f, "{} NOT BETWEEN {} AND {}", expr, lower, upper)
                 } else {
                     write!(f, "{} BETWEEN {} AND {}", expr, lower, upper)
                 }
             }
-            RelationalExpr::In { expr, values, negated } => {
+            RelationalExpr::In { expr, values, negated, .. } => {
                 if *negated {
                     write!(f, "{} NOT IN (", expr)?;
                 } else {
@@ -212,13 +945,30 @@ impl fmt::Display for RelationalExpr {
                 }
                 write!(f, ")")
             }
-            RelationalExpr::IsNull { expr, negated } => {
+            RelationalExpr::InCollection { expr, collection, negated, .. } => {
+                if *negated {
+                    write!(f, "{} NOT IN {}", expr, collection)
+                } else {
+                    write!(f, "{} IN {}", expr, collection)
+                }
+            }
+            RelationalExpr::Contains { collection, value, negated, .. } => {
+                if *negated {
+                    write!(f, "{} NOT CONTAINS {}", collection, value)
+                } else {
+                    write!(f, "{} CONTAINS {}", collection, value)
+                }
+            }
+            RelationalExpr::IsNull { expr, negated, .. } => {
                 if *negated {
                     write!(f, "{} IS NOT NULL", expr)
                 } else {
                     write!(f, "{} IS NULL", expr)
                 }
             }
+            RelationalExpr::Collated { expr, collation, .. } => {
+                write!(f, "{} COLLATE {}", expr, collation)
+            }
         }
     }
 }
@@ -243,18 +993,89 @@ impl fmt::Display for ComparisonOp {
     }
 }
 
+impl fmt::Display for LogicalOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogicalOp::And => write!(f, "AND"),
+            LogicalOp::Or => write!(f, "OR"),
+        }
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryOp::Add => write!(f, "+"),
+            BinaryOp::Subtract => write!(f, "-"),
+            BinaryOp::Multiply => write!(f, "*"),
+            BinaryOp::Divide => write!(f, "/"),
+            BinaryOp::Modulo => write!(f, "%"),
+            BinaryOp::Concat => write!(f, "||"),
+        }
+    }
+}
+
+impl fmt::Display for Quantifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Quantifier::Any => write!(f, "ANY"),
+            Quantifier::All => write!(f, "ALL"),
+        }
+    }
+}
+
 impl fmt::Display for ValueExpr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ValueExpr::Add(left, right) => write!(f, "({} + {})", left, right),
-            ValueExpr::Subtract(left, right) => write!(f, "({} - {})", left, right),
-            ValueExpr::Multiply(left, right) => write!(f, "({} * {})", left, right),
-            ValueExpr::Divide(left, right) => write!(f, "({} / {})", left, right),
-            ValueExpr::Modulo(left, right) => write!(f, "({} % {})", left, right),
-            ValueExpr::UnaryPlus(expr) => write!(f, "+{}", expr),
-            ValueExpr::UnaryMinus(expr) => write!(f, "-{}", expr),
-            ValueExpr::Literal(lit) => write!(f, "{}", lit),
-            ValueExpr::Variable(name) => write!(f, "{}", name),
+            ValueExpr::Add(..)
+            | ValueExpr::Subtract(..)
+            | ValueExpr::Multiply(..)
+            | ValueExpr::Divide(..)
+            | ValueExpr::Modulo(..)
+            | ValueExpr::Concat(..) => {
+                let (first, links) = self.chain();
+                write!(f, "{}{}", "(".repeat(links.len()), first)?;
+                links.iter().try_for_each(|(op, right, _)| write!(f, " {} {})", op, right))
+            }
+            ValueExpr::UnaryPlus(expr, _) => write!(f, "+{}", expr),
+            ValueExpr::UnaryMinus(expr, _) => write!(f, "-{}", expr),
+            ValueExpr::Literal(lit, _) => write!(f, "{}", lit),
+            ValueExpr::Variable(name, _) => write!(f, "{}", name),
+            ValueExpr::Path(root, segments, _) => write_path(f, root, segments),
+            ValueExpr::Parameter(param, _) => write!(f, "{}", param),
+            ValueExpr::FunctionCall { name, args, .. } => {
+                write!(f, "{}(", name)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+            ValueExpr::Quantified(quantifier, collection, _) => write!(f, "{}({})", quantifier, collection),
+            ValueExpr::Case { operand, branches, else_result, .. } => {
+                write!(f, "CASE")?;
+                if let Some(operand) = operand {
+                    write!(f, " {}", operand)?;
+                }
+                for branch in branches {
+                    write!(f, " WHEN {} THEN {}", branch.when, branch.result)?;
+                }
+                if let Some(result) = else_result {
+                    write!(f, " ELSE {}", result)?;
+                }
+                write!(f, " END")
+            }
+        }
+    }
+}
+
+impl fmt::Display for CaseWhen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaseWhen::Condition(condition) => write!(f, "{}", condition),
+            CaseWhen::Value(value) => write!(f, "{}", value),
         }
     }
 }
@@ -263,10 +1084,42 @@ impl fmt::Display for ValueLiteral {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ValueLiteral::Integer(n) => write!(f, "{}", n),
-            ValueLiteral::Float(n) => write!(f, "{}", n),
-            ValueLiteral::String(s) => write!(f, "'{}'", s),
+            // Debug formatting keeps the decimal point (1.0 rather than 1) so the
+            // literal reads back as a float
+            ValueLiteral::Float(n) => write!(f, "{:?}", n),
+            // Typed, so that the text parses back as a decimal without decimal literals
+            ValueLiteral::Decimal(n) => write!(f, "DECIMAL '{}'", n),
+            ValueLiteral::Date(d) => write!(f, "DATE '{}'", d),
+            ValueLiteral::Timestamp(t) => write!(f, "TIMESTAMP '{}'", t),
+            ValueLiteral::Interval(i) => write!(f, "INTERVAL '{}'", i),
+            ValueLiteral::String(s) => write!(f, "'{}'", s.replace('\'', "''")),
             ValueLiteral::Null => write!(f, "NULL"),
             ValueLiteral::Boolean(b) => write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
         }
     }
 }
+
+impl fmt::Display for PathSegment {
+    /// Fields that read back as identifiers are written `.name`, others `['name']`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Field(name) if Lexer::is_identifier(name) => write!(f, ".{}", name),
+            PathSegment::Field(name) => write!(f, "['{}']", name.replace('\'', "''")),
+            PathSegment::Index(index) => write!(f, "[{}]", index),
+        }
+    }
+}
+
+fn write_path(f: &mut fmt::Formatter<'_>, root: &str, segments: &[PathSegment]) -> fmt::Result {
+    write!(f, "{}", root)?;
+    segments.iter().try_for_each(|segment| write!(f, "{}", segment))
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Parameter::Positional(_) => write!(f, "?"),
+            Parameter::Named(name) => write!(f, ":{}", name),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! String collations for SQL Expression Evaluation
+//!
+//! A collation decides when two strings are equal and how they are ordered.
+//! It applies to string operands of `=`, `<>`, `<`, `<=`, `>`, `>=`, BETWEEN,
+//! IN, CONTAINS, ANY/ALL comparisons and LIKE.  The collation comes from
+//! [`EvalOptions::collation`], or from a `COLLATE` clause ending the predicate,
+//! which takes precedence:
+//!
+//! ```sql
+//! country = 'germany' COLLATE NOCASE
+//! name LIKE 'jo%' COLLATE NOCASE
+//! ```
+//!
+//! Each collation maps a string to a key, and strings compare as their keys do,
+//! code point by code point.  Case-insensitive comparison lowercases the key
+//! with Unicode's default lowercase mapping; it does not apply language rules
+//! or full case folding, so `'ß'` does not equal `'SS'`.
+//!
+//! [`EvalOptions::collation`]: crate::EvalOptions::collation
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+/// How strings are compared.
+///
+/// # Examples
+/// ```
+/// use sqlexpr_rust::Collation;
+///
+/// assert!(Collation::NoCase.equals("Germany", "GERMANY"));
+/// // A precomposed "é" and "e" followed by a combining acute accent
+/// assert!(Collation::Unicode.equals("caf\u{e9}", "cafe\u{301}"));
+/// assert!(!Collation::Binary.equals("caf\u{e9}", "cafe\u{301}"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Collation {
+    /// Compare the strings' code points as they are (the default).
+    #[default]
+    Binary,
+
+    /// Compare the strings' Unicode canonical compositions (NFC), so that
+    /// different encodings of the same accented character are equal.
+    Unicode,
+
+    /// Compare lowercased NFC compositions, ignoring case as well as encoding.
+    NoCase,
+}
+
+impl Collation {
+    /// The name used in a `COLLATE` clause
+    pub fn name(self) -> &'static str {
+        match self {
+            Collation::Binary => "BINARY",
+            Collation::Unicode => "UNICODE",
+            Collation::NoCase => "NOCASE",
+        }
+    }
+
+    /// The collation with the given `COLLATE` name, ignoring case
+    pub fn from_name(name: &str) -> Option<Collation> {
+        [Collation::Binary, Collation::Unicode, Collation::NoCase]
+            .into_iter()
+            .find(|collation| collation.name().eq_ignore_ascii_case(name))
+    }
+
+    /// The key `s` is compared by under this collation
+    pub fn key(self, s: &str) -> Cow<'_, str> {
+        match self {
+            Collation::Binary => Cow::Borrowed(s),
+            Collation::Unicode if is_nfc_quick(s.chars()) == IsNormalized::Yes => Cow::Borrowed(s),
+            Collation::Unicode => Cow::Owned(s.nfc().collect()),
+            // Lowercasing the decomposition lowers the base letter of each
+            // accented character before it is recomposed
+            Collation::NoCase => Cow::Owned(s.nfd().flat_map(char::to_lowercase).nfc().collect()),
+        }
+    }
+
+    /// Order two strings under this collation
+    pub fn compare(self, a: &str, b: &str) -> Ordering {
+        match self {
+            Collation::Binary => a.cmp(b),
+            _ => self.key(a).cmp(&self.key(b)),
+        }
+    }
+
+    /// Whether two strings are equal under this collation
+    pub fn equals(self, a: &str, b: &str) -> bool {
+        self.compare(a, b) == Ordering::Equal
+    }
+}
+
+impl fmt::Display for Collation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
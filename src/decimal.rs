@@ -0,0 +1,548 @@
+//! Exact decimal numbers for SQL Expression Evaluation
+//!
+//! `Decimal` is a fixed-point number: an integer mantissa of at most 38 digits
+//! and a scale giving how many of those digits follow the decimal point, so
+//! `12.50` is stored as mantissa 1250 with scale 2.  Addition, subtraction,
+//! multiplication and remainder are exact; only results needing more than 38
+//! digits lose fractional digits (rounded half away from zero) or, if the
+//! integer part alone is too large, fail.  Division stops after
+//! [`Decimal::DIVISION_SCALE`] fractional digits and rounds the last one.
+//!
+//! Values compare and hash by their numeric value, so `1.5` equals `1.50`; the
+//! scale only affects how a value is displayed.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Neg;
+use std::str::FromStr;
+
+/// Largest mantissa: 38 nines
+const MAX_MANTISSA: i128 = 10i128.pow(Decimal::MAX_PRECISION) - 1;
+
+/// An exact decimal number with up to 38 significant digits.
+///
+/// # Examples
+/// ```
+/// use sqlexpr_rust::Decimal;
+///
+/// let a: Decimal = "0.1".parse().unwrap();
+/// let b: Decimal = "0.2".parse().unwrap();
+/// assert_eq!(a.checked_add(b).unwrap(), "0.3".parse().unwrap());
+/// assert_eq!("1.50".parse::<Decimal>().unwrap().to_string(), "1.50");
+/// ```
+// Aligning the i128 mantissa to 8 rather than 16 bytes keeps tokens, literals
+// and values containing a decimal no larger than they are without one
+#[derive(Clone, Copy)]
+#[repr(C, packed(8))]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    /// Maximum number of digits in the mantissa, and so also the maximum scale
+    pub const MAX_PRECISION: u32 = 38;
+
+    /// Number of fractional digits a quotient is computed to when the division
+    /// is not exact
+    pub const DIVISION_SCALE: u32 = 20;
+
+    /// Zero with scale 0
+    pub const ZERO: Decimal = Decimal { mantissa: 0, scale: 0 };
+
+    /// Create the decimal `mantissa * 10^-scale`.  Returns `None` if the mantissa
+    /// has more than 38 digits or the scale is more than 38.
+    pub fn new(mantissa: i128, scale: u32) -> Option<Decimal> {
+        (mantissa.unsigned_abs() <= MAX_MANTISSA as u128 && scale <= Self::MAX_PRECISION)
+            .then_some(Decimal { mantissa, scale })
+    }
+
+    /// The digits of the number as an integer
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    /// Number of digits after the decimal point
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// -1, 0 or 1 according to the sign of the number
+    pub fn signum(&self) -> i64 {
+        self.mantissa.signum() as i64
+    }
+
+    pub fn abs(self) -> Decimal {
+        Decimal { mantissa: self.mantissa.abs(), scale: self.scale }
+    }
+
+    /// The nearest floating point number
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().expect("a formatted decimal is a valid float")
+    }
+
+    /// The same value without trailing fractional zeros
+    pub fn normalize(self) -> Decimal {
+        let mut result = self;
+        while result.scale > 0 && result.mantissa % 10 == 0 {
+            result.mantissa /= 10;
+            result.scale -= 1;
+        }
+        result
+    }
+
+    pub fn checked_add(self, other: Decimal) -> Option<Decimal> {
+        // Aligned to a common scale, the mantissas can have up to 76 digits
+        let scale = self.scale.max(other.scale);
+        let (a, b) = (self.aligned(scale), other.aligned(scale));
+        let (a_negative, b_negative) = (self.mantissa < 0, other.mantissa < 0);
+        if a_negative == b_negative {
+            Self::from_wide(a_negative, a.add(b), scale)
+        } else if a >= b {
+            Self::from_wide(a_negative, a.sub(b), scale)
+        } else {
+            Self::from_wide(b_negative, b.sub(a), scale)
+        }
+    }
+
+    pub fn checked_sub(self, other: Decimal) -> Option<Decimal> {
+        self.checked_add(-other)
+    }
+
+    pub fn checked_mul(self, other: Decimal) -> Option<Decimal> {
+        // The exact product can have up to 76 digits, most of them fractional
+        let product = Wide::product(self.mantissa.unsigned_abs(), other.mantissa.unsigned_abs());
+        Self::from_wide((self.mantissa < 0) != (other.mantissa < 0), product, self.scale + other.scale)
+    }
+
+    /// Divide, giving up to [`DIVISION_SCALE`](Self::DIVISION_SCALE) fractional
+    /// digits (fewer if the quotient would otherwise need more than 38 digits).
+    /// Returns `None` when dividing by zero or when the quotient is out of range.
+    pub fn checked_div(self, other: Decimal) -> Option<Decimal> {
+        if other.is_zero() {
+            return None;
+        }
+        let scale = self.scale.max(other.scale);
+        let divisor = other.aligned(scale);
+        let negative = (self.mantissa < 0) != (other.mantissa < 0);
+
+        // Long division, one fractional digit at a time
+        let (mut quotient, mut remainder) = self.aligned(scale).div_rem(divisor);
+        let mut digits = 0;
+        let short = |q: &Wide| q.to_u128().is_some_and(|q| q < MAX_MANTISSA as u128 / 10);
+        while !remainder.is_zero() && digits < Self::DIVISION_SCALE && short(&quotient) {
+            let (digit, rest) = remainder.mul_10().div_rem(divisor);
+            quotient = quotient.mul_10().add(digit);
+            remainder = rest;
+            digits += 1;
+        }
+        if !remainder.is_zero() && remainder >= divisor.sub(remainder) {
+            quotient = quotient.add(Wide::from(1));
+        }
+        Self::from_wide(negative, quotient, digits)
+    }
+
+    /// Remainder of truncating division; the result has the sign of `self`.
+    /// Returns `None` when dividing by zero.
+    pub fn checked_rem(self, other: Decimal) -> Option<Decimal> {
+        if other.is_zero() {
+            return None;
+        }
+        let scale = self.scale.max(other.scale);
+        let (_, remainder) = self.aligned(scale).div_rem(other.aligned(scale));
+        Self::from_wide(self.mantissa < 0, remainder, scale)
+    }
+
+    /// Round half away from zero to `digits` fractional digits.  A negative
+    /// `digits` rounds to a multiple of a power of ten.  Returns `None` if the
+    /// rounded value is out of range.
+    pub fn round_dp(self, digits: i64) -> Option<Decimal> {
+        if digits >= self.scale as i64 {
+            return Some(self);
+        }
+        let dropped = self.scale as i64 - digits;
+        let rounded = if dropped > Self::MAX_PRECISION as i64 {
+            0
+        } else {
+            div_round(self.mantissa, pow10(dropped as u32))
+        };
+        if digits >= 0 {
+            Self::new(rounded, digits as u32)
+        } else if rounded == 0 {
+            Some(Self::ZERO)
+        } else if -digits > Self::MAX_PRECISION as i64 {
+            None
+        } else {
+            Self::new(rounded.checked_mul(pow10(-digits as u32))?, 0)
+        }
+    }
+
+    /// Largest integer not greater than the number
+    pub fn floor(self) -> Decimal {
+        let (whole, fraction) = self.split();
+        let mantissa = if fraction < 0 { whole - 1 } else { whole };
+        Decimal { mantissa, scale: 0 }
+    }
+
+    /// Smallest integer not less than the number
+    pub fn ceil(self) -> Decimal {
+        let (whole, fraction) = self.split();
+        let mantissa = if fraction > 0 { whole + 1 } else { whole };
+        Decimal { mantissa, scale: 0 }
+    }
+
+    /// Build a result from its sign and the magnitude of its mantissa, dropping
+    /// fractional digits until it fits
+    fn from_wide(negative: bool, mut magnitude: Wide, mut scale: u32) -> Option<Decimal> {
+        let fits = |m: &Wide| m.to_u128().is_some_and(|m| m <= MAX_MANTISSA as u128);
+        let mut dropped = 0;
+        while scale > 0 && (scale > Self::MAX_PRECISION || !fits(&magnitude)) {
+            dropped = magnitude.div_rem_10();
+            scale -= 1;
+        }
+        // Rounding half away from zero only depends on the first digit dropped
+        let mut mantissa = magnitude.to_u128()?;
+        if dropped >= 5 {
+            mantissa += 1;
+            // 38 nines round up to 10^38, which loses one more (zero) digit
+            if mantissa > MAX_MANTISSA as u128 && scale > 0 {
+                mantissa /= 10;
+                scale -= 1;
+            }
+        }
+        let mantissa = i128::try_from(mantissa).ok()?;
+        Self::new(if negative { -mantissa } else { mantissa }, scale)
+    }
+
+    /// Magnitude of the mantissa for a scale at least as large as the current one
+    fn aligned(&self, scale: u32) -> Wide {
+        Wide::product(self.mantissa.unsigned_abs(), pow10(scale - self.scale) as u128)
+    }
+
+    /// Integer part and the remaining fractional digits, both truncated toward zero
+    fn split(&self) -> (i128, i128) {
+        let unit = pow10(self.scale);
+        (self.mantissa / unit, self.mantissa % unit)
+    }
+}
+
+/// An unsigned 256-bit integer, wide enough for the product of two mantissas,
+/// stored as 64-bit limbs from least to most significant.  Values built from
+/// decimals stay below 10^77, so the arithmetic below cannot overflow.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Wide([u64; 4]);
+
+impl Wide {
+    fn product(a: u128, b: u128) -> Wide {
+        let a = [a as u64, (a >> 64) as u64];
+        let b = [b as u64, (b >> 64) as u64];
+        let mut limbs = [0u64; 4];
+        for i in 0..2 {
+            let mut carry = 0u128;
+            for j in 0..2 {
+                let t = a[i] as u128 * b[j] as u128 + limbs[i + j] as u128 + carry;
+                limbs[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            limbs[i + 2] = carry as u64;
+        }
+        Wide(limbs)
+    }
+
+    fn add(self, other: Wide) -> Wide {
+        let mut limbs = [0u64; 4];
+        let mut carry = false;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (sum, overflow1) = self.0[i].overflowing_add(other.0[i]);
+            let (sum, overflow2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = overflow1 || overflow2;
+        }
+        Wide(limbs)
+    }
+
+    /// Subtract a value no larger than `self`
+    fn sub(self, other: Wide) -> Wide {
+        let mut limbs = [0u64; 4];
+        let mut borrow = false;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (difference, overflow1) = self.0[i].overflowing_sub(other.0[i]);
+            let (difference, overflow2) = difference.overflowing_sub(borrow as u64);
+            *limb = difference;
+            borrow = overflow1 || overflow2;
+        }
+        Wide(limbs)
+    }
+
+    fn mul_10(self) -> Wide {
+        let mut limbs = [0u64; 4];
+        let mut carry = 0u128;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let t = self.0[i] as u128 * 10 + carry;
+            *limb = t as u64;
+            carry = t >> 64;
+        }
+        Wide(limbs)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    /// Quotient and remainder of dividing by a non-zero `divisor`, computed one
+    /// bit at a time
+    fn div_rem(self, divisor: Wide) -> (Wide, Wide) {
+        let mut quotient = [0u64; 4];
+        let mut remainder = Wide([0; 4]);
+        for bit in (0..256).rev() {
+            remainder = remainder.add(remainder);
+            remainder.0[0] |= (self.0[bit / 64] >> (bit % 64)) & 1;
+            if remainder >= divisor {
+                remainder = remainder.sub(divisor);
+                quotient[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        (Wide(quotient), remainder)
+    }
+
+    /// Divide by ten in place, returning the remainder
+    fn div_rem_10(&mut self) -> u8 {
+        let mut remainder = 0u128;
+        for limb in self.0.iter_mut().rev() {
+            let current = (remainder << 64) | *limb as u128;
+            *limb = (current / 10) as u64;
+            remainder = current % 10;
+        }
+        remainder as u8
+    }
+
+    fn to_u128(self) -> Option<u128> {
+        let [low, high, 0, 0] = self.0 else { return None };
+        Some((high as u128) << 64 | low as u128)
+    }
+}
+
+impl PartialOrd for Wide {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Wide {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl From<u128> for Wide {
+    fn from(value: u128) -> Self {
+        Wide([value as u64, (value >> 64) as u64, 0, 0])
+    }
+}
+
+fn pow10(exponent: u32) -> i128 {
+    10i128.pow(exponent)
+}
+
+/// Divide rounding half away from zero
+fn div_round(value: i128, divisor: i128) -> i128 {
+    let quotient = value / divisor;
+    let remainder = (value % divisor).abs();
+    if remainder >= divisor - remainder {
+        quotient + value.signum()
+    } else {
+        quotient
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(value: i64) -> Self {
+        Decimal { mantissa: value as i128, scale: 0 }
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        Decimal { mantissa: -self.mantissa, scale: self.scale }
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Compare integer parts first so that aligning the fractions cannot overflow
+        let scale = self.scale.max(other.scale);
+        let (self_whole, self_fraction) = self.split();
+        let (other_whole, other_fraction) = other.split();
+        self_whole.cmp(&other_whole).then_with(|| {
+            let self_fraction = self_fraction * pow10(scale - self.scale);
+            let other_fraction = other_fraction * pow10(scale - other.scale);
+            self_fraction.cmp(&other_fraction)
+        })
+    }
+}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let Decimal { mantissa, scale } = self.normalize();
+        mantissa.hash(state);
+        scale.hash(state);
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, whole, fraction)
+    }
+}
+
+impl fmt::Debug for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = String;
+
+    /// Parse an optionally signed number with an optional fractional part, such
+    /// as `-12.50` or `.5`.  Exponents are not accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+            return Err(format!("Invalid decimal '{}'", s));
+        }
+
+        let out_of_range = || format!("Decimal '{}' has more than {} digits", s, Self::MAX_PRECISION);
+        let scale = u32::try_from(fraction.len()).map_err(|_| out_of_range())?;
+        let mut mantissa: i128 = 0;
+        for digit in whole.bytes().chain(fraction.bytes()) {
+            mantissa = mantissa.checked_mul(10)
+                .and_then(|m| m.checked_add((digit - b'0') as i128))
+                .filter(|m| *m <= MAX_MANTISSA)
+                .ok_or_else(out_of_range)?;
+        }
+        Decimal::new(if negative { -mantissa } else { mantissa }, scale).ok_or_else(out_of_range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(dec("12.50").to_string(), "12.50");
+        assert_eq!(dec("-0.05").to_string(), "-0.05");
+        assert_eq!(dec(".5").to_string(), "0.5");
+        assert_eq!(dec("7").to_string(), "7");
+        assert!("1.2.3".parse::<Decimal>().is_err());
+        assert!("1e5".parse::<Decimal>().is_err());
+        assert!("123456789012345678901234567890123456789".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_value_equality_ignores_scale() {
+        assert_eq!(dec("1.5"), dec("1.500"));
+        assert!(dec("-1.5") < dec("-1.2"));
+        assert!(dec("0.3") > dec("-0.5"));
+        assert!(dec("99999999999999999999999999999999999999") > dec("0.00000000000000000000000000000000000001"));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(dec("0.1").checked_add(dec("0.2")), Some(dec("0.3")));
+        assert_eq!(dec("1.10").checked_mul(dec("3")).unwrap().to_string(), "3.30");
+        assert_eq!(dec("10").checked_div(dec("4")).unwrap().to_string(), "2.5");
+        assert_eq!(dec("1").checked_div(dec("3")).unwrap().to_string(), "0.33333333333333333333");
+        assert_eq!(dec("2").checked_div(dec("3")).unwrap().to_string(), "0.66666666666666666667");
+        assert_eq!(dec("-7.5").checked_rem(dec("2")), Some(dec("-1.5")));
+        assert_eq!(dec("1").checked_div(Decimal::ZERO), None);
+        assert_eq!(dec("99999999999999999999999999999999999999").checked_add(dec("1")), None);
+    }
+
+    #[test]
+    fn test_multiplication_rounds_wide_products() {
+        // The raw mantissas multiply to 10^42, beyond i128, but the value is 1
+        let product = dec("1.0000000000000000000000").checked_mul(dec("1.00000000000000000000")).unwrap();
+        assert_eq!(product, dec("1"));
+        assert_eq!(product.scale(), 37);
+        let third = dec("1").checked_div(dec("3")).unwrap();
+        assert_eq!(third.checked_mul(third).unwrap().to_string(), "0.11111111111111111110888888888888888889");
+        assert_eq!(dec("0.00000000000000000000000000000000000005").checked_mul(dec("0.1")), Some(dec("0.00000000000000000000000000000000000001")));
+        assert_eq!(dec("-0.00000000000000000000000000000000000004").checked_mul(dec("0.1")), Some(Decimal::ZERO));
+        assert_eq!(dec("99999999999999999999999999999999999999").checked_mul(dec("0.99999999999999999999999999999999999999")).unwrap().to_string(), "99999999999999999999999999999999999998");
+        assert_eq!(dec("99999999999999999999999999999999999999").checked_mul(dec("1.1")), None);
+        assert_eq!(dec("10000000000000000000").checked_mul(dec("10000000000000000000")), None);
+    }
+
+    #[test]
+    fn test_large_integer_part_with_large_scale() {
+        // Aligned to 20 fractional digits the mantissas have 41 digits, but the
+        // results round to 38
+        let big = dec("12345678901234567890.5");
+        let tiny = dec("0.00000000000000000001");
+        let sum = big.checked_add(tiny).unwrap();
+        assert_eq!(sum, big);
+        assert_eq!(sum.scale(), 18);
+        assert_eq!(big.checked_sub(tiny).unwrap().to_string(), "12345678901234567890.500000000000000000");
+        assert_eq!(tiny.checked_sub(big).unwrap().to_string(), "-12345678901234567890.500000000000000000");
+        assert_eq!(big.checked_add(dec("-0.0000000000000000006")).unwrap().to_string(), "12345678901234567890.499999999999999999");
+
+        assert_eq!(big.checked_div(dec("1.00000000000000000000")), Some(big));
+        assert_eq!(big.checked_div(dec("3.00000000000000000000")).unwrap().to_string(), "4115226300411522630.1666666666666666667");
+        assert_eq!(big.checked_div(dec("-0.5")).unwrap().to_string(), "-24691357802469135781");
+        assert_eq!(big.checked_div(dec("0.00000000000000000002")), None);
+
+        assert_eq!(big.checked_rem(dec("0.00000000000000000003")), Some(dec("0.00000000000000000002")));
+        assert_eq!(big.checked_rem(dec("0.00000000000000000007")), Some(dec("0.00000000000000000003")));
+        assert_eq!((-big).checked_rem(dec("0.3")), Some(dec("-0.2")));
+        assert_eq!(tiny.checked_rem(big), Some(tiny));
+    }
+
+    #[test]
+    fn test_rounding() {
+        assert_eq!(dec("2.345").round_dp(2).unwrap().to_string(), "2.35");
+        assert_eq!(dec("-2.345").round_dp(2).unwrap().to_string(), "-2.35");
+        assert_eq!(dec("1250").round_dp(-2).unwrap().to_string(), "1300");
+        assert_eq!(dec("-2.5").floor().to_string(), "-3");
+        assert_eq!(dec("-2.5").ceil().to_string(), "-2");
+    }
+}
@@ -6,22 +6,44 @@
 //! value substitution to ensure type safety during evaluation.
 
 use crate::ast::*;
-use crate::parser::{parse, ParseError};
-
-use std::collections::HashMap;
+use crate::collation::Collation;
+use crate::decimal::Decimal;
+use crate::numeric;
+use crate::functions::{lookup_builtin, FunctionRegistry};
+use crate::parser::{parse, parse_with_functions, parse_with_options, ParseError, ParserOptions};
+use crate::pattern::{LikePattern, RegexPattern, RegexSyntax};
+use crate::span::Span;
+use crate::temporal::{Date, Interval, Timestamp};
+
+use std::cell::Cell;
+use std::cmp;
+
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::hash::BuildHasher;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 // ============================================================================
 // PUBLIC API
 // ============================================================================
 
-/// User-provided values for variable substitution
+/// User-provided values for variable substitution.
+///
+/// Lists and maps hold nested data, reached through variable paths such as
+/// `order.customer.tier`, `items[0].sku` or `headers['x-id']`.
 #[derive(Debug, Clone, PartialEq)]
 pub enum RuntimeValue {
     Integer(i64),
     Float(f64),
+    Decimal(Decimal),
+    Date(Date),
+    Timestamp(Timestamp),
+    Interval(Interval),
     String(String),
     Boolean(bool),
+    List(Vec<RuntimeValue>),
+    Map(BTreeMap<String, RuntimeValue>),
     Null,
 }
 
@@ -37,6 +59,11 @@ pub enum EvalError {
         name: String
     },
 
+    /// Parameter placeholder evaluated without a value being bound to it
+    UnboundParameter {
+        parameter: Parameter,
+    },
+
     /// Type mismatch in operation
     TypeError {
         operation: String,
@@ -62,6 +89,38 @@ pub enum EvalError {
         literal_type: String,
         error: String,
     },
+
+    /// Function called that is neither built in nor registered in the evaluation options
+    UnknownFunction {
+        name: String,
+    },
+
+    /// User-defined function failed or returned a value of the wrong type
+    FunctionError {
+        name: String,
+        message: String,
+    },
+
+    /// Expression nested too deeply to evaluate without risking a stack overflow
+    NestingTooDeep {
+        depth: usize,
+        limit: usize,
+    },
+
+    /// Evaluation took more steps than the budget in the evaluation options
+    BudgetExceeded {
+        limit: u64,
+    },
+
+    /// Evaluation stopped because its cancellation flag was set
+    Cancelled,
+
+    /// Integer result out of range under [`OverflowPolicy::Error`], or decimal
+    /// result out of range under any policy
+    Overflow {
+        operation: String,
+        expression: String,
+    },
 }
 
 impl fmt::Display for EvalError {
@@ -71,6 +130,12 @@ impl fmt::Display for EvalError {
             EvalError::UnboundVariable { name } => {
                 write!(f, "Unbound variable '{}' - not found in value map", name)
             }
+            EvalError::UnboundParameter { parameter: Parameter::Positional(n) } => {
+                write!(f, "Unbound parameter {} - no value bound to positional parameter", n)
+            }
+            EvalError::UnboundParameter { parameter: Parameter::Named(name) } => {
+                write!(f, "Unbound parameter :{} - no value bound to named parameter", name)
+            }
             EvalError::TypeError { operation, expected, actual, context } => {
                 write!(f, "Type error in {}: expected {}, got {} (context: {})",
                     operation, expected, actual, context)
@@ -85,6 +150,23 @@ impl fmt::Display for EvalError {
             EvalError::InvalidLiteral { literal, literal_type, error } => {
                 write!(f, "Invalid {} literal '{}': {}", literal_type, literal, error)
             }
+            EvalError::UnknownFunction { name } => {
+                write!(f, "Unknown function '{}' - not built in or registered", name)
+            }
+            EvalError::FunctionError { name, message } => {
+                write!(f, "Function {} failed: {}", name, message)
+            }
+            EvalError::NestingTooDeep { depth, limit } => {
+                write!(f, "Expression is nested {} levels deep, more than the {} that can be evaluated",
+                    depth, limit)
+            }
+            EvalError::BudgetExceeded { limit } => {
+                write!(f, "Evaluation exceeded its budget of {} steps", limit)
+            }
+            EvalError::Cancelled => write!(f, "Evaluation cancelled"),
+            EvalError::Overflow { operation, expression } => {
+                write!(f, "Overflow in {}: {}", operation, expression)
+            }
         }
     }
 }
@@ -110,8 +192,341 @@ impl From<ParseError> for EvalError {
 }
 
 
+/// Source of variable values used during evaluation.
+///
+/// The evaluator asks the provider for a variable's value only when the variable
+/// is actually reached, so implementations can bind directly onto application data
+/// and produce values lazily instead of copying every field into a map up front.
+/// Returning `None` reports the variable as unbound.
+///
+/// Implementations are provided for `HashMap` and `BTreeMap` keyed by `String`, and
+/// for closures of the form `Fn(&str) -> Option<RuntimeValue>`.
+///
+/// # Examples
+/// ```
+/// use sqlexpr_rust::{CompiledExpr, RuntimeValue, ValueProvider};
+///
+/// struct Message { priority: i64, topic: String }
+///
+/// impl ValueProvider for Message {
+///     fn resolve(&self, name: &str) -> Option<RuntimeValue> {
+///         match name {
+///             "priority" => Some(RuntimeValue::Integer(self.priority)),
+///             "topic" => Some(RuntimeValue::String(self.topic.clone())),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// let filter = CompiledExpr::compile("priority > 5 AND topic LIKE 'orders.%'").unwrap();
+/// let msg = Message { priority: 7, topic: "orders.created".to_string() };
+/// assert_eq!(filter.eval(&msg).unwrap(), true);
+/// ```
+pub trait ValueProvider {
+    /// Look up the value bound to `name`, or `None` if the variable is unbound.
+    fn resolve(&self, name: &str) -> Option<RuntimeValue>;
+}
+
+impl<S: BuildHasher> ValueProvider for HashMap<String, RuntimeValue, S> {
+    fn resolve(&self, name: &str) -> Option<RuntimeValue> {
+        self.get(name).cloned()
+    }
+}
+
+impl ValueProvider for BTreeMap<String, RuntimeValue> {
+    fn resolve(&self, name: &str) -> Option<RuntimeValue> {
+        self.get(name).cloned()
+    }
+}
+
+impl<F> ValueProvider for F
+where
+    F: Fn(&str) -> Option<RuntimeValue>,
+{
+    fn resolve(&self, name: &str) -> Option<RuntimeValue> {
+        self(name)
+    }
+}
+
+/// Values for the parameter placeholders of an expression, supplied separately
+/// from the variable bindings.
+///
+/// Positional values are taken in order: the first value is bound to the first `?`
+/// in the expression, the second to the second, and so on.  Named values are bound
+/// to `:name` placeholders by name.
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use sqlexpr_rust::{CompiledExpr, Parameters, RuntimeValue};
+///
+/// let template = CompiledExpr::compile("tenant = :tenant AND size > ?").unwrap();
+/// let params = Parameters::new()
+///     .set("tenant", RuntimeValue::String("O'Brien & Co".to_string()))
+///     .push(RuntimeValue::Integer(100));
+/// let filter = template.bind(&params).unwrap();
+///
+/// let mut row = HashMap::new();
+/// row.insert("tenant".to_string(), RuntimeValue::String("O'Brien & Co".to_string()));
+/// row.insert("size".to_string(), RuntimeValue::Integer(250));
+/// assert_eq!(filter.eval(&row).unwrap(), true);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Parameters {
+    positional: Vec<RuntimeValue>,
+    named: HashMap<String, RuntimeValue>,
+}
+
+impl Parameters {
+    /// An empty set of parameter values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the value of the next positional (`?`) parameter.
+    pub fn push(mut self, value: RuntimeValue) -> Self {
+        self.positional.push(value);
+        self
+    }
+
+    /// Set the value of the named parameter `:name`.
+    pub fn set(mut self, name: &str, value: RuntimeValue) -> Self {
+        self.named.insert(name.to_string(), value);
+        self
+    }
+
+    /// The value bound to `parameter`, if any.
+    pub fn get(&self, parameter: &Parameter) -> Option<&RuntimeValue> {
+        match parameter {
+            Parameter::Positional(n) => n.checked_sub(1).and_then(|i| self.positional.get(i)),
+            Parameter::Named(name) => self.named.get(name),
+        }
+    }
+}
+
+/// How NULL is treated when it reaches an operator other than IS NULL / IS NOT NULL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullSemantics {
+    /// NULL operands raise [`EvalError::NullInOperation`] (the default).
+    #[default]
+    Strict,
+
+    /// SQL three-valued logic: NULL propagates through arithmetic, predicates on
+    /// NULL yield UNKNOWN, and AND/OR/NOT follow the Kleene truth tables.
+    ThreeValued,
+}
+
+/// What happens when an integer operation's result does not fit in an `i64`.
+///
+/// Every policy behaves the same in debug and release builds.  The policy does not
+/// apply to decimals: a decimal result that needs more than 38 digits before the
+/// decimal point is always an [`EvalError::Overflow`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Fail with [`EvalError::Overflow`] (the default).
+    #[default]
+    Error,
+
+    /// Compute the result as a float instead.
+    PromoteToFloat,
+
+    /// Clamp the result to `i64::MIN` or `i64::MAX`.
+    Saturate,
+}
+
+impl OverflowPolicy {
+    /// Pick the result of an integer operation: `checked` if it did not overflow,
+    /// otherwise `saturated`, `promoted` or the error, according to the policy
+    pub(crate) fn resolve(
+        self,
+        checked: Option<i64>,
+        saturated: i64,
+        promoted: f64,
+        error: impl FnOnce() -> EvalError,
+    ) -> Result<RuntimeValue, EvalError> {
+        match (checked, self) {
+            (Some(i), _) => Ok(RuntimeValue::Integer(i)),
+            (None, OverflowPolicy::Error) => Err(error()),
+            (None, OverflowPolicy::PromoteToFloat) => Ok(RuntimeValue::Float(promoted)),
+            (None, OverflowPolicy::Saturate) => Ok(RuntimeValue::Integer(saturated)),
+        }
+    }
+}
+
+/// Options controlling how an expression is evaluated.
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use sqlexpr_rust::{CompiledExpr, EvalOptions, NullSemantics, RuntimeValue, Truth};
+///
+/// let options = EvalOptions::new().null_semantics(NullSemantics::ThreeValued);
+/// let expr = CompiledExpr::compile("discount > 10").unwrap();
+///
+/// let mut map = HashMap::new();
+/// map.insert("discount".to_string(), RuntimeValue::Null);
+/// assert_eq!(expr.eval_truth(&map, &options).unwrap(), Truth::Unknown);
+/// assert_eq!(expr.eval_with(&map, &options).unwrap(), false);
+/// ```
+///
+/// A step budget bounds the work a single evaluation may do:
+/// ```
+/// use std::collections::HashMap;
+/// use sqlexpr_rust::{CompiledExpr, EvalError, EvalOptions};
+///
+/// let expr = CompiledExpr::compile("1 + 2 + 3 + 4 > 5").unwrap();
+/// let options = EvalOptions::new().max_steps(5);
+/// let err = expr.eval_with(&HashMap::new(), &options).unwrap_err();
+/// assert_eq!(err, EvalError::BudgetExceeded { limit: 5 });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EvalOptions {
+    null_semantics: NullSemantics,
+    collation: Collation,
+    overflow: OverflowPolicy,
+    functions: FunctionRegistry,
+    max_steps: Option<u64>,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl EvalOptions {
+    /// Default options: strict NULL handling and built-in functions only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Select how NULL operands are handled.
+    pub fn null_semantics(mut self, null_semantics: NullSemantics) -> Self {
+        self.null_semantics = null_semantics;
+        self
+    }
+
+    /// Select how strings are compared by predicates that do not end in a
+    /// `COLLATE` clause.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use sqlexpr_rust::{Collation, CompiledExpr, EvalOptions, RuntimeValue};
+    ///
+    /// let expr = CompiledExpr::compile("country = 'germany'").unwrap();
+    /// let mut map = HashMap::new();
+    /// map.insert("country".to_string(), RuntimeValue::String("Germany".to_string()));
+    /// assert!(!expr.eval_with(&map, &EvalOptions::new()).unwrap());
+    /// let options = EvalOptions::new().collation(Collation::NoCase);
+    /// assert!(expr.eval_with(&map, &options).unwrap());
+    /// ```
+    pub fn collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
+    /// Select what happens when integer arithmetic overflows.
+    ///
+    /// The policy applies to `+`, `-`, `*` and unary minus on integers and to the
+    /// integer results of ABS and ROUND.
+    pub fn overflow_policy(mut self, overflow: OverflowPolicy) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Make user-defined functions available to the expression.
+    pub fn functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = functions;
+        self
+    }
+
+    /// Fail with [`EvalError::BudgetExceeded`] after `steps` evaluation steps.
+    ///
+    /// Evaluating any node of the AST takes a step, and so does each LIKE match.
+    /// Short-circuited operands are not evaluated and take no steps.  Partial
+    /// evaluation counts the steps of folding and of evaluating the residual
+    /// against the same budget.
+    pub fn max_steps(mut self, steps: u64) -> Self {
+        self.max_steps = Some(steps);
+        self
+    }
+
+    /// Fail with [`EvalError::Cancelled`] once `flag` is set, e.g. by another
+    /// thread giving up on the evaluation.
+    ///
+    /// The flag is checked at the first step and every
+    /// [`EvalOptions::CANCEL_CHECK_INTERVAL`] steps after that.
+    pub fn cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(flag);
+        self
+    }
+
+    /// Number of steps between checks of the cancellation flag
+    pub const CANCEL_CHECK_INTERVAL: u64 = 64;
+}
+
+/// Three-valued truth value produced by SQL boolean logic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Truth {
+    True,
+    False,
+    Unknown,
+}
+
+impl Truth {
+    /// Kleene conjunction: FALSE dominates, otherwise UNKNOWN dominates.
+    pub fn and(self, other: Truth) -> Truth {
+        match (self, other) {
+            (Truth::False, _) | (_, Truth::False) => Truth::False,
+            (Truth::True, Truth::True) => Truth::True,
+            _ => Truth::Unknown,
+        }
+    }
+
+    /// Kleene disjunction: TRUE dominates, otherwise UNKNOWN dominates.
+    pub fn or(self, other: Truth) -> Truth {
+        match (self, other) {
+            (Truth::True, _) | (_, Truth::True) => Truth::True,
+            (Truth::False, Truth::False) => Truth::False,
+            _ => Truth::Unknown,
+        }
+    }
+
+    /// Collapse to a boolean the way a SQL WHERE clause does: only TRUE passes.
+    pub fn is_true(self) -> bool {
+        self == Truth::True
+    }
+}
+
+impl std::ops::Not for Truth {
+    type Output = Truth;
+
+    /// Kleene negation: UNKNOWN stays UNKNOWN.
+    fn not(self) -> Truth {
+        match self {
+            Truth::True => Truth::False,
+            Truth::False => Truth::True,
+            Truth::Unknown => Truth::Unknown,
+        }
+    }
+}
+
+impl From<bool> for Truth {
+    fn from(b: bool) -> Self {
+        if b { Truth::True } else { Truth::False }
+    }
+}
+
+impl fmt::Display for Truth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Truth::True => write!(f, "TRUE"),
+            Truth::False => write!(f, "FALSE"),
+            Truth::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
 /// Public evaluation function that calculates the value of an AST given a mpa variable bindings.
 ///
+/// The input is parsed on every call.  Use [`CompiledExpr`] when the same expression
+/// is evaluated repeatedly against different bindings.
+///
 /// # Arguments
 /// * `input` - SQL expression string to evaluate (must be boolean-valued)
 /// * `map` - Variable name to value bindings for substitution
@@ -132,76 +547,603 @@ impl From<ParseError> for EvalError {
 /// assert_eq!(result, true);
 /// ```
 pub fn evaluate(input: &str, map: &HashMap<String, RuntimeValue>) -> Result<bool, EvalError> {
-    let evaluator = Evaluator::new(input, map)?;
-    evaluator.eval_boolean(&evaluator.ast)
-
+    let compiled = CompiledExpr::compile(input)?;
+    compiled.eval(map)
 }
 
 // ============================================================================
-// EVALUATOR
+// COMPILED EXPRESSIONS
 // ============================================================================
 
-/// Private evaluator implementation
-struct Evaluator<'a> {
+/// A parsed expression that can be evaluated any number of times without re-parsing.
+///
+/// A `CompiledExpr` owns its AST and the source text it was built from.  It is
+/// immutable once built, so it is `Send + Sync` and can be stored in long-lived
+/// structures or shared across threads (e.g. behind an `Arc`).
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use sqlexpr_rust::{CompiledExpr, RuntimeValue};
+///
+/// let filter = CompiledExpr::compile("priority > 5 AND region = 'eu'").unwrap();
+///
+/// let mut msg = HashMap::new();
+/// msg.insert("priority".to_string(), RuntimeValue::Integer(7));
+/// msg.insert("region".to_string(), RuntimeValue::String("eu".to_string()));
+/// assert_eq!(filter.eval(&msg).unwrap(), true);
+///
+/// msg.insert("priority".to_string(), RuntimeValue::Integer(3));
+/// assert_eq!(filter.eval(&msg).unwrap(), false);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledExpr {
     input: String,
     ast: BooleanExpr,
-    value_map: &'a HashMap<String, RuntimeValue>,
+    /// Depth of `ast`, checked against `MAX_EVAL_DEPTH` before walking it
+    depth: usize,
 }
 
-impl<'a> Evaluator<'a> {
-    /// Create new evaluator by parsing input
-    fn new(input: &str, value_map: &'a HashMap<String, RuntimeValue>) -> Result<Self, EvalError> {
+/// Deepest expression that can be evaluated, partially evaluated or bound.
+///
+/// Evaluation recurses once per level of the AST, and walks chains such as
+/// `a OR b OR c` with a loop.  In an unoptimized build the most expensive walk,
+/// binding nested CASE conditions, takes about 13 KiB of stack per level, or
+/// about 1.3 MiB at this depth, which fits in the 2 MiB that Rust gives spawned
+/// threads; optimized builds need far less.  Deeper expressions, which the parser only accepts if its
+/// [`ParserOptions::max_depth`] is raised above this limit, fail to evaluate with
+/// [`EvalError::NestingTooDeep`] instead.
+pub const MAX_EVAL_DEPTH: usize = 100;
+
+impl CompiledExpr {
+    /// Parse the input once and keep the resulting AST for later evaluation.
+    pub fn compile(input: &str) -> Result<Self, ParseError> {
         let ast = parse(input)?;
-        Ok(Evaluator {
+        Ok(CompiledExpr {
             input: input.to_string(),
+            depth: ast.depth(),
             ast,
+        })
+    }
+
+    /// Parse an input that may call the user-defined functions in `functions`,
+    /// checking each call's arity.  Evaluate it with options carrying the same
+    /// registry (see [`EvalOptions::functions`]).
+    pub fn compile_with_functions(input: &str, functions: &FunctionRegistry) -> Result<Self, ParseError> {
+        let ast = parse_with_functions(input, functions)?;
+        Ok(CompiledExpr {
+            input: input.to_string(),
+            depth: ast.depth(),
+            ast,
+        })
+    }
+
+    /// Parse an input accepting the features allowed by `options`.
+    pub fn compile_with_options(input: &str, options: &ParserOptions) -> Result<Self, ParseError> {
+        let ast = parse_with_options(input, options)?;
+        Ok(CompiledExpr {
+            input: input.to_string(),
+            depth: ast.depth(),
+            ast,
+        })
+    }
+
+    /// The AST this expression evaluates.
+    pub fn ast(&self) -> &BooleanExpr {
+        &self.ast
+    }
+
+    /// The source text of this expression.  For expressions built directly
+    /// from an AST this is the AST's `Display` rendering.
+    pub fn source(&self) -> &str {
+        &self.input
+    }
+
+    /// Evaluate the expression against a set of variable bindings.
+    ///
+    /// The bindings can be any [`ValueProvider`], such as a `HashMap`, a `BTreeMap`,
+    /// a closure or an application type that resolves variables itself.
+    pub fn eval<P: ValueProvider + ?Sized>(&self, bindings: &P) -> Result<bool, EvalError> {
+        self.eval_with(bindings, &EvalOptions::default())
+    }
+
+    /// Evaluate the expression with explicit evaluation options.
+    ///
+    /// Under three-valued NULL semantics an UNKNOWN result is reported as `false`,
+    /// matching how a SQL WHERE clause or message selector filters rows.
+    pub fn eval_with<P: ValueProvider + ?Sized>(&self, bindings: &P, options: &EvalOptions)
+        -> Result<bool, EvalError>
+    {
+        Ok(self.eval_truth(bindings, options)?.is_true())
+    }
+
+    /// Evaluate the expression to a three-valued [`Truth`] without collapsing UNKNOWN.
+    ///
+    /// Under strict NULL semantics the result is never `Truth::Unknown`.
+    pub fn eval_truth<P: ValueProvider + ?Sized>(&self, bindings: &P, options: &EvalOptions)
+        -> Result<Truth, EvalError>
+    {
+        self.check_depth()?;
+        let evaluator = Evaluator::new(&self.input, bindings, options);
+        evaluator.eval_boolean(&self.ast)
+    }
+
+    /// Evaluate the expression like [`CompiledExpr::eval_truth`], reporting where in
+    /// the source an error was raised.
+    ///
+    /// The span is that of the innermost subexpression whose evaluation failed, e.g.
+    /// the variable that is unbound or the division whose divisor is zero.  For an
+    /// expression built from an AST, spans refer to the text the AST was parsed from.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::collections::HashMap;
+    /// use sqlexpr_rust::{CompiledExpr, EvalError, EvalOptions, RuntimeValue};
+    ///
+    /// let input = "total > 10 AND ratio / count > 0.5";
+    /// let expr = CompiledExpr::compile(input).unwrap();
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert("total".to_string(), RuntimeValue::Integer(20));
+    /// map.insert("ratio".to_string(), RuntimeValue::Float(3.0));
+    ///
+    /// let err = expr.eval_spanned(&map, &EvalOptions::default()).unwrap_err();
+    /// assert!(matches!(err.error, EvalError::UnboundVariable { .. }));
+    /// assert_eq!(&input[err.span.start..err.span.end], "count");
+    /// ```
+    #[allow(clippy::result_large_err)]
+    pub fn eval_spanned<P: ValueProvider + ?Sized>(&self, bindings: &P, options: &EvalOptions)
+        -> Result<Truth, SpannedEvalError>
+    {
+        self.check_depth().map_err(|error| SpannedEvalError { error, span: self.ast.span() })?;
+        let evaluator = Evaluator::new(&self.input, bindings, options);
+        evaluator.eval_boolean(&self.ast).map_err(|error| SpannedEvalError {
+            error,
+            span: evaluator.error_span.get().unwrap_or_else(|| self.ast.span()),
+        })
+    }
+
+    /// Partially evaluate the expression when only some variables are known.
+    ///
+    /// Variables the bindings cannot resolve are treated as not yet known.  Every
+    /// subexpression whose variables are all bound is folded to a literal, and
+    /// AND/OR are short-circuited wherever one side is already decided.  The result
+    /// is either the definite outcome or a simplified residual expression over the
+    /// remaining variables, which can be printed or turned back into a `CompiledExpr`
+    /// and evaluated once the rest of the bindings are available.
+    ///
+    /// A subexpression that fails to evaluate (e.g. division by zero) is kept in the
+    /// residual with its known values substituted, so the error is only reported if
+    /// evaluating the residual actually reaches it.  Errors that do not depend on any
+    /// unbound variable are reported immediately.  Short-circuiting may discard a
+    /// residual branch that would have raised an error under full evaluation.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::collections::HashMap;
+    /// use sqlexpr_rust::{CompiledExpr, PartialResult, RuntimeValue};
+    ///
+    /// let expr = CompiledExpr::compile("tenant = 'acme' AND size > limit * 2").unwrap();
+    ///
+    /// let mut known = HashMap::new();
+    /// known.insert("tenant".to_string(), RuntimeValue::String("acme".to_string()));
+    /// known.insert("limit".to_string(), RuntimeValue::Integer(50));
+    ///
+    /// match expr.partial_eval(&known).unwrap() {
+    ///     PartialResult::Residual(residual) => assert_eq!(residual.to_string(), "size > 100"),
+    ///     PartialResult::Value(_) => unreachable!(),
+    /// }
+    ///
+    /// known.insert("tenant".to_string(), RuntimeValue::String("other".to_string()));
+    /// assert_eq!(expr.partial_eval(&known).unwrap(), PartialResult::Value(false));
+    /// ```
+    pub fn partial_eval<P: ValueProvider + ?Sized>(&self, bindings: &P) -> Result<PartialResult, EvalError> {
+        self.partial_eval_with(bindings, &EvalOptions::default())
+    }
+
+    /// Partially evaluate the expression with explicit evaluation options.
+    ///
+    /// See [`CompiledExpr::partial_eval`].  Under three-valued NULL semantics a
+    /// definite UNKNOWN outcome is reported as `PartialResult::Value(false)`.
+    pub fn partial_eval_with<P: ValueProvider + ?Sized>(&self, bindings: &P, options: &EvalOptions)
+        -> Result<PartialResult, EvalError>
+    {
+        self.check_depth()?;
+        let evaluator = Evaluator::new(&self.input, bindings, options);
+        match evaluator.fold_boolean(&self.ast)? {
+            Folded::Known(b) => Ok(PartialResult::Value(b)),
+            Folded::Residual(residual) => {
+                // A residual without unbound variables evaluates the same way now as it
+                // would later, so settle it here.  Otherwise evaluation stops at the
+                // first unbound variable, and any error reached before it does not
+                // depend on the missing bindings.
+                let no_bindings = |_: &str| None;
+                let residual_evaluator = Evaluator::new(&self.input, &no_bindings, options);
+                residual_evaluator.steps.set(evaluator.steps.get());
+                match residual_evaluator.eval_boolean(&residual) {
+                    Ok(truth) => Ok(PartialResult::Value(truth.is_true())),
+                    Err(EvalError::UnboundVariable { .. } | EvalError::UnboundParameter { .. }) => {
+                        Ok(PartialResult::Residual(residual))
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+        }
+    }
+
+    /// Substitute values for the expression's parameter placeholders.
+    ///
+    /// The result is an expression without parameters that evaluates like any
+    /// other; its source text and spans remain those of this expression.  Values
+    /// are inserted as literals, so they never need to be quoted or escaped into
+    /// the source.  Every placeholder must have a value; values that no placeholder
+    /// refers to are ignored.
+    ///
+    /// Evaluating an expression whose parameters have not been bound raises
+    /// [`EvalError::UnboundParameter`].  See [`Parameters`] for an example.
+    pub fn bind(&self, params: &Parameters) -> Result<CompiledExpr, EvalError> {
+        self.check_depth()?;
+        Ok(CompiledExpr {
+            input: self.input.clone(),
+            ast: bind_boolean(&self.ast, params)?,
+            depth: self.depth,
+        })
+    }
+
+    /// Refuse to walk an AST deep enough to overflow the stack
+    fn check_depth(&self) -> Result<(), EvalError> {
+        if self.depth > MAX_EVAL_DEPTH {
+            return Err(EvalError::NestingTooDeep { depth: self.depth, limit: MAX_EVAL_DEPTH });
+        }
+        Ok(())
+    }
+}
+
+/// An evaluation error together with the part of the source that raised it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedEvalError {
+    pub error: EvalError,
+    pub span: Span,
+}
+
+impl fmt::Display for SpannedEvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.error, self.span)
+    }
+}
+
+impl std::error::Error for SpannedEvalError {}
+
+/// Outcome of [`CompiledExpr::partial_eval`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PartialResult {
+    /// The known bindings decide the expression.
+    Value(bool),
+
+    /// Simplified expression over the variables that are not yet bound.
+    Residual(BooleanExpr),
+}
+
+impl From<BooleanExpr> for CompiledExpr {
+    fn from(ast: BooleanExpr) -> Self {
+        CompiledExpr {
+            input: ast.to_string(),
+            depth: ast.depth(),
+            ast,
+        }
+    }
+}
+
+impl std::str::FromStr for CompiledExpr {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        CompiledExpr::compile(input)
+    }
+}
+
+// ============================================================================
+// PARAMETER BINDING
+// ============================================================================
+
+fn bind_boolean(expr: &BooleanExpr, params: &Parameters) -> Result<BooleanExpr, EvalError> {
+    let bind = |e: &BooleanExpr| bind_boolean(e, params).map(Box::new);
+    Ok(match expr {
+        BooleanExpr::Or(..) | BooleanExpr::And(..) => {
+            // A chain nests as deep as it is long, so it is bound with a loop
+            let (first, links) = expr.chain();
+            let mut bound = bind_boolean(first, params)?;
+            for (op, right, span) in links {
+                bound = BooleanExpr::logical(op, bound, bind_boolean(right, params)?, span);
+            }
+            bound
+        }
+        BooleanExpr::Not(e, span) => BooleanExpr::Not(bind(e)?, *span),
+        BooleanExpr::Literal(..) | BooleanExpr::Variable(..) | BooleanExpr::Path(..) => expr.clone(),
+        BooleanExpr::Relational(rel) => BooleanExpr::Relational(Box::new(bind_relational(rel, params)?)),
+    })
+}
+
+fn bind_relational(expr: &RelationalExpr, params: &Parameters) -> Result<RelationalExpr, EvalError> {
+    let bind = |e: &ValueExpr| bind_value(e, params);
+    Ok(match expr {
+        RelationalExpr::Equality { left, op, right, span } => RelationalExpr::Equality {
+            left: bind(left)?,
+            op: *op,
+            right: bind(right)?,
+            span: *span,
+        },
+        RelationalExpr::Comparison { left, op, right, span } => RelationalExpr::Comparison {
+            left: bind(left)?,
+            op: *op,
+            right: bind(right)?,
+            span: *span,
+        },
+        RelationalExpr::Like { expr, pattern, negated, case_insensitive, span } => RelationalExpr::Like {
+            expr: bind(expr)?,
+            pattern: pattern.clone(),
+            negated: *negated,
+            case_insensitive: *case_insensitive,
+            span: *span,
+        },
+        RelationalExpr::Regex { expr, pattern, negated, span } => RelationalExpr::Regex {
+            expr: bind(expr)?,
+            pattern: pattern.clone(),
+            negated: *negated,
+            span: *span,
+        },
+        RelationalExpr::Between { expr, lower, upper, negated, span } => RelationalExpr::Between {
+            expr: bind(expr)?,
+            lower: bind(lower)?,
+            upper: bind(upper)?,
+            negated: *negated,
+            span: *span,
+        },
+        RelationalExpr::In { expr, values, negated, span } => RelationalExpr::In {
+            expr: bind(expr)?,
+            values: values.iter().map(bind).collect::<Result<_, _>>()?,
+            negated: *negated,
+            span: *span,
+        },
+        RelationalExpr::InCollection { expr, collection, negated, span } => RelationalExpr::InCollection {
+            expr: bind(expr)?,
+            collection: bind(collection)?,
+            negated: *negated,
+            span: *span,
+        },
+        RelationalExpr::Contains { collection, value, negated, span } => RelationalExpr::Contains {
+            collection: bind(collection)?,
+            value: bind(value)?,
+            negated: *negated,
+            span: *span,
+        },
+        RelationalExpr::IsNull { expr, negated, span } => RelationalExpr::IsNull {
+            expr: bind(expr)?,
+            negated: *negated,
+            span: *span,
+        },
+        RelationalExpr::Collated { expr, collation, span } => RelationalExpr::Collated {
+            expr: Box::new(bind_relational(expr, params)?),
+            collation: *collation,
+            span: *span,
+        },
+    })
+}
+
+fn bind_value(expr: &ValueExpr, params: &Parameters) -> Result<ValueExpr, EvalError> {
+    let bind = |e: &ValueExpr| bind_value(e, params).map(Box::new);
+    Ok(match expr {
+        ValueExpr::Parameter(parameter, span) => match params.get(parameter) {
+            Some(value) => {
+                let value = SubValue::from_runtime(value.clone());
+                let type_name = value.type_name();
+                match value.into_literal() {
+                    Some(lit) => ValueExpr::Literal(lit, *span),
+                    None => return Err(EvalError::TypeError {
+                        operation: format!("parameter {}", parameter),
+                        expected: "a value that can be written as a literal".to_string(),
+                        actual: type_name,
+                        context: "parameter binding".to_string(),
+                    }),
+                }
+            }
+            None => return Err(EvalError::UnboundParameter { parameter: parameter.clone() }),
+        },
+        ValueExpr::Literal(..) | ValueExpr::Variable(..) | ValueExpr::Path(..) => expr.clone(),
+        ValueExpr::Add(..)
+        | ValueExpr::Subtract(..)
+        | ValueExpr::Multiply(..)
+        | ValueExpr::Divide(..)
+        | ValueExpr::Modulo(..)
+        | ValueExpr::Concat(..) => {
+            let (first, links) = expr.chain();
+            let mut bound = bind_value(first, params)?;
+            for (op, right, span) in links {
+                bound = ValueExpr::binary(op, bound, bind_value(right, params)?, span);
+            }
+            bound
+        }
+        ValueExpr::UnaryPlus(e, span) => ValueExpr::UnaryPlus(bind(e)?, *span),
+        ValueExpr::UnaryMinus(e, span) => ValueExpr::UnaryMinus(bind(e)?, *span),
+        ValueExpr::Quantified(quantifier, e, span) => ValueExpr::Quantified(*quantifier, bind(e)?, *span),
+        ValueExpr::FunctionCall { name, args, span } => ValueExpr::FunctionCall {
+            name: name.clone(),
+            args: args.iter().map(|arg| bind_value(arg, params)).collect::<Result<_, _>>()?,
+            span: *span,
+        },
+        ValueExpr::Case { operand, branches, else_result, span } => ValueExpr::Case {
+            operand: operand.as_deref().map(bind).transpose()?,
+            branches: branches.iter()
+                .map(|branch| Ok(CaseBranch {
+                    when: match &branch.when {
+                        CaseWhen::Condition(condition) => CaseWhen::Condition(bind_boolean(condition, params)?),
+                        CaseWhen::Value(value) => CaseWhen::Value(bind_value(value, params)?),
+                    },
+                    result: bind_value(&branch.result, params)?,
+                }))
+                .collect::<Result<_, EvalError>>()?,
+            else_result: else_result.as_deref().map(bind).transpose()?,
+            span: *span,
+        },
+    })
+}
+
+// ============================================================================
+// EVALUATOR
+// ============================================================================
+
+/// Private evaluator implementation
+struct Evaluator<'a, P: ValueProvider + ?Sized> {
+    input: &'a str,
+    value_map: &'a P,
+    options: &'a EvalOptions,
+    /// Span of the innermost node that failed, once evaluation has failed
+    error_span: Cell<Option<Span>>,
+    /// Steps taken so far
+    steps: Cell<u64>,
+    /// Collation of the predicate being evaluated
+    collation: Cell<Collation>,
+}
+
+impl<'a, P: ValueProvider + ?Sized> Evaluator<'a, P> {
+    /// Create new evaluator over an already parsed expression's source text
+    fn new(input: &'a str, value_map: &'a P, options: &'a EvalOptions) -> Self {
+        Evaluator {
+            input,
             value_map,
+            options,
+            error_span: Cell::new(None),
+            steps: Cell::new(0),
+            collation: Cell::new(options.collation),
+        }
+    }
+
+    /// Result of an integer operation under the overflow policy.  `describe` gives
+    /// the operation and the expression for the error.
+    fn integer_result(
+        &self,
+        checked: Option<i64>,
+        saturated: i64,
+        promoted: f64,
+        describe: impl FnOnce() -> (&'static str, String),
+    ) -> Result<SubValue, EvalError> {
+        let result = self.options.overflow.resolve(checked, saturated, promoted, || {
+            let (operation, expression) = describe();
+            EvalError::Overflow { operation: operation.to_string(), expression }
+        });
+        result.map(SubValue::from_runtime)
+    }
+
+    /// Result of a decimal operation; `None` is an overflow whatever the policy
+    fn decimal_result(
+        result: Option<Decimal>,
+        describe: impl FnOnce() -> (&'static str, String),
+    ) -> Result<SubValue, EvalError> {
+        result.map(SubValue::Decimal).ok_or_else(|| {
+            let (operation, expression) = describe();
+            EvalError::Overflow { operation: operation.to_string(), expression }
         })
     }
 
+    /// Take an evaluation step, failing once the budget is used up or the
+    /// evaluation has been cancelled
+    fn step(&self) -> Result<(), EvalError> {
+        let steps = self.steps.get() + 1;
+        self.steps.set(steps);
+        if let Some(limit) = self.options.max_steps
+            && steps > limit
+        {
+            return Err(EvalError::BudgetExceeded { limit });
+        }
+        if let Some(cancel) = &self.options.cancel
+            && steps % EvalOptions::CANCEL_CHECK_INTERVAL == 1
+            && cancel.load(Ordering::Relaxed)
+        {
+            return Err(EvalError::Cancelled);
+        }
+        Ok(())
+    }
+
+    /// Remember where the first error was raised.  Errors propagate outwards, so
+    /// the first node to record one is the innermost node that failed.
+    fn locate<T>(&self, span: Span, result: Result<T, EvalError>) -> Result<T, EvalError> {
+        if result.is_err() && self.error_span.get().is_none() {
+            self.error_span.set(Some(span));
+        }
+        result
+    }
+
+    /// Report a NULL operand.  Under strict NULL semantics this is an error; under
+    /// three-valued semantics it succeeds and the caller propagates NULL or UNKNOWN.
+    fn null_operand(&self, operation: &str, context: &str) -> Result<(), EvalError> {
+        match self.options.null_semantics {
+            NullSemantics::Strict => Err(EvalError::NullInOperation {
+                operation: operation.to_string(),
+                context: context.to_string(),
+            }),
+            NullSemantics::ThreeValued => Ok(()),
+        }
+    }
+
     // ========================================================================
     // BOOLEAN EXPRESSION EVALUATION
     // ========================================================================
 
     /// Evaluate a boolean expression
-    fn eval_boolean(&self, expr: &BooleanExpr) -> Result<bool, EvalError> {
+    fn eval_boolean(&self, expr: &BooleanExpr) -> Result<Truth, EvalError> {
+        let result = self.step().and_then(|()| self.eval_boolean_node(expr));
+        self.locate(expr.span(), result)
+    }
+
+    /// Evaluate a chain of ANDs and ORs with a loop, as it nests as deep as it
+    /// is long (see `BooleanExpr::chain`)
+    fn eval_logical_chain(&self, expr: &BooleanExpr) -> Result<Truth, EvalError> {
+        let (first, links) = expr.chain();
+        self.step_chain(&links)?;
+        let mut truth = self.eval_boolean(first)?;
+        for (op, right, _) in links {
+            truth = match (op, truth) {
+                // Short-circuit: if the left side decides the link, don't evaluate the right
+                (LogicalOp::And, Truth::False) => Truth::False,
+                (LogicalOp::Or, Truth::True) => Truth::True,
+                (LogicalOp::And, left) => left.and(self.eval_boolean(right)?),
+                (LogicalOp::Or, left) => left.or(self.eval_boolean(right)?),
+            };
+        }
+        Ok(truth)
+    }
+
+    /// Take the steps for the links of a chain below the outermost, whose step
+    /// has been taken already, as if each were evaluated in turn
+    fn step_chain<Op, T>(&self, links: &[(Op, &T, Span)]) -> Result<(), EvalError> {
+        for (_, _, span) in links.iter().rev().skip(1) {
+            let result = self.step();
+            self.locate(*span, result)?;
+        }
+        Ok(())
+    }
+
+    fn eval_boolean_node(&self, expr: &BooleanExpr) -> Result<Truth, EvalError> {
         match expr {
-            BooleanExpr::Literal(b) => Ok(*b),
-
-            BooleanExpr::Variable(name) => {
-                match self.value_map.get(name) {
-                    Some(RuntimeValue::Boolean(b)) => Ok(*b),
-                    Some(other) => Err(EvalError::TypeError {
-                        operation: "boolean variable".to_string(),
-                        expected: "boolean".to_string(),
-                        actual: Self::runtime_type_name(other),
-                        context: format!("variable '{}'", name),
-                    }),
+            BooleanExpr::Literal(b, _) => Ok(Truth::from(*b)),
+
+            BooleanExpr::Variable(name, _) => {
+                match self.value_map.resolve(name) {
+                    Some(value) => self.boolean_variable(value, name),
                     None => Err(EvalError::UnboundVariable {
                         name: name.clone(),
                     }),
                 }
             }
 
-            BooleanExpr::And(left, right) => {
-                let l = self.eval_boolean(left)?;
-                // Short-circuit: if left is false, don't evaluate right
-                if !l {
-                    return Ok(false);
-                }
-                self.eval_boolean(right)
+            BooleanExpr::Path(root, segments, _) => {
+                let value = self.resolve_path(root, segments)?;
+                self.boolean_variable(value, &expr.to_string())
             }
 
-            BooleanExpr::Or(left, right) => {
-                let l = self.eval_boolean(left)?;
-                // Short-circuit: if left is true, don't evaluate right
-                if l {
-                    return Ok(true);
-                }
-                self.eval_boolean(right)
-            }
+            BooleanExpr::And(..) | BooleanExpr::Or(..) => self.eval_logical_chain(expr),
 
-            BooleanExpr::Not(expr) => {
+            BooleanExpr::Not(expr, _) => {
                 Ok(!self.eval_boolean(expr)?)
             }
 
@@ -216,58 +1158,92 @@ impl<'a> Evaluator<'a> {
     // ========================================================================
 
     /// Evaluate a relational expression to boolean
-    fn eval_relational(&self, expr: &RelationalExpr) -> Result<bool, EvalError> {
+    fn eval_relational(&self, expr: &RelationalExpr) -> Result<Truth, EvalError> {
+        let result = self.step().and_then(|()| self.eval_relational_node(expr));
+        self.locate(expr.span(), result)
+    }
+
+    fn eval_relational_node(&self, expr: &RelationalExpr) -> Result<Truth, EvalError> {
         match expr {
-            RelationalExpr::Equality { left, op, right } => {
+            RelationalExpr::Equality { left, op, right, .. } => {
                 self.eval_equality(left, right, *op)
             }
 
-            RelationalExpr::Comparison { left, op, right } => {
+            RelationalExpr::Comparison { left, op, right, .. } => {
                 self.eval_comparison(left, right, *op)
             }
 
-            RelationalExpr::Like { expr, pattern, escape, negated } => {
-                self.eval_like(expr, pattern, escape.as_ref(), *negated)
+            RelationalExpr::Like { expr, pattern, negated, case_insensitive, .. } => {
+                self.eval_like(expr, pattern, *negated, *case_insensitive)
             }
 
-            RelationalExpr::Between { expr, lower, upper, negated } => {
+            RelationalExpr::Regex { expr, pattern, negated, .. } => {
+                self.eval_regex(expr, pattern, *negated)
+            }
+
+            RelationalExpr::Between { expr, lower, upper, negated, .. } => {
                 self.eval_between(expr, lower, upper, *negated)
             }
 
-            RelationalExpr::In { expr, values, negated } => {
+            RelationalExpr::In { expr, values, negated, .. } => {
                 self.eval_in(expr, values, *negated)
             }
 
-            RelationalExpr::IsNull { expr, negated } => {
+            RelationalExpr::InCollection { expr, collection, negated, .. } => {
+                let value = self.eval_value(expr)?;
+                let collection = self.eval_value(collection)?;
+                self.eval_membership("IN", &value, collection, *negated)
+            }
+
+            RelationalExpr::Contains { collection, value, negated, .. } => {
+                let collection = self.eval_value(collection)?;
+                let value = self.eval_value(value)?;
+                self.eval_membership("CONTAINS", &value, collection, *negated)
+            }
+
+            RelationalExpr::IsNull { expr, negated, .. } => {
                 self.eval_is_null(expr, *negated)
             }
+
+            RelationalExpr::Collated { expr, collation, .. } => {
+                let outer = self.collation.replace(*collation);
+                let result = self.eval_relational(expr);
+                self.collation.set(outer);
+                result
+            }
         }
     }
 
     /// Evaluate equality/inequality operators
     fn eval_equality(&self, left: &ValueExpr, right: &ValueExpr, op: EqualityOp)
-        -> Result<bool, EvalError>
+        -> Result<Truth, EvalError>
     {
-        let l_val = self.eval_value(left)?;
-        let r_val = self.eval_value(right)?;
+        self.eval_compared(left, right, |l_val, r_val| self.equality_truth(l_val, r_val, op))
+    }
 
+    /// Compare two values for equality or inequality
+    fn equality_truth(&self, l_val: &SubValue, r_val: &SubValue, op: EqualityOp) -> Result<Truth, EvalError> {
         // NULL handling
         if l_val.is_null() || r_val.is_null() {
-            return Err(EvalError::NullInOperation {
-                operation: format!("{:?}", op),
-                context: "cannot compare NULL values (use IS NULL instead)".to_string(),
-            });
+            self.null_operand(&format!("{:?}", op), "cannot compare NULL values (use IS NULL instead)")?;
+            return Ok(Truth::Unknown);
         }
 
-        let equal = match (&l_val, &r_val) {
-            // Numeric comparisons
-            (SubValue::Integer(a), SubValue::Integer(b)) => a == b,
-            (SubValue::Float(a), SubValue::Float(b)) => a == b,
-            (SubValue::Integer(a), SubValue::Float(b)) => (*a as f64) == *b,
-            (SubValue::Float(a), SubValue::Integer(b)) => *a == (*b as f64),
+        // Numbers compare exactly, whatever their types
+        if let Some(order) = l_val.numeric_order(r_val) {
+            return Ok(Truth::from(order.is_eq() == (op == EqualityOp::Equal)));
+        }
+
+        let equal = match (l_val, r_val) {
+            // Dates and timestamps compare as instants, a date being midnight UTC
+            (SubValue::Date(a), SubValue::Date(b)) => a == b,
+            (SubValue::Timestamp(a), SubValue::Timestamp(b)) => a == b,
+            (SubValue::Date(a), SubValue::Timestamp(b)) => Timestamp::from(*a) == *b,
+            (SubValue::Timestamp(a), SubValue::Date(b)) => *a == Timestamp::from(*b),
+            (SubValue::Interval(a), SubValue::Interval(b)) => a == b,
 
-            // String comparisons
-            (SubValue::String(a), SubValue::String(b)) => a == b,
+            // String comparisons under the predicate's collation
+            (SubValue::String(a), SubValue::String(b)) => self.collation.get().equals(a, b),
 
             // Boolean comparisons (only for equality)
             (SubValue::Boolean(a), SubValue::Boolean(b)) => a == b,
@@ -281,65 +1257,150 @@ impl<'a> Evaluator<'a> {
             }),
         };
 
-        Ok(match op {
+        Ok(Truth::from(match op {
             EqualityOp::Equal => equal,
             EqualityOp::NotEqual => !equal,
-        })
+        }))
     }
 
     /// Evaluate comparison operators (>, <, >=, <=)
     fn eval_comparison(&self, left: &ValueExpr, right: &ValueExpr, op: ComparisonOp)
-        -> Result<bool, EvalError>
+        -> Result<Truth, EvalError>
     {
-        let l_val = self.eval_value(left)?;
-        let r_val = self.eval_value(right)?;
+        self.eval_compared(left, right, |l_val, r_val| self.comparison_truth(l_val, r_val, op))
+    }
 
+    /// Compare two values with >, <, >= or <=
+    fn comparison_truth(&self, l_val: &SubValue, r_val: &SubValue, op: ComparisonOp) -> Result<Truth, EvalError> {
         // NULL handling
         if l_val.is_null() || r_val.is_null() {
-            return Err(EvalError::NullInOperation {
-                operation: format!("{:?}", op),
-                context: "cannot compare NULL values".to_string(),
-            });
+            self.null_operand(&format!("{:?}", op), "cannot compare NULL values")?;
+            return Ok(Truth::Unknown);
         }
 
-        match (&l_val, &r_val) {
-            // Numeric comparisons
-            (SubValue::Integer(a), SubValue::Integer(b)) => {
-                Ok(Self::apply_comparison_op(*a, *b, op))
+        // Numbers compare exactly, whatever their types
+        if let Some(order) = l_val.numeric_order(r_val) {
+            return Ok(Truth::from(Self::apply_comparison_op(order, cmp::Ordering::Equal, op)));
+        }
+
+        let result = match (l_val, r_val) {
+            // Dates and timestamps compare as instants, a date being midnight
+            // UTC; intervals by length, a month being 30 days
+            (SubValue::Date(a), SubValue::Date(b)) => {
+                Self::apply_comparison_op(a, b, op)
             }
-            (SubValue::Float(a), SubValue::Float(b)) => {
-                Ok(Self::apply_comparison_op(*a, *b, op))
+            (SubValue::Timestamp(a), SubValue::Timestamp(b)) => {
+                Self::apply_comparison_op(a, b, op)
             }
-            (SubValue::Integer(a), SubValue::Float(b)) => {
-                Ok(Self::apply_comparison_op(*a as f64, *b, op))
+            (SubValue::Date(a), SubValue::Timestamp(b)) => {
+                Self::apply_comparison_op(Timestamp::from(*a), *b, op)
             }
-            (SubValue::Float(a), SubValue::Integer(b)) => {
-                Ok(Self::apply_comparison_op(*a, *b as f64, op))
+            (SubValue::Timestamp(a), SubValue::Date(b)) => {
+                Self::apply_comparison_op(*a, Timestamp::from(*b), op)
+            }
+            (SubValue::Interval(a), SubValue::Interval(b)) => {
+                Self::apply_comparison_op(a, b, op)
             }
 
-            // String comparisons (lexicographic)
+            // String comparisons (lexicographic, under the predicate's collation)
             (SubValue::String(a), SubValue::String(b)) => {
-                Ok(Self::apply_comparison_op(a, b, op))
+                Self::apply_comparison_op(self.collation.get().compare(a, b), cmp::Ordering::Equal, op)
             }
 
             // Boolean not allowed in comparisons
             (SubValue::Boolean(_), _) | (_, SubValue::Boolean(_)) => {
-                Err(EvalError::TypeError {
+                return Err(EvalError::TypeError {
                     operation: format!("{:?}", op),
-                    expected: "numeric or string".to_string(),
+                    expected: "numeric, string, date, timestamp or interval".to_string(),
                     actual: "boolean".to_string(),
                     context: "comparison operand".to_string(),
-                })
+                });
             }
 
             // Type mismatch
-            _ => Err(EvalError::TypeError {
+            _ => return Err(EvalError::TypeError {
                 operation: format!("{:?}", op),
                 expected: "matching types".to_string(),
                 actual: format!("{} vs {}", l_val.type_name(), r_val.type_name()),
                 context: "comparison".to_string(),
             }),
+        };
+
+        Ok(Truth::from(result))
+    }
+
+    /// Evaluate the operands of a comparison and compare them with `compare`.
+    /// When one operand is ANY(...) or ALL(...), the other operand is compared
+    /// with each element in turn, and the results are combined with OR for ANY
+    /// and AND for ALL: ANY over no elements is false, ALL over none is true.
+    fn eval_compared(
+        &self,
+        left: &ValueExpr,
+        right: &ValueExpr,
+        compare: impl Fn(&SubValue, &SubValue) -> Result<Truth, EvalError>,
+    ) -> Result<Truth, EvalError> {
+        match (left, right) {
+            (ValueExpr::Quantified(quantifier, collection, span), _) => {
+                let elements = self.quantified_elements(*quantifier, collection, *span)?;
+                let value = self.eval_value(right)?;
+                self.quantify(*quantifier, elements.as_deref(), |element| compare(element, &value))
+            }
+            (_, ValueExpr::Quantified(quantifier, collection, span)) => {
+                let value = self.eval_value(left)?;
+                let elements = self.quantified_elements(*quantifier, collection, *span)?;
+                self.quantify(*quantifier, elements.as_deref(), |element| compare(&value, element))
+            }
+            _ => compare(&self.eval_value(left)?, &self.eval_value(right)?),
+        }
+    }
+
+    /// Evaluate the operand of ANY(...) or ALL(...) to the values it ranges over:
+    /// the elements of a list or the values of a map.  `None` if it is NULL.
+    fn quantified_elements(&self, quantifier: Quantifier, collection: &ValueExpr, span: Span)
+        -> Result<Option<Vec<SubValue>>, EvalError>
+    {
+        let result = self.step().and_then(|()| match self.eval_value(collection)? {
+            SubValue::List(items) => Ok(Some(items.into_iter().map(SubValue::from_runtime).collect())),
+            SubValue::Map(entries) => Ok(Some(entries.into_values().map(SubValue::from_runtime).collect())),
+            SubValue::Null => {
+                self.null_operand(&quantifier.to_string(), "cannot compare with the elements of NULL")?;
+                Ok(None)
+            }
+            other => Err(EvalError::TypeError {
+                operation: quantifier.to_string(),
+                expected: "list or map".to_string(),
+                actual: other.type_name(),
+                context: "quantified comparison".to_string(),
+            }),
+        });
+        self.locate(span, result)
+    }
+
+    /// Combine the results of `compare` over `elements`, with OR for ANY and AND
+    /// for ALL, stopping as soon as the result is decided.  UNKNOWN if there
+    /// are no elements because the collection is NULL.
+    fn quantify(
+        &self,
+        quantifier: Quantifier,
+        elements: Option<&[SubValue]>,
+        compare: impl Fn(&SubValue) -> Result<Truth, EvalError>,
+    ) -> Result<Truth, EvalError> {
+        let Some(elements) = elements else {
+            return Ok(Truth::Unknown);
+        };
+        let decided = Truth::from(quantifier == Quantifier::Any);
+        let mut result = !decided;
+        for element in elements {
+            self.step()?;
+            result = match quantifier {
+                Quantifier::Any => result.or(compare(element)?),
+                Quantifier::All => result.and(compare(element)?),
+            };
+            if result == decided {
+                break;
+            }
         }
+        Ok(result)
     }
 
     fn apply_comparison_op<T: PartialOrd>(a: T, b: T, op: ComparisonOp) -> bool {
@@ -351,71 +1412,62 @@ impl<'a> Evaluator<'a> {
         }
     }
 
-    /// Evaluate LIKE operator with wildcards
-    fn eval_like(&self, expr: &ValueExpr, pattern: &str, escape: Option<&String>, negated: bool)
-        -> Result<bool, EvalError>
+    /// Evaluate LIKE operator with wildcards, or ILIKE, which matches regardless
+    /// of case whatever the collation
+    fn eval_like(&self, expr: &ValueExpr, pattern: &LikePattern, negated: bool, case_insensitive: bool)
+        -> Result<Truth, EvalError>
     {
-        let val = self.eval_value(expr)?;
-
-        let string_val = match val {
-            SubValue::String(s) => s,
-            SubValue::Null => {
-                return Err(EvalError::NullInOperation {
-                    operation: "LIKE".to_string(),
-                    context: "cannot apply LIKE to NULL".to_string(),
-                });
-            }
-            _ => {
-                return Err(EvalError::TypeError {
-                    operation: "LIKE".to_string(),
-                    expected: "string".to_string(),
-                    actual: val.type_name(),
-                    context: "left operand".to_string(),
-                });
-            }
+        let operation = if case_insensitive { "ILIKE" } else { "LIKE" };
+        let Some(string_val) = self.pattern_operand(expr, operation)? else {
+            return Ok(Truth::Unknown);
         };
 
-        let matches = Self::match_pattern(&string_val, pattern, escape)?;
-        Ok(if negated { !matches } else { matches })
+        self.step()?;
+        let collation = if case_insensitive { Collation::NoCase } else { self.collation.get() };
+        let matches = pattern.matches_collated(&string_val, collation);
+        Ok(Truth::from(if negated { !matches } else { matches }))
     }
 
-    /// Pattern matching with SQL wildcards (% = any chars, _ = single char)
-    fn match_pattern(s: &str, pattern: &str, escape: Option<&String>) -> Result<bool, EvalError> {
-        let escape_char = escape.and_then(|e| e.chars().next());
+    /// Evaluate REGEXP or SIMILAR TO
+    fn eval_regex(&self, expr: &ValueExpr, pattern: &RegexPattern, negated: bool)
+        -> Result<Truth, EvalError>
+    {
+        let operation = match pattern.syntax() {
+            RegexSyntax::Regexp => "REGEXP",
+            RegexSyntax::SimilarTo => "SIMILAR TO",
+        };
+        let Some(string_val) = self.pattern_operand(expr, operation)? else {
+            return Ok(Truth::Unknown);
+        };
 
-        // Convert SQL pattern to regex
-        let mut regex_pattern = String::from("^");
-        let mut chars = pattern.chars().peekable();
+        self.step()?;
+        let matches = pattern.matches(&string_val);
+        Ok(Truth::from(if negated { !matches } else { matches }))
+    }
 
-        while let Some(ch) = chars.next() {
-            if Some(ch) == escape_char {
-                // Escaped character - treat next character literally
-                if let Some(next) = chars.next() {
-                    regex_pattern.push_str(&regex::escape(&next.to_string()));
-                }
-            } else if ch == '%' {
-                regex_pattern.push_str(".*");
-            } else if ch == '_' {
-                regex_pattern.push('.');
-            } else {
-                regex_pattern.push_str(&regex::escape(&ch.to_string()));
+    /// The string a pattern is matched against, or `None` if it is NULL and
+    /// NULL operands are allowed
+    fn pattern_operand(&self, expr: &ValueExpr, operation: &str) -> Result<Option<String>, EvalError> {
+        match self.eval_value(expr)? {
+            SubValue::String(s) => Ok(Some(s)),
+            SubValue::Null => {
+                self.null_operand(operation, &format!("cannot apply {} to NULL", operation))?;
+                Ok(None)
             }
+            val => Err(EvalError::TypeError {
+                operation: operation.to_string(),
+                expected: "string".to_string(),
+                actual: val.type_name(),
+                context: "left operand".to_string(),
+            }),
         }
-        regex_pattern.push('$');
-
-        let re = regex::Regex::new(&regex_pattern)
-            .map_err(|e| EvalError::InvalidLiteral {
-                literal: pattern.to_string(),
-                literal_type: "LIKE pattern".to_string(),
-                error: format!("{}", e),
-            })?;
-
-        Ok(re.is_match(s))
     }
 
-    /// Evaluate BETWEEN operator
+    /// Evaluate BETWEEN operator.  Bounds that are only known at runtime may be
+    /// reversed; like `lower <= expr AND expr <= upper`, the range is then empty,
+    /// so BETWEEN is false and NOT BETWEEN true.
     fn eval_between(&self, expr: &ValueExpr, lower: &ValueExpr, upper: &ValueExpr, negated: bool)
-        -> Result<bool, EvalError>
+        -> Result<Truth, EvalError>
     {
         let val = self.eval_value(expr)?;
         let low = self.eval_value(lower)?;
@@ -423,98 +1475,107 @@ impl<'a> Evaluator<'a> {
 
         // Check for NULL
         if val.is_null() || low.is_null() || high.is_null() {
-            return Err(EvalError::NullInOperation {
-                operation: "BETWEEN".to_string(),
-                context: "cannot use NULL in BETWEEN".to_string(),
-            });
+            self.null_operand("BETWEEN", "cannot use NULL in BETWEEN")?;
+            return Ok(Truth::Unknown);
         }
 
-        // All must be same comparable type
-        let in_range = match (&val, &low, &high) {
-            (SubValue::Integer(v), SubValue::Integer(l), SubValue::Integer(h)) => {
-                v >= l && v <= h
-            }
-            (SubValue::Float(v), SubValue::Float(l), SubValue::Float(h)) => {
-                v >= l && v <= h
-            }
-            (SubValue::String(v), SubValue::String(l), SubValue::String(h)) => {
-                v >= l && v <= h
-            }
-            // Mixed numeric types need coercion
-            _ => {
-                // Try numeric comparison with coercion
-                let v_num = Self::to_numeric(&val)?;
-                let l_num = Self::to_numeric(&low)?;
-                let h_num = Self::to_numeric(&high)?;
-                v_num >= l_num && v_num <= h_num
-            }
+        // All must be mutually comparable: numbers of any type, strings,
+        // dates with timestamps, or intervals
+        let collation = self.collation.get();
+        let in_range = match (low.value_order(&val, collation), val.value_order(&high, collation)) {
+            (Some(l), Some(h)) => l != cmp::Ordering::Greater && h != cmp::Ordering::Greater,
+            _ => return Err(EvalError::TypeError {
+                operation: "BETWEEN".to_string(),
+                expected: format!("{} bounds", val.type_name()),
+                actual: format!("{} and {}", low.type_name(), high.type_name()),
+                context: "BETWEEN bounds".to_string(),
+            }),
         };
 
-        Ok(if negated { !in_range } else { in_range })
+        Ok(Truth::from(if negated { !in_range } else { in_range }))
     }
 
-    /// Evaluate IN operator
-    fn eval_in(&self, expr: &ValueExpr, values: &[ValueLiteral], negated: bool)
-        -> Result<bool, EvalError>
+    /// Evaluate IN operator.  Members are evaluated in order until one equals
+    /// the left operand, and each must be type-compatible with it.
+    fn eval_in(&self, expr: &ValueExpr, values: &[ValueExpr], negated: bool)
+        -> Result<Truth, EvalError>
     {
         let val = self.eval_value(expr)?;
 
         if val.is_null() {
-            return Err(EvalError::NullInOperation {
-                operation: "IN".to_string(),
-                context: "cannot use NULL in IN".to_string(),
-            });
+            self.null_operand("IN", "cannot use NULL in IN")?;
+            return Ok(Truth::Unknown);
         }
 
-        // Type consistency of the values list is now guaranteed by the parser,
-        // so we only need to check if the left operand is type-compatible with the list
-        if !values.is_empty() {
-            let first_list_val = SubValue::from_literal(&values[0]);
-            let is_compatible = Self::are_types_compatible_for_in(&val, &first_list_val);
+        // Literal members all have the same type, which the parser checks;
+        // members that are expressions can only be checked once evaluated
+        let mut found = Truth::False;
+        for value in values {
+            let list_val = self.eval_value(value)?;
 
-            // If list is incompatible with left value, it's a type error
-            if !is_compatible {
+            if list_val.is_null() {
+                self.null_operand("IN", "IN list member is NULL")?;
+                found = Truth::Unknown;
+                continue;
+            }
+
+            if !Self::are_types_compatible_for_in(&val, &list_val) {
                 return Err(EvalError::TypeError {
                     operation: "IN".to_string(),
-                    expected: first_list_val.type_name(),
+                    expected: list_val.type_name(),
                     actual: val.type_name(),
                     context: "left operand type doesn't match list element types".to_string(),
                 });
             }
 
-            // Note: Type consistency check for list elements removed - now done at parse time
+            if self.equality_truth(&val, &list_val, EqualityOp::Equal)?.is_true() {
+                found = Truth::True;
+                break;
+            }
         }
 
-        let mut found = false;
-        for lit_val in values {
-            let list_val = SubValue::from_literal(lit_val);
-
-            // Check if values match (with type compatibility)
-            let matches = match (&val, &list_val) {
-                (SubValue::Integer(a), SubValue::Integer(b)) => a == b,
-                (SubValue::Float(a), SubValue::Float(b)) => a == b,
-                (SubValue::Integer(a), SubValue::Float(b)) => (*a as f64) == *b,
-                (SubValue::Float(a), SubValue::Integer(b)) => *a == (*b as f64),
-                (SubValue::String(a), SubValue::String(b)) => a == b,
-                (SubValue::Boolean(a), SubValue::Boolean(b)) => a == b,
-                (SubValue::Null, SubValue::Null) => true,
-                _ => false,  // Means no match since type mismatches are caused above
-            };
+        Ok(if negated { !found } else { found })
+    }
 
-            if matches {
-                found = true;
-                break;
+    /// Evaluate IN against a collection and CONTAINS: whether `value` equals an
+    /// element of a list or a key of a map
+    fn eval_membership(&self, operation: &str, value: &SubValue, collection: SubValue, negated: bool)
+        -> Result<Truth, EvalError>
+    {
+        let elements: Vec<SubValue> = match collection {
+            SubValue::List(items) => items.into_iter().map(SubValue::from_runtime).collect(),
+            SubValue::Map(entries) => entries.into_keys().map(SubValue::String).collect(),
+            SubValue::Null => {
+                self.null_operand(operation, "cannot test membership in NULL")?;
+                return Ok(Truth::Unknown);
             }
+            other => return Err(EvalError::TypeError {
+                operation: operation.to_string(),
+                expected: "list or map".to_string(),
+                actual: other.type_name(),
+                context: "collection operand".to_string(),
+            }),
+        };
+        if value.is_null() {
+            self.null_operand(operation, "cannot test membership of NULL")?;
+            return Ok(Truth::Unknown);
         }
 
+        let found = self.quantify(Quantifier::Any, Some(&elements), |element| {
+            if element.is_null() {
+                self.null_operand(operation, "collection has a NULL element")?;
+                return Ok(Truth::Unknown);
+            }
+            self.equality_truth(value, element, EqualityOp::Equal)
+        })?;
         Ok(if negated { !found } else { found })
     }
 
     /// Evaluate IS NULL operator
-    fn eval_is_null(&self, expr: &ValueExpr, negated: bool) -> Result<bool, EvalError> {
+    fn eval_is_null(&self, expr: &ValueExpr, negated: bool) -> Result<Truth, EvalError> {
         let val = self.eval_value(expr)?;
         let is_null = val.is_null();
-        Ok(if negated { !is_null } else { is_null })
+        Ok(Truth::from(if negated { !is_null } else { is_null }))
     }
 
     // ========================================================================
@@ -523,11 +1584,16 @@ impl<'a> Evaluator<'a> {
 
     /// Evaluate a value expression to a concrete value
     fn eval_value(&self, expr: &ValueExpr) -> Result<SubValue, EvalError> {
+        let result = self.step().and_then(|()| self.eval_value_node(expr));
+        self.locate(expr.span(), result)
+    }
+
+    fn eval_value_node(&self, expr: &ValueExpr) -> Result<SubValue, EvalError> {
         match expr {
-            ValueExpr::Literal(lit) => Ok(SubValue::from_literal(lit)),
+            ValueExpr::Literal(lit, _) => Ok(SubValue::from_literal(lit)),
 
-            ValueExpr::Variable(name) => {
-                match self.value_map.get(name) {
+            ValueExpr::Variable(name, _) => {
+                match self.value_map.resolve(name) {
                     Some(rv) => Ok(SubValue::from_runtime(rv)),
                     None => Err(EvalError::UnboundVariable {
                         name: name.clone(),
@@ -535,21 +1601,43 @@ impl<'a> Evaluator<'a> {
                 }
             }
 
-            ValueExpr::Add(l, r) => self.eval_arithmetic_add(l, r),
-            ValueExpr::Subtract(l, r) => self.eval_arithmetic_subtract(l, r),
-            ValueExpr::Multiply(l, r) => self.eval_arithmetic_multiply(l, r),
-            ValueExpr::Divide(l, r) => self.eval_arithmetic_divide(l, r),
-            ValueExpr::Modulo(l, r) => self.eval_arithmetic_modulo(l, r),
+            ValueExpr::Path(root, segments, _) => self.resolve_path(root, segments).map(SubValue::from_runtime),
+
+            ValueExpr::Parameter(parameter, _) => Err(EvalError::UnboundParameter {
+                parameter: parameter.clone(),
+            }),
+
+            ValueExpr::FunctionCall { name, args, .. } => self.eval_function_call(name, args),
+
+            ValueExpr::Case { operand, branches, else_result, .. } => {
+                self.eval_case(operand.as_deref(), branches, else_result.as_deref())
+            }
+
+            ValueExpr::Quantified(quantifier, ..) => Err(EvalError::TypeError {
+                operation: quantifier.to_string(),
+                expected: "comparison operand".to_string(),
+                actual: "value operand".to_string(),
+                context: "ANY(...) and ALL(...) can only be compared with =, <>, <, <=, > or >=".to_string(),
+            }),
+
+            ValueExpr::Add(..)
+            | ValueExpr::Subtract(..)
+            | ValueExpr::Multiply(..)
+            | ValueExpr::Divide(..)
+            | ValueExpr::Modulo(..)
+            | ValueExpr::Concat(..) => self.eval_binary_chain(expr),
 
-            ValueExpr::UnaryPlus(e) => {
+            ValueExpr::UnaryPlus(e, _) => {
                 let val = self.eval_value(e)?;
                 match val {
                     SubValue::Integer(i) => Ok(SubValue::Integer(i)),
                     SubValue::Float(f) => Ok(SubValue::Float(f)),
-                    SubValue::Null => Err(EvalError::NullInOperation {
-                        operation: "unary plus".to_string(),
-                        context: "cannot apply unary plus to NULL".to_string(),
-                    }),
+                    SubValue::Decimal(d) => Ok(SubValue::Decimal(d)),
+                    SubValue::Interval(i) => Ok(SubValue::Interval(i)),
+                    SubValue::Null => {
+                        self.null_operand("unary plus", "cannot apply unary plus to NULL")?;
+                        Ok(SubValue::Null)
+                    }
                     _ => Err(EvalError::TypeError {
                         operation: "unary plus".to_string(),
                         expected: "numeric".to_string(),
@@ -559,15 +1647,23 @@ impl<'a> Evaluator<'a> {
                 }
             }
 
-            ValueExpr::UnaryMinus(e) => {
+            ValueExpr::UnaryMinus(e, _) => {
                 let val = self.eval_value(e)?;
                 match val {
-                    SubValue::Integer(i) => Ok(SubValue::Integer(-i)),
+                    SubValue::Integer(i) => {
+                        self.integer_result(i.checked_neg(), i.saturating_neg(), -(i as f64), || {
+                            ("unary minus", format!("-({})", i))
+                        })
+                    }
                     SubValue::Float(f) => Ok(SubValue::Float(-f)),
-                    SubValue::Null => Err(EvalError::NullInOperation {
-                        operation: "unary minus".to_string(),
-                        context: "cannot apply unary minus to NULL".to_string(),
+                    SubValue::Decimal(d) => Ok(SubValue::Decimal(-d)),
+                    SubValue::Interval(i) => i.checked_neg().map(SubValue::Interval).ok_or_else(|| {
+                        EvalError::Overflow { operation: "unary minus".to_string(), expression: format!("-(INTERVAL '{}')", i) }
                     }),
+                    SubValue::Null => {
+                        self.null_operand("unary minus", "cannot apply unary minus to NULL")?;
+                        Ok(SubValue::Null)
+                    }
                     _ => Err(EvalError::TypeError {
                         operation: "unary minus".to_string(),
                         expected: "numeric".to_string(),
@@ -579,22 +1675,170 @@ impl<'a> Evaluator<'a> {
         }
     }
 
+    /// Truth of the value of a variable or path used as a boolean
+    fn boolean_variable(&self, value: RuntimeValue, name: &str) -> Result<Truth, EvalError> {
+        match value {
+            RuntimeValue::Boolean(b) => Ok(Truth::from(b)),
+            RuntimeValue::Null if self.options.null_semantics == NullSemantics::ThreeValued => {
+                Ok(Truth::Unknown)
+            }
+            other => Err(EvalError::TypeError {
+                operation: "boolean variable".to_string(),
+                expected: "boolean".to_string(),
+                actual: Self::runtime_type_name(&other),
+                context: format!("variable '{}'", name),
+            }),
+        }
+    }
+
+    /// Resolve a variable path by walking the maps and lists bound to its root.
+    /// A missing map entry or list element is an unbound variable named by the
+    /// path up to and including the missing segment; a NULL along the way makes
+    /// the whole path NULL.
+    fn resolve_path(&self, root: &str, segments: &[PathSegment]) -> Result<RuntimeValue, EvalError> {
+        let mut value = self.value_map.resolve(root).ok_or_else(|| EvalError::UnboundVariable {
+            name: root.to_string(),
+        })?;
+        let mut path = root.to_string();
+        for segment in segments {
+            path.push_str(&segment.to_string());
+            let next = match (value, segment) {
+                (RuntimeValue::Null, _) => return Ok(RuntimeValue::Null),
+                (RuntimeValue::Map(mut map), PathSegment::Field(name)) => map.remove(name),
+                (RuntimeValue::List(mut list), PathSegment::Index(index)) => {
+                    (*index < list.len()).then(|| list.swap_remove(*index))
+                }
+                (other, _) => return Err(EvalError::TypeError {
+                    operation: "path".to_string(),
+                    expected: match segment {
+                        PathSegment::Field(_) => "map".to_string(),
+                        PathSegment::Index(_) => "list".to_string(),
+                    },
+                    actual: Self::runtime_type_name(&other),
+                    context: format!("path '{}'", path),
+                }),
+            };
+            value = next.ok_or_else(|| EvalError::UnboundVariable { name: path.clone() })?;
+        }
+        Ok(value)
+    }
+
+    /// Evaluate a scalar function call
+    fn eval_function_call(&self, name: &str, args: &[ValueExpr]) -> Result<SubValue, EvalError> {
+        // Registered functions take precedence over built-ins of the same name
+        if let Some(function) = self.options.functions.get(name) {
+            if function.params.len() != args.len() {
+                return Err(Self::arity_error(&function.name, &function.params.len().to_string(), args.len()));
+            }
+            let Some(values) = self.eval_function_args(&function.name, args, false)? else {
+                return Ok(SubValue::Null);
+            };
+            return Ok(SubValue::from_runtime(function.call(&values)?));
+        }
+
+        let function = match lookup_builtin(name) {
+            Some(f) if f.accepts_arity(args.len()) => f,
+            Some(f) => return Err(Self::arity_error(f.name, &f.arity_description(), args.len())),
+            None => return Err(EvalError::UnknownFunction {
+                name: name.to_string(),
+            }),
+        };
+
+        let Some(values) = self.eval_function_args(function.name, args, function.accepts_null)? else {
+            return Ok(SubValue::Null);
+        };
+        match function.call(&values, self.options.overflow) {
+            Ok(result) => Ok(SubValue::from_runtime(result)),
+            Err(EvalError::DivisionByZero { .. }) => Err(EvalError::DivisionByZero {
+                expression: self.input.to_string(),
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Check whether the function called `name` can return different results
+    /// for the same arguments
+    fn is_volatile(&self, name: &str) -> bool {
+        match self.options.functions.get(name) {
+            Some(function) => function.volatile,
+            None => lookup_builtin(name).is_some_and(|function| function.volatile),
+        }
+    }
+
+    /// Evaluate function arguments.  Returns `None` when a NULL argument makes the
+    /// call itself NULL under three-valued semantics.
+    fn eval_function_args(&self, function: &str, args: &[ValueExpr], accepts_null: bool)
+        -> Result<Option<Vec<RuntimeValue>>, EvalError>
+    {
+        let values = args.iter()
+            .map(|arg| self.eval_value(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !accepts_null && values.iter().any(SubValue::is_null) {
+            self.null_operand(function, &format!("cannot pass NULL to {}", function))?;
+            return Ok(None);
+        }
+
+        Ok(Some(values.into_iter().map(SubValue::into_runtime).collect()))
+    }
+
+    fn arity_error(function: &str, expected: &str, actual: usize) -> EvalError {
+        EvalError::TypeError {
+            operation: function.to_string(),
+            expected: format!("{} argument(s)", expected),
+            actual: actual.to_string(),
+            context: "function call".to_string(),
+        }
+    }
+
+    /// Evaluate a chain of binary operations with a loop, as it nests as deep
+    /// as it is long (see `ValueExpr::chain`)
+    fn eval_binary_chain(&self, expr: &ValueExpr) -> Result<SubValue, EvalError> {
+        let (first, links) = expr.chain();
+        self.step_chain(&links)?;
+        let mut value = self.eval_value(first)?;
+        for (op, right, span) in links {
+            let result = self.eval_value(right).and_then(|right| self.eval_binary(op, value, right));
+            value = self.locate(span, result)?;
+        }
+        Ok(value)
+    }
+
+    /// Apply a binary operator to the values of its operands
+    fn eval_binary(&self, op: BinaryOp, left: SubValue, right: SubValue) -> Result<SubValue, EvalError> {
+        match op {
+            BinaryOp::Add => self.eval_arithmetic_add(left, right),
+            BinaryOp::Subtract => self.eval_arithmetic_subtract(left, right),
+            BinaryOp::Multiply => self.eval_arithmetic_multiply(left, right),
+            BinaryOp::Divide => self.eval_arithmetic_divide(left, right),
+            BinaryOp::Modulo => self.eval_arithmetic_modulo(left, right),
+            BinaryOp::Concat => self.eval_concat(left, right),
+        }
+    }
+
     /// Arithmetic addition with type checking and coercion
-    fn eval_arithmetic_add(&self, l: &ValueExpr, r: &ValueExpr) -> Result<SubValue, EvalError> {
-        let left = self.eval_value(l)?;
-        let right = self.eval_value(r)?;
+    fn eval_arithmetic_add(&self, left: SubValue, right: SubValue) -> Result<SubValue, EvalError> {
 
         // Check for NULL
         if left.is_null() || right.is_null() {
-            return Err(EvalError::NullInOperation {
-                operation: "addition".to_string(),
-                context: "cannot add NULL values".to_string(),
-            });
+            self.null_operand("addition", "cannot add NULL values")?;
+            return Ok(SubValue::Null);
+        }
+
+        // Exact decimal arithmetic, promoting an integer operand
+        if let Some((a, b)) = SubValue::decimal_operands(&left, &right) {
+            return Self::decimal_result(a.checked_add(b), || ("decimal addition", format!("{} + {}", a, b)));
+        }
+
+        if left.is_temporal() || right.is_temporal() {
+            return Self::temporal_arithmetic("addition", &left, &right);
         }
 
         match (&left, &right) {
             (SubValue::Integer(a), SubValue::Integer(b)) => {
-                Ok(SubValue::Integer(a + b))
+                self.integer_result(a.checked_add(*b), a.saturating_add(*b), *a as f64 + *b as f64, || {
+                    ("addition", format!("{} + {}", a, b))
+                })
             }
             (SubValue::Float(a), SubValue::Float(b)) => {
                 Ok(SubValue::Float(a + b))
@@ -606,6 +1850,19 @@ impl<'a> Evaluator<'a> {
             (SubValue::Float(a), SubValue::Integer(b)) => {
                 Ok(SubValue::Float(a + *b as f64))
             }
+            // Floats are approximate already: float + decimal = float
+            (SubValue::Decimal(a), SubValue::Float(b)) => {
+                Ok(SubValue::Float(a.to_f64() + b))
+            }
+            (SubValue::Float(a), SubValue::Decimal(b)) => {
+                Ok(SubValue::Float(a + b.to_f64()))
+            }
+            (SubValue::String(_), _) | (_, SubValue::String(_)) => Err(EvalError::TypeError {
+                operation: "addition".to_string(),
+                expected: "numeric types".to_string(),
+                actual: format!("{} and {}", left.type_name(), right.type_name()),
+                context: "strings are joined with ||, not +".to_string(),
+            }),
             _ => Err(EvalError::TypeError {
                 operation: "addition".to_string(),
                 expected: "numeric types".to_string(),
@@ -616,20 +1873,27 @@ impl<'a> Evaluator<'a> {
     }
 
     /// Arithmetic subtraction
-    fn eval_arithmetic_subtract(&self, l: &ValueExpr, r: &ValueExpr) -> Result<SubValue, EvalError> {
-        let left = self.eval_value(l)?;
-        let right = self.eval_value(r)?;
+    fn eval_arithmetic_subtract(&self, left: SubValue, right: SubValue) -> Result<SubValue, EvalError> {
 
         if left.is_null() || right.is_null() {
-            return Err(EvalError::NullInOperation {
-                operation: "subtraction".to_string(),
-                context: "cannot subtract NULL values".to_string(),
-            });
+            self.null_operand("subtraction", "cannot subtract NULL values")?;
+            return Ok(SubValue::Null);
+        }
+
+        // Exact decimal arithmetic, promoting an integer operand
+        if let Some((a, b)) = SubValue::decimal_operands(&left, &right) {
+            return Self::decimal_result(a.checked_sub(b), || ("decimal subtraction", format!("{} - {}", a, b)));
+        }
+
+        if left.is_temporal() || right.is_temporal() {
+            return Self::temporal_arithmetic("subtraction", &left, &right);
         }
 
         match (&left, &right) {
             (SubValue::Integer(a), SubValue::Integer(b)) => {
-                Ok(SubValue::Integer(a - b))
+                self.integer_result(a.checked_sub(*b), a.saturating_sub(*b), *a as f64 - *b as f64, || {
+                    ("subtraction", format!("{} - {}", a, b))
+                })
             }
             (SubValue::Float(a), SubValue::Float(b)) => {
                 Ok(SubValue::Float(a - b))
@@ -640,6 +1904,13 @@ impl<'a> Evaluator<'a> {
             (SubValue::Float(a), SubValue::Integer(b)) => {
                 Ok(SubValue::Float(a - *b as f64))
             }
+            // Floats are approximate already: float - decimal = float
+            (SubValue::Decimal(a), SubValue::Float(b)) => {
+                Ok(SubValue::Float(a.to_f64() - b))
+            }
+            (SubValue::Float(a), SubValue::Decimal(b)) => {
+                Ok(SubValue::Float(a - b.to_f64()))
+            }
             _ => Err(EvalError::TypeError {
                 operation: "subtraction".to_string(),
                 expected: "numeric types".to_string(),
@@ -650,20 +1921,27 @@ impl<'a> Evaluator<'a> {
     }
 
     /// Arithmetic multiplication
-    fn eval_arithmetic_multiply(&self, l: &ValueExpr, r: &ValueExpr) -> Result<SubValue, EvalError> {
-        let left = self.eval_value(l)?;
-        let right = self.eval_value(r)?;
+    fn eval_arithmetic_multiply(&self, left: SubValue, right: SubValue) -> Result<SubValue, EvalError> {
 
         if left.is_null() || right.is_null() {
-            return Err(EvalError::NullInOperation {
-                operation: "multiplication".to_string(),
-                context: "cannot multiply NULL values".to_string(),
-            });
+            self.null_operand("multiplication", "cannot multiply NULL values")?;
+            return Ok(SubValue::Null);
+        }
+
+        // Exact decimal arithmetic, promoting an integer operand
+        if let Some((a, b)) = SubValue::decimal_operands(&left, &right) {
+            return Self::decimal_result(a.checked_mul(b), || ("decimal multiplication", format!("{} * {}", a, b)));
+        }
+
+        if left.is_temporal() || right.is_temporal() {
+            return Self::temporal_arithmetic("multiplication", &left, &right);
         }
 
         match (&left, &right) {
             (SubValue::Integer(a), SubValue::Integer(b)) => {
-                Ok(SubValue::Integer(a * b))
+                self.integer_result(a.checked_mul(*b), a.saturating_mul(*b), *a as f64 * *b as f64, || {
+                    ("multiplication", format!("{} * {}", a, b))
+                })
             }
             (SubValue::Float(a), SubValue::Float(b)) => {
                 Ok(SubValue::Float(a * b))
@@ -674,6 +1952,13 @@ impl<'a> Evaluator<'a> {
             (SubValue::Float(a), SubValue::Integer(b)) => {
                 Ok(SubValue::Float(a * *b as f64))
             }
+            // Floats are approximate already: float * decimal = float
+            (SubValue::Decimal(a), SubValue::Float(b)) => {
+                Ok(SubValue::Float(a.to_f64() * b))
+            }
+            (SubValue::Float(a), SubValue::Decimal(b)) => {
+                Ok(SubValue::Float(a * b.to_f64()))
+            }
             _ => Err(EvalError::TypeError {
                 operation: "multiplication".to_string(),
                 expected: "numeric types".to_string(),
@@ -683,22 +1968,29 @@ impl<'a> Evaluator<'a> {
         }
     }
 
-    /// Division with mandatory float coercion
-    fn eval_arithmetic_divide(&self, l: &ValueExpr, r: &ValueExpr) -> Result<SubValue, EvalError> {
-        let left = self.eval_value(l)?;
-        let right = self.eval_value(r)?;
+    /// Division with mandatory float coercion, unless a decimal is divided by a
+    /// decimal or integer (or the other way round)
+    fn eval_arithmetic_divide(&self, left: SubValue, right: SubValue) -> Result<SubValue, EvalError> {
 
         if left.is_null() || right.is_null() {
-            return Err(EvalError::NullInOperation {
-                operation: "division".to_string(),
-                context: "cannot divide NULL values".to_string(),
-            });
+            self.null_operand("division", "cannot divide NULL values")?;
+            return Ok(SubValue::Null);
+        }
+
+        if let Some((a, b)) = SubValue::decimal_operands(&left, &right) {
+            if b.is_zero() {
+                return Err(EvalError::DivisionByZero {
+                    expression: self.input.to_string(),
+                });
+            }
+            return Self::decimal_result(a.checked_div(b), || ("decimal division", format!("{} / {}", a, b)));
         }
 
         // Convert both to float for division
         let left_float = match left {
             SubValue::Integer(i) => i as f64,
             SubValue::Float(f) => f,
+            SubValue::Decimal(d) => d.to_f64(),
             _ => return Err(EvalError::TypeError {
                 operation: "division".to_string(),
                 expected: "numeric".to_string(),
@@ -710,6 +2002,7 @@ impl<'a> Evaluator<'a> {
         let right_float = match right {
             SubValue::Integer(i) => i as f64,
             SubValue::Float(f) => f,
+            SubValue::Decimal(d) => d.to_f64(),
             _ => return Err(EvalError::TypeError {
                 operation: "division".to_string(),
                 expected: "numeric".to_string(),
@@ -720,7 +2013,7 @@ impl<'a> Evaluator<'a> {
 
         if right_float == 0.0 {
             return Err(EvalError::DivisionByZero {
-                expression: self.input.clone(),
+                expression: self.input.to_string(),
             });
         }
 
@@ -728,30 +2021,36 @@ impl<'a> Evaluator<'a> {
     }
 
     /// Arithmetic modulo
-    fn eval_arithmetic_modulo(&self, l: &ValueExpr, r: &ValueExpr) -> Result<SubValue, EvalError> {
-        let left = self.eval_value(l)?;
-        let right = self.eval_value(r)?;
+    fn eval_arithmetic_modulo(&self, left: SubValue, right: SubValue) -> Result<SubValue, EvalError> {
 
         if left.is_null() || right.is_null() {
-            return Err(EvalError::NullInOperation {
-                operation: "modulo".to_string(),
-                context: "cannot modulo NULL values".to_string(),
-            });
+            self.null_operand("modulo", "cannot modulo NULL values")?;
+            return Ok(SubValue::Null);
+        }
+
+        if let Some((a, b)) = SubValue::decimal_operands(&left, &right) {
+            if b.is_zero() {
+                return Err(EvalError::DivisionByZero {
+                    expression: self.input.to_string(),
+                });
+            }
+            return Self::decimal_result(a.checked_rem(b), || ("decimal modulo", format!("{} % {}", a, b)));
         }
 
         match (&left, &right) {
             (SubValue::Integer(a), SubValue::Integer(b)) => {
                 if *b == 0 {
                     return Err(EvalError::DivisionByZero {
-                        expression: self.input.clone(),
+                        expression: self.input.to_string(),
                     });
                 }
-                Ok(SubValue::Integer(a % b))
+                // The remainder always fits; wrapping_rem gives i64::MIN % -1 = 0 where % would panic
+                Ok(SubValue::Integer(a.wrapping_rem(*b)))
             }
             (SubValue::Float(a), SubValue::Float(b)) => {
                 if *b == 0.0 {
                     return Err(EvalError::DivisionByZero {
-                        expression: self.input.clone(),
+                        expression: self.input.to_string(),
                     });
                 }
                 Ok(SubValue::Float(a % b))
@@ -759,7 +2058,7 @@ impl<'a> Evaluator<'a> {
             (SubValue::Integer(a), SubValue::Float(b)) => {
                 if *b == 0.0 {
                     return Err(EvalError::DivisionByZero {
-                        expression: self.input.clone(),
+                        expression: self.input.to_string(),
                     });
                 }
                 Ok(SubValue::Float((*a as f64) % b))
@@ -767,11 +2066,20 @@ impl<'a> Evaluator<'a> {
             (SubValue::Float(a), SubValue::Integer(b)) => {
                 if *b == 0 {
                     return Err(EvalError::DivisionByZero {
-                        expression: self.input.clone(),
+                        expression: self.input.to_string(),
                     });
                 }
                 Ok(SubValue::Float(a % (*b as f64)))
             }
+            (SubValue::Decimal(_), SubValue::Float(_)) | (SubValue::Float(_), SubValue::Decimal(_)) => {
+                let (a, b) = (Self::to_numeric(&left)?, Self::to_numeric(&right)?);
+                if b == 0.0 {
+                    return Err(EvalError::DivisionByZero {
+                        expression: self.input.to_string(),
+                    });
+                }
+                Ok(SubValue::Float(a % b))
+            }
             _ => Err(EvalError::TypeError {
                 operation: "modulo".to_string(),
                 expected: "numeric types".to_string(),
@@ -781,6 +2089,528 @@ impl<'a> Evaluator<'a> {
         }
     }
 
+    /// CASE: the result of the first branch whose condition is true, or whose
+    /// value equals the operand, or else the ELSE result or NULL.  The operand
+    /// is evaluated once, before the values it is compared with.  The results
+    /// that are literals were checked against each other when parsed; the
+    /// result chosen is checked against them here.  The other results are not
+    /// evaluated, so a mismatch in a result not chosen goes unreported.
+    fn eval_case(&self, operand: Option<&ValueExpr>, branches: &[CaseBranch], else_result: Option<&ValueExpr>)
+        -> Result<SubValue, EvalError>
+    {
+        let operand = operand.map(|operand| self.case_operand(operand)).transpose()?;
+        let mut chosen = else_result;
+        for branch in branches {
+            let matched = match (&branch.when, &operand) {
+                (CaseWhen::Condition(condition), _) => self.eval_boolean(condition)?,
+                (CaseWhen::Value(value), Some(operand)) => self.case_matches(operand, value)?,
+                (CaseWhen::Value(value), None) => return self.locate(value.span(), Err(EvalError::TypeError {
+                    operation: "CASE".to_string(),
+                    expected: "condition".to_string(),
+                    actual: "value".to_string(),
+                    context: "WHEN of a CASE without an operand".to_string(),
+                })),
+            };
+            if matched.is_true() {
+                chosen = Some(&branch.result);
+                break;
+            }
+        }
+        let Some(result) = chosen else {
+            return Ok(SubValue::Null);
+        };
+        let value = self.eval_value(result)?;
+        self.check_case_result(branches, else_result, result, value)
+    }
+
+    /// Evaluate the operand of a simple CASE: its value, or the elements it
+    /// ranges over if it is ANY(...) or ALL(...)
+    fn case_operand(&self, operand: &ValueExpr) -> Result<CaseOperand, EvalError> {
+        match operand {
+            ValueExpr::Quantified(quantifier, collection, span) => {
+                Ok(CaseOperand::Elements(*quantifier, self.quantified_elements(*quantifier, collection, *span)?))
+            }
+            _ => Ok(CaseOperand::Value(self.eval_value(operand)?)),
+        }
+    }
+
+    /// Compare the operand of a simple CASE with a WHEN value by `=`
+    fn case_matches(&self, operand: &CaseOperand, value: &ValueExpr) -> Result<Truth, EvalError> {
+        let equal = |left: &SubValue, right: &SubValue| self.equality_truth(left, right, EqualityOp::Equal);
+        let result = self.step().and_then(|()| match (operand, value) {
+            (CaseOperand::Elements(quantifier, elements), _) => {
+                let value = self.eval_value(value)?;
+                self.quantify(*quantifier, elements.as_deref(), |element| equal(element, &value))
+            }
+            (CaseOperand::Value(operand), ValueExpr::Quantified(quantifier, collection, span)) => {
+                let elements = self.quantified_elements(*quantifier, collection, *span)?;
+                self.quantify(*quantifier, elements.as_deref(), |element| equal(operand, element))
+            }
+            (CaseOperand::Value(operand), _) => equal(operand, &self.eval_value(value)?),
+        });
+        self.locate(value.span(), result)
+    }
+
+    /// Check the value of a CASE's chosen result against the first of its
+    /// results that is a non-NULL literal
+    fn check_case_result(&self, branches: &[CaseBranch], else_result: Option<&ValueExpr>, result: &ValueExpr,
+        value: SubValue) -> Result<SubValue, EvalError>
+    {
+        let literal = branches.iter().map(|branch| &branch.result).chain(else_result)
+            .filter_map(Self::literal_result)
+            .find(|literal| !literal.is_null());
+        match literal {
+            Some(literal) if !value.is_null() && literal.result_kind() != value.result_kind() => {
+                self.locate(result.span(), Err(EvalError::TypeError {
+                    operation: "CASE".to_string(),
+                    expected: literal.result_kind(),
+                    actual: value.type_name(),
+                    context: "result of a branch".to_string(),
+                }))
+            }
+            _ => Ok(value),
+        }
+    }
+
+    /// The value of a CASE result that is a literal, ignoring its sign, which
+    /// does not change its type
+    fn literal_result(result: &ValueExpr) -> Option<SubValue> {
+        match result {
+            ValueExpr::Literal(lit, _) => Some(SubValue::from_literal(lit)),
+            ValueExpr::UnaryPlus(inner, _) | ValueExpr::UnaryMinus(inner, _) => Self::literal_result(inner),
+            _ => None,
+        }
+    }
+
+    /// String concatenation.  At least one operand must be a string; the other
+    /// may be any scalar, which is joined as its text (see
+    /// [`SubValue::concat_text`]).  A NULL operand makes the result NULL, or is
+    /// an error under strict NULL semantics.
+    fn eval_concat(&self, left: SubValue, right: SubValue) -> Result<SubValue, EvalError> {
+
+        if left.is_null() || right.is_null() {
+            self.null_operand("||", "cannot concatenate NULL values")?;
+            return Ok(SubValue::Null);
+        }
+
+        let has_string = matches!(left, SubValue::String(_)) || matches!(right, SubValue::String(_));
+        match (left.concat_text(), right.concat_text()) {
+            (Some(a), Some(b)) if has_string => Ok(SubValue::String(a + &b)),
+            _ => Err(EvalError::TypeError {
+                operation: "||".to_string(),
+                expected: "a string and a string or scalar value".to_string(),
+                actual: format!("{} and {}", left.type_name(), right.type_name()),
+                context: "string concatenation".to_string(),
+            }),
+        }
+    }
+
+    /// Arithmetic on dates, timestamps and intervals:
+    ///
+    /// - date ± interval is a date, or a timestamp if the interval has a time part
+    /// - timestamp ± interval is a timestamp
+    /// - interval ± interval and interval * integer are intervals
+    /// - date - date is an interval of days; other differences of dates and
+    ///   timestamps are intervals of time
+    ///
+    /// Results outside the years 1 to 9999 are overflows whatever the policy.
+    fn temporal_arithmetic(operation: &'static str, left: &SubValue, right: &SubValue) -> Result<SubValue, EvalError> {
+        let result = match (operation, left, right) {
+            ("addition", SubValue::Date(d), SubValue::Interval(i))
+            | ("addition", SubValue::Interval(i), SubValue::Date(d)) => Self::date_plus_interval(*d, *i),
+            ("subtraction", SubValue::Date(d), SubValue::Interval(i)) => {
+                i.checked_neg().and_then(|i| Self::date_plus_interval(*d, i))
+            }
+            ("addition", SubValue::Timestamp(t), SubValue::Interval(i))
+            | ("addition", SubValue::Interval(i), SubValue::Timestamp(t)) => t.checked_add(*i).map(SubValue::Timestamp),
+            ("subtraction", SubValue::Timestamp(t), SubValue::Interval(i)) => t.checked_sub(*i).map(SubValue::Timestamp),
+            ("addition", SubValue::Interval(a), SubValue::Interval(b)) => a.checked_add(*b).map(SubValue::Interval),
+            ("subtraction", SubValue::Interval(a), SubValue::Interval(b)) => a.checked_sub(*b).map(SubValue::Interval),
+            ("multiplication", SubValue::Interval(i), SubValue::Integer(n))
+            | ("multiplication", SubValue::Integer(n), SubValue::Interval(i)) => i.checked_mul(*n).map(SubValue::Interval),
+            ("subtraction", SubValue::Date(a), SubValue::Date(b)) => {
+                Some(SubValue::Interval(Interval::new(0, a.days_since_epoch() - b.days_since_epoch(), 0)))
+            }
+            ("subtraction", SubValue::Date(_) | SubValue::Timestamp(_), SubValue::Date(_) | SubValue::Timestamp(_)) => {
+                left.as_timestamp().zip(right.as_timestamp()).map(|(a, b)| SubValue::Interval(a.since(b)))
+            }
+            _ => return Err(EvalError::TypeError {
+                operation: operation.to_string(),
+                expected: "numeric types, or dates and timestamps with intervals".to_string(),
+                actual: format!("{} and {}", left.type_name(), right.type_name()),
+                context: "arithmetic operation".to_string(),
+            }),
+        };
+        result.ok_or_else(|| {
+            let symbol = match operation {
+                "addition" => "+",
+                "subtraction" => "-",
+                _ => "*",
+            };
+            EvalError::Overflow {
+                operation: format!("date and time {}", operation),
+                expression: format!("{} {} {}", left.describe(), symbol, right.describe()),
+            }
+        })
+    }
+
+    /// A date plus an interval: a date when the interval is whole days, a
+    /// timestamp at midnight UTC plus the interval otherwise
+    fn date_plus_interval(date: Date, interval: Interval) -> Option<SubValue> {
+        if interval.micros() == 0 {
+            date.add_months_and_days(interval.months(), interval.days()).map(SubValue::Date)
+        } else {
+            Timestamp::from(date).checked_add(interval).map(SubValue::Timestamp)
+        }
+    }
+
+    // ========================================================================
+    // PARTIAL EVALUATION
+    // ========================================================================
+
+    /// Fold a boolean expression as far as the bound variables allow
+    fn fold_boolean(&self, expr: &BooleanExpr) -> Result<Folded, EvalError> {
+        match expr {
+            BooleanExpr::Literal(b, _) => Ok(Folded::Known(*b)),
+
+            BooleanExpr::Variable(name, span) => {
+                match self.value_map.resolve(name) {
+                    None => Ok(Folded::Residual(expr.clone())),
+                    Some(RuntimeValue::Null) if self.options.null_semantics == NullSemantics::ThreeValued => {
+                        Ok(Folded::Residual(Self::unknown_stand_in(*span)))
+                    }
+                    // Bound to a boolean, or a type error that no later binding can fix
+                    Some(_) => Ok(Folded::Known(self.eval_boolean(expr)?.is_true())),
+                }
+            }
+
+            BooleanExpr::Path(_, _, span) => {
+                match self.eval_boolean(expr) {
+                    Ok(Truth::Unknown) => Ok(Folded::Residual(Self::unknown_stand_in(*span))),
+                    Ok(truth) => Ok(Folded::Known(truth.is_true())),
+                    Err(EvalError::UnboundVariable { .. }) => Ok(Folded::Residual(expr.clone())),
+                    Err(e) => Err(e),
+                }
+            }
+
+            BooleanExpr::And(..) | BooleanExpr::Or(..) => self.fold_logical_chain(expr),
+
+            BooleanExpr::Not(inner, span) => {
+                Ok(match self.fold_boolean(inner)? {
+                    Folded::Known(b) => Folded::Known(!b),
+                    Folded::Residual(r) => Folded::Residual(BooleanExpr::Not(Box::new(r), *span)),
+                })
+            }
+
+            BooleanExpr::Relational(rel) => {
+                // Fold the operands first, so each is evaluated once, and evaluate
+                // the predicate itself only if they all settled
+                let folded = self.fold_relational(rel)?;
+                if !Self::relational_settled(&folded) {
+                    return Ok(Folded::Residual(BooleanExpr::Relational(Box::new(folded))));
+                }
+                match self.eval_relational(&folded) {
+                    Ok(Truth::True) => Ok(Folded::Known(true)),
+                    Ok(Truth::False) => Ok(Folded::Known(false)),
+                    // Running out of budget is not something a residual can defer
+                    Err(e @ (EvalError::BudgetExceeded { .. } | EvalError::Cancelled)) => Err(e),
+                    // UNKNOWN, an unbound variable, or an error that should only surface
+                    // if the residual is evaluated: keep the predicate with values folded
+                    Ok(Truth::Unknown) | Err(_) => Ok(Folded::Residual(BooleanExpr::Relational(Box::new(folded)))),
+                }
+            }
+        }
+    }
+
+    /// Fold a chain of ANDs and ORs with a loop, as it nests as deep as it is long
+    fn fold_logical_chain(&self, expr: &BooleanExpr) -> Result<Folded, EvalError> {
+        let (first, links) = expr.chain();
+        let mut folded = self.fold_boolean(first)?;
+        for (op, right, span) in links {
+            folded = self.fold_link(op, folded, right, span)?;
+        }
+        Ok(folded)
+    }
+
+    /// Fold one AND or OR of a chain, given its folded left side
+    fn fold_link(&self, op: LogicalOp, l: Folded, right: &BooleanExpr, span: Span) -> Result<Folded, EvalError> {
+        match op {
+            LogicalOp::And => {
+                if let Folded::Known(false) = l {
+                    return Ok(Folded::Known(false));
+                }
+                let r = self.fold_boolean(right)?;
+                Ok(match (l, r) {
+                    (_, Folded::Known(false)) => Folded::Known(false),
+                    (Folded::Known(true), other) | (other, Folded::Known(true)) => other,
+                    (Folded::Residual(l), Folded::Residual(r)) => {
+                        Folded::Residual(BooleanExpr::And(Box::new(l), Box::new(r), span))
+                    }
+                    (Folded::Known(false), _) => unreachable!(),
+                })
+            }
+            LogicalOp::Or => {
+                if let Folded::Known(true) = l {
+                    return Ok(Folded::Known(true));
+                }
+                let r = self.fold_boolean(right)?;
+                Ok(match (l, r) {
+                    (_, Folded::Known(true)) => Folded::Known(true),
+                    (Folded::Known(false), other) | (other, Folded::Known(false)) => other,
+                    (Folded::Residual(l), Folded::Residual(r)) => {
+                        Folded::Residual(BooleanExpr::Or(Box::new(l), Box::new(r), span))
+                    }
+                    (Folded::Known(true), _) => unreachable!(),
+                })
+            }
+        }
+    }
+
+    /// There is no boolean NULL literal, so an UNKNOWN boolean folds to `NULL = NULL`
+    fn unknown_stand_in(span: Span) -> BooleanExpr {
+        BooleanExpr::Relational(Box::new(RelationalExpr::Equality {
+            left: ValueExpr::Literal(ValueLiteral::Null, span),
+            op: EqualityOp::Equal,
+            right: ValueExpr::Literal(ValueLiteral::Null, span),
+            span,
+        }))
+    }
+
+    /// Check whether every operand of a folded relational expression is a
+    /// literal, a variable or path, or ANY/ALL over one, so evaluating the
+    /// predicate computes nothing that folding did not
+    fn relational_settled(expr: &RelationalExpr) -> bool {
+        fn settled(e: &ValueExpr) -> bool {
+            match e {
+                ValueExpr::Literal(..) | ValueExpr::Variable(..) | ValueExpr::Path(..) => true,
+                ValueExpr::Quantified(_, inner, _) => settled(inner),
+                _ => false,
+            }
+        }
+        match expr {
+            RelationalExpr::Equality { left, right, .. } | RelationalExpr::Comparison { left, right, .. } => {
+                settled(left) && settled(right)
+            }
+            RelationalExpr::Like { expr, .. } | RelationalExpr::Regex { expr, .. }
+            | RelationalExpr::IsNull { expr, .. } => settled(expr),
+            RelationalExpr::Between { expr, lower, upper, .. } => settled(expr) && settled(lower) && settled(upper),
+            RelationalExpr::In { expr, values, .. } => settled(expr) && values.iter().all(settled),
+            RelationalExpr::InCollection { expr, collection, .. } => settled(expr) && settled(collection),
+            RelationalExpr::Contains { collection, value, .. } => settled(collection) && settled(value),
+            RelationalExpr::Collated { expr, .. } => Self::relational_settled(expr),
+        }
+    }
+
+    /// Fold the value operands of a relational expression
+    fn fold_relational(&self, expr: &RelationalExpr) -> Result<RelationalExpr, EvalError> {
+        Ok(match expr {
+            RelationalExpr::Equality { left, op, right, span } => RelationalExpr::Equality {
+                left: self.fold_value(left)?,
+                op: *op,
+                right: self.fold_value(right)?,
+                span: *span,
+            },
+            RelationalExpr::Comparison { left, op, right, span } => RelationalExpr::Comparison {
+                left: self.fold_value(left)?,
+                op: *op,
+                right: self.fold_value(right)?,
+                span: *span,
+            },
+            RelationalExpr::Like { expr, pattern, negated, case_insensitive, span } => RelationalExpr::Like {
+                expr: self.fold_value(expr)?,
+                pattern: pattern.clone(),
+                negated: *negated,
+                case_insensitive: *case_insensitive,
+                span: *span,
+            },
+            RelationalExpr::Regex { expr, pattern, negated, span } => RelationalExpr::Regex {
+                expr: self.fold_value(expr)?,
+                pattern: pattern.clone(),
+                negated: *negated,
+                span: *span,
+            },
+            RelationalExpr::Between { expr, lower, upper, negated, span } => RelationalExpr::Between {
+                expr: self.fold_value(expr)?,
+                lower: self.fold_value(lower)?,
+                upper: self.fold_value(upper)?,
+                negated: *negated,
+                span: *span,
+            },
+            RelationalExpr::In { expr, values, negated, span } => RelationalExpr::In {
+                expr: self.fold_value(expr)?,
+                values: values.iter().map(|value| self.fold_value(value)).collect::<Result<_, _>>()?,
+                negated: *negated,
+                span: *span,
+            },
+            RelationalExpr::InCollection { expr, collection, negated, span } => RelationalExpr::InCollection {
+                expr: self.fold_value(expr)?,
+                collection: self.fold_value(collection)?,
+                negated: *negated,
+                span: *span,
+            },
+            RelationalExpr::Contains { collection, value, negated, span } => RelationalExpr::Contains {
+                collection: self.fold_value(collection)?,
+                value: self.fold_value(value)?,
+                negated: *negated,
+                span: *span,
+            },
+            RelationalExpr::IsNull { expr, negated, span } => RelationalExpr::IsNull {
+                expr: self.fold_value(expr)?,
+                negated: *negated,
+                span: *span,
+            },
+            RelationalExpr::Collated { expr, collation, span } => RelationalExpr::Collated {
+                expr: Box::new(self.fold_relational(expr)?),
+                collation: *collation,
+                span: *span,
+            },
+        })
+    }
+
+    /// Fold a value expression bottom-up: bound variables are substituted, and a
+    /// node whose operands all folded to literals is computed and replaced by a
+    /// literal, so no subtree is evaluated more than once.  Nodes that fail to
+    /// evaluate keep their structure, unless evaluation ran out of budget or was
+    /// cancelled.  Lists, maps and non-finite floats have no literal form, so
+    /// variables, paths and nodes that evaluate to them stay as they are.
+    fn fold_value(&self, expr: &ValueExpr) -> Result<ValueExpr, EvalError> {
+        let fold = |e: &ValueExpr| self.fold_value(e).map(Box::new);
+        let folded = match expr {
+            ValueExpr::Literal(..) | ValueExpr::Parameter(..) => return Ok(expr.clone()),
+            ValueExpr::Variable(..) | ValueExpr::Path(..) => expr.clone(),
+            ValueExpr::Add(..)
+            | ValueExpr::Subtract(..)
+            | ValueExpr::Multiply(..)
+            | ValueExpr::Divide(..)
+            | ValueExpr::Modulo(..)
+            | ValueExpr::Concat(..) => return self.fold_binary_chain(expr),
+            ValueExpr::UnaryPlus(e, span) => ValueExpr::UnaryPlus(fold(e)?, *span),
+            ValueExpr::UnaryMinus(e, span) => ValueExpr::UnaryMinus(fold(e)?, *span),
+            ValueExpr::Quantified(quantifier, e, span) => return Ok(ValueExpr::Quantified(*quantifier, fold(e)?, *span)),
+            ValueExpr::FunctionCall { name, args, span } => ValueExpr::FunctionCall {
+                name: name.clone(),
+                args: args.iter().map(|arg| self.fold_value(arg)).collect::<Result<_, _>>()?,
+                span: *span,
+            },
+            ValueExpr::Case { operand, branches, else_result, span } => {
+                return self.fold_case(operand.as_deref(), branches, else_result.as_deref(), *span);
+            }
+        };
+        self.compute_folded(folded)
+    }
+
+    /// Fold a chain of binary operations with a loop, as it nests as deep as it
+    /// is long, computing each link whose operands settle
+    fn fold_binary_chain(&self, expr: &ValueExpr) -> Result<ValueExpr, EvalError> {
+        let (first, links) = expr.chain();
+        let mut folded = self.fold_value(first)?;
+        for (op, right, span) in links {
+            folded = self.compute_folded(ValueExpr::binary(op, folded, self.fold_value(right)?, span))?;
+        }
+        Ok(folded)
+    }
+
+    /// Replace a node whose operands are folded by its value, if it can be
+    /// computed and written as a literal
+    fn compute_folded(&self, folded: ValueExpr) -> Result<ValueExpr, EvalError> {
+        // Variables and paths are leaves; any other operand left unevaluated means
+        // the node cannot be computed yet
+        let settled = |e: &ValueExpr| matches!(e, ValueExpr::Literal(..) | ValueExpr::Variable(..) | ValueExpr::Path(..));
+        let ready = match &folded {
+            ValueExpr::Add(l, r, _) | ValueExpr::Subtract(l, r, _) | ValueExpr::Multiply(l, r, _)
+            | ValueExpr::Divide(l, r, _) | ValueExpr::Modulo(l, r, _) | ValueExpr::Concat(l, r, _) => {
+                settled(l) && settled(r)
+            }
+            ValueExpr::UnaryPlus(e, _) | ValueExpr::UnaryMinus(e, _) => settled(e),
+            ValueExpr::FunctionCall { name, args, .. } => !self.is_volatile(name) && args.iter().all(settled),
+            _ => true,
+        };
+        if ready {
+            match self.eval_value(&folded).map(SubValue::into_literal) {
+                // NaN and infinities would print as variable names in the residual
+                Ok(Some(ValueLiteral::Float(f))) if !f.is_finite() => {}
+                Ok(Some(lit)) => return Ok(ValueExpr::Literal(lit, folded.span())),
+                Ok(None) => {}
+                Err(e @ (EvalError::BudgetExceeded { .. } | EvalError::Cancelled)) => return Err(e),
+                Err(_) => {}
+            }
+        }
+        Ok(folded)
+    }
+
+    /// Fold a CASE: branches whose conditions fold to false, or whose values
+    /// fold to literals that the operand's literal does not equal, are dropped,
+    /// and the first branch that is certain to match makes its result the ELSE
+    /// result of the branches before it
+    fn fold_case(&self, operand: Option<&ValueExpr>, branches: &[CaseBranch], else_result: Option<&ValueExpr>,
+        span: Span) -> Result<ValueExpr, EvalError>
+    {
+        let operand_folded = operand.map(|operand| self.fold_value(operand)).transpose()?;
+        let mut folded = Vec::new();
+        let mut last = else_result;
+        for branch in branches {
+            let when = match &branch.when {
+                CaseWhen::Condition(condition) => match self.fold_boolean(condition) {
+                    Ok(Folded::Known(false)) => continue,
+                    Ok(Folded::Known(true)) => {
+                        last = Some(&branch.result);
+                        break;
+                    }
+                    Ok(Folded::Residual(condition)) => CaseWhen::Condition(condition),
+                    Err(e @ (EvalError::BudgetExceeded { .. } | EvalError::Cancelled)) => return Err(e),
+                    // An error that should only surface if the residual is evaluated
+                    Err(_) => branch.when.clone(),
+                },
+                CaseWhen::Value(value) => {
+                    let value = self.fold_value(value)?;
+                    if let (Some(ValueExpr::Literal(operand, _)), ValueExpr::Literal(lit, _)) = (&operand_folded, &value) {
+                        let compared = self.equality_truth(
+                            &SubValue::from_literal(operand),
+                            &SubValue::from_literal(lit),
+                            EqualityOp::Equal,
+                        );
+                        match compared {
+                            Ok(Truth::True) => {
+                                last = Some(&branch.result);
+                                break;
+                            }
+                            Ok(Truth::False | Truth::Unknown) => continue,
+                            // An error that should only surface if the residual is evaluated
+                            Err(_) => {}
+                        }
+                    }
+                    CaseWhen::Value(value)
+                }
+            };
+            folded.push(CaseBranch { when, result: self.fold_value(&branch.result)? });
+        }
+        let else_result_folded = last.map(|result| self.fold_value(result)).transpose()?;
+        if !folded.is_empty() {
+            return Ok(ValueExpr::Case {
+                operand: operand_folded.map(Box::new),
+                branches: folded,
+                else_result: else_result_folded.map(Box::new),
+                span,
+            });
+        }
+        Ok(match (last, else_result_folded) {
+            // The chosen result must still be of the kind of the CASE's literal results
+            (Some(result), Some(ValueExpr::Literal(ref lit, lit_span))) => {
+                match self.check_case_result(branches, else_result, result, SubValue::from_literal(lit)) {
+                    Ok(_) => ValueExpr::Literal(lit.clone(), lit_span),
+                    Err(_) => ValueExpr::Case {
+                        operand: operand.cloned().map(Box::new),
+                        branches: branches.to_vec(),
+                        else_result: else_result.cloned().map(Box::new),
+                        span,
+                    },
+                }
+            }
+            (_, Some(result)) => result,
+            (_, None) => ValueExpr::Literal(ValueLiteral::Null, span),
+        })
+    }
+
     // ========================================================================
     // HELPER FUNCTIONS
     // ========================================================================
@@ -789,6 +2619,7 @@ impl<'a> Evaluator<'a> {
         match val {
             SubValue::Integer(i) => Ok(*i as f64),
             SubValue::Float(f) => Ok(*f),
+            SubValue::Decimal(d) => Ok(d.to_f64()),
             _ => Err(EvalError::TypeError {
                 operation: "numeric comparison".to_string(),
                 expected: "numeric".to_string(),
@@ -811,6 +2642,11 @@ impl<'a> Evaluator<'a> {
             // Numeric type mixing is allowed
             (SubValue::Integer(_), SubValue::Float(_)) => true,
             (SubValue::Float(_), SubValue::Integer(_)) => true,
+            (SubValue::Decimal(_), SubValue::Integer(_) | SubValue::Float(_) | SubValue::Decimal(_)) => true,
+            (SubValue::Integer(_) | SubValue::Float(_), SubValue::Decimal(_)) => true,
+            // Dates and timestamps mix, intervals only match intervals
+            (SubValue::Date(_) | SubValue::Timestamp(_), SubValue::Date(_) | SubValue::Timestamp(_)) => true,
+            (SubValue::Interval(_), SubValue::Interval(_)) => true,
             // Everything else is incompatible
             _ => false,
         }
@@ -820,8 +2656,14 @@ impl<'a> Evaluator<'a> {
         match rv {
             RuntimeValue::Integer(_) => "integer".to_string(),
             RuntimeValue::Float(_) => "float".to_string(),
+            RuntimeValue::Decimal(_) => "decimal".to_string(),
+            RuntimeValue::Date(_) => "date".to_string(),
+            RuntimeValue::Timestamp(_) => "timestamp".to_string(),
+            RuntimeValue::Interval(_) => "interval".to_string(),
             RuntimeValue::String(_) => "string".to_string(),
             RuntimeValue::Boolean(_) => "boolean".to_string(),
+            RuntimeValue::List(_) => "list".to_string(),
+            RuntimeValue::Map(_) => "map".to_string(),
             RuntimeValue::Null => "NULL".to_string(),
         }
     }
@@ -836,19 +2678,31 @@ impl<'a> Evaluator<'a> {
 enum SubValue {
     Integer(i64),
     Float(f64),
+    Decimal(Decimal),
+    Date(Date),
+    Timestamp(Timestamp),
+    Interval(Interval),
     String(String),
     Boolean(bool),
+    List(Vec<RuntimeValue>),
+    Map(BTreeMap<String, RuntimeValue>),
     Null,
 }
 
 impl SubValue {
     /// Convert from RuntimeValue
-    fn from_runtime(rv: &RuntimeValue) -> Self {
+    fn from_runtime(rv: RuntimeValue) -> Self {
         match rv {
-            RuntimeValue::Integer(i) => SubValue::Integer(*i),
-            RuntimeValue::Float(f) => SubValue::Float(*f),
-            RuntimeValue::String(s) => SubValue::String(s.clone()),
-            RuntimeValue::Boolean(b) => SubValue::Boolean(*b),
+            RuntimeValue::Integer(i) => SubValue::Integer(i),
+            RuntimeValue::Float(f) => SubValue::Float(f),
+            RuntimeValue::Decimal(d) => SubValue::Decimal(d),
+            RuntimeValue::Date(d) => SubValue::Date(d),
+            RuntimeValue::Timestamp(t) => SubValue::Timestamp(t),
+            RuntimeValue::Interval(i) => SubValue::Interval(i),
+            RuntimeValue::String(s) => SubValue::String(s),
+            RuntimeValue::Boolean(b) => SubValue::Boolean(b),
+            RuntimeValue::List(l) => SubValue::List(l),
+            RuntimeValue::Map(m) => SubValue::Map(m),
             RuntimeValue::Null => SubValue::Null,
         }
     }
@@ -858,18 +2712,62 @@ impl SubValue {
         match lit {
             ValueLiteral::Integer(i) => SubValue::Integer(*i),
             ValueLiteral::Float(f) => SubValue::Float(*f),
+            ValueLiteral::Decimal(d) => SubValue::Decimal(*d),
+            ValueLiteral::Date(d) => SubValue::Date(*d),
+            ValueLiteral::Timestamp(t) => SubValue::Timestamp(*t),
+            ValueLiteral::Interval(i) => SubValue::Interval(*i),
             ValueLiteral::String(s) => SubValue::String(s.clone()),
             ValueLiteral::Boolean(b) => SubValue::Boolean(*b),
             ValueLiteral::Null => SubValue::Null,
         }
     }
 
+    /// Convert back into a RuntimeValue (used when calling functions)
+    fn into_runtime(self) -> RuntimeValue {
+        match self {
+            SubValue::Integer(i) => RuntimeValue::Integer(i),
+            SubValue::Float(f) => RuntimeValue::Float(f),
+            SubValue::Decimal(d) => RuntimeValue::Decimal(d),
+            SubValue::Date(d) => RuntimeValue::Date(d),
+            SubValue::Timestamp(t) => RuntimeValue::Timestamp(t),
+            SubValue::Interval(i) => RuntimeValue::Interval(i),
+            SubValue::String(s) => RuntimeValue::String(s),
+            SubValue::Boolean(b) => RuntimeValue::Boolean(b),
+            SubValue::List(l) => RuntimeValue::List(l),
+            SubValue::Map(m) => RuntimeValue::Map(m),
+            SubValue::Null => RuntimeValue::Null,
+        }
+    }
+
+    /// Convert back into a ValueLiteral (used when folding expressions); lists
+    /// and maps have no literal form
+    fn into_literal(self) -> Option<ValueLiteral> {
+        Some(match self {
+            SubValue::Integer(i) => ValueLiteral::Integer(i),
+            SubValue::Float(f) => ValueLiteral::Float(f),
+            SubValue::Decimal(d) => ValueLiteral::Decimal(d),
+            SubValue::Date(d) => ValueLiteral::Date(d),
+            SubValue::Timestamp(t) => ValueLiteral::Timestamp(t),
+            SubValue::Interval(i) => ValueLiteral::Interval(i),
+            SubValue::String(s) => ValueLiteral::String(s),
+            SubValue::Boolean(b) => ValueLiteral::Boolean(b),
+            SubValue::Null => ValueLiteral::Null,
+            SubValue::List(_) | SubValue::Map(_) => return None,
+        })
+    }
+
     fn type_name(&self) -> String {
         match self {
             SubValue::Integer(_) => "integer".to_string(),
             SubValue::Float(_) => "float".to_string(),
+            SubValue::Decimal(_) => "decimal".to_string(),
+            SubValue::Date(_) => "date".to_string(),
+            SubValue::Timestamp(_) => "timestamp".to_string(),
+            SubValue::Interval(_) => "interval".to_string(),
             SubValue::String(_) => "string".to_string(),
             SubValue::Boolean(_) => "boolean".to_string(),
+            SubValue::List(_) => "list".to_string(),
+            SubValue::Map(_) => "map".to_string(),
             SubValue::Null => "NULL".to_string(),
         }
     }
@@ -877,6 +2775,118 @@ impl SubValue {
     fn is_null(&self) -> bool {
         matches!(self, SubValue::Null)
     }
+
+    /// The text a scalar is concatenated as: strings as they are, numbers as
+    /// they are written (decimals keeping their scale), dates, timestamps and
+    /// intervals in the form their literals take, and booleans as `true` or
+    /// `false`.  Lists, maps and NULL have no text.
+    fn concat_text(&self) -> Option<String> {
+        Some(match self {
+            SubValue::String(s) => s.clone(),
+            SubValue::Integer(i) => i.to_string(),
+            SubValue::Float(f) if f.is_nan() => "NaN".to_string(),
+            SubValue::Float(f) if f.is_infinite() => if *f > 0.0 { "Infinity" } else { "-Infinity" }.to_string(),
+            // Debug keeps the point on whole floats, as a float literal displays
+            SubValue::Float(f) => format!("{:?}", f),
+            SubValue::Decimal(d) => d.to_string(),
+            SubValue::Date(d) => d.to_string(),
+            SubValue::Timestamp(t) => t.to_string(),
+            SubValue::Interval(i) => i.to_string(),
+            SubValue::Boolean(b) => b.to_string(),
+            SubValue::List(_) | SubValue::Map(_) | SubValue::Null => return None,
+        })
+    }
+
+    /// The kind of value a CASE result is, where results of one CASE must all be
+    /// the same kind: numbers, dates and timestamps, or else values of one type
+    fn result_kind(&self) -> String {
+        match self {
+            SubValue::Integer(_) | SubValue::Float(_) | SubValue::Decimal(_) => "numeric".to_string(),
+            SubValue::Date(_) | SubValue::Timestamp(_) => "date or timestamp".to_string(),
+            _ => self.type_name(),
+        }
+    }
+
+    /// The value as it would be written in an expression, or its type name if it
+    /// has no literal form
+    fn describe(&self) -> String {
+        self.clone().into_literal().map_or_else(|| self.type_name(), |lit| lit.to_string())
+    }
+
+    /// The value as a decimal, if it is an integer or decimal
+    fn as_decimal(&self) -> Option<Decimal> {
+        match self {
+            SubValue::Integer(i) => Some(Decimal::from(*i)),
+            SubValue::Decimal(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    fn is_temporal(&self) -> bool {
+        matches!(self, SubValue::Date(_) | SubValue::Timestamp(_) | SubValue::Interval(_))
+    }
+
+    /// The value as a timestamp, if it is a date (midnight UTC) or timestamp
+    fn as_timestamp(&self) -> Option<Timestamp> {
+        match self {
+            SubValue::Date(d) => Some(Timestamp::from(*d)),
+            SubValue::Timestamp(t) => Some(*t),
+            _ => None,
+        }
+    }
+
+    /// Exact order of two numbers of any numeric type, following the total
+    /// order of [`numeric`]; `None` unless both are numbers
+    fn numeric_order(&self, other: &SubValue) -> Option<cmp::Ordering> {
+        Some(match (self, other) {
+            (SubValue::Integer(a), SubValue::Integer(b)) => a.cmp(b),
+            (SubValue::Float(a), SubValue::Float(b)) => numeric::cmp_f64(*a, *b),
+            (SubValue::Integer(a), SubValue::Float(b)) => numeric::cmp_i64_f64(*a, *b),
+            (SubValue::Float(a), SubValue::Integer(b)) => numeric::cmp_i64_f64(*b, *a).reverse(),
+            (SubValue::Decimal(a), SubValue::Float(b)) => numeric::cmp_decimal_f64(*a, *b),
+            (SubValue::Float(a), SubValue::Decimal(b)) => numeric::cmp_decimal_f64(*b, *a).reverse(),
+            _ => self.as_decimal()?.cmp(&other.as_decimal()?),
+        })
+    }
+
+    /// Order of two values that BETWEEN can range over: numbers, strings under
+    /// a collation, or the temporal pairs of [`SubValue::temporal_order`]
+    fn value_order(&self, other: &SubValue, collation: Collation) -> Option<cmp::Ordering> {
+        match (self, other) {
+            (SubValue::String(a), SubValue::String(b)) => Some(collation.compare(a, b)),
+            _ => self.numeric_order(other).or_else(|| self.temporal_order(other)),
+        }
+    }
+
+    /// Order of two dates or timestamps, or of two intervals; `None` for any
+    /// other pair
+    fn temporal_order(&self, other: &SubValue) -> Option<cmp::Ordering> {
+        match (self, other) {
+            (SubValue::Date(a), SubValue::Date(b)) => Some(a.cmp(b)),
+            (SubValue::Interval(a), SubValue::Interval(b)) => Some(a.cmp(b)),
+            _ => Some(self.as_timestamp()?.cmp(&other.as_timestamp()?)),
+        }
+    }
+
+    /// Both operands as decimals, if one is a decimal and the other an integer
+    /// or decimal
+    fn decimal_operands(left: &SubValue, right: &SubValue) -> Option<(Decimal, Decimal)> {
+        if !matches!(left, SubValue::Decimal(_)) && !matches!(right, SubValue::Decimal(_)) {
+            return None;
+        }
+        Some((left.as_decimal()?, right.as_decimal()?))
+    }
 }
 
+/// The operand of a simple CASE, evaluated once for all its WHEN values
+enum CaseOperand {
+    Value(SubValue),
+    /// The elements of ANY(...) or ALL(...), `None` if the collection is NULL
+    Elements(Quantifier, Option<Vec<SubValue>>),
+}
 
+/// Partially evaluated boolean expression
+enum Folded {
+    Known(bool),
+    Residual(BooleanExpr),
+}
@@ -0,0 +1,679 @@
+//! Scalar functions for SQL Expression Evaluation
+//!
+//! This module defines the library of scalar functions that can be called from
+//! value expressions, e.g. `UPPER(name) = 'BOB'` or `COALESCE(region, 'us') = 'eu'`,
+//! and the `FunctionRegistry` through which applications add their own functions.
+//! Function names are case-insensitive.  The parser checks the number of arguments
+//! of every call at parse time; argument types are checked at evaluation time and
+//! reported as `EvalError::TypeError`.
+//!
+//! Functions operate on `RuntimeValue`s.  Unless a function is marked as accepting
+//! NULL arguments (COALESCE, NULLIF), the evaluator handles NULL arguments before
+//! the function is called, applying the active NULL semantics.
+
+use crate::decimal::Decimal;
+use crate::evaluator::{EvalError, OverflowPolicy, RuntimeValue};
+use crate::numeric;
+use crate::temporal::Timestamp;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Signature shared by all built-in function implementations.  Functions with
+/// integer results apply the overflow policy of the evaluation.
+type BuiltinFn = fn(&[RuntimeValue], OverflowPolicy) -> Result<RuntimeValue, EvalError>;
+
+/// A built-in scalar function
+pub(crate) struct Builtin {
+    /// Canonical (upper case) function name
+    pub name: &'static str,
+    /// Minimum number of arguments
+    pub min_args: usize,
+    /// Maximum number of arguments, `None` for variadic functions
+    pub max_args: Option<usize>,
+    /// Whether the function receives NULL arguments instead of the evaluator
+    /// handling them
+    pub accepts_null: bool,
+    /// Whether the result can differ between calls with the same arguments, so
+    /// partial evaluation must leave calls in the residual
+    pub volatile: bool,
+    eval: BuiltinFn,
+}
+
+impl Builtin {
+    /// Call the function with already evaluated arguments
+    pub fn call(&self, args: &[RuntimeValue], overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+        (self.eval)(args, overflow)
+    }
+
+    /// Check whether the function accepts the given number of arguments
+    pub fn accepts_arity(&self, count: usize) -> bool {
+        count >= self.min_args && self.max_args.is_none_or(|max| count <= max)
+    }
+
+    /// Describe the accepted number of arguments for error messages
+    pub fn arity_description(&self) -> String {
+        match self.max_args {
+            Some(max) if max == self.min_args => format!("{}", max),
+            Some(max) => format!("{} to {}", self.min_args, max),
+            None => format!("at least {}", self.min_args),
+        }
+    }
+}
+
+/// Look up a built-in function by name (case-insensitive)
+pub(crate) fn lookup_builtin(name: &str) -> Option<&'static Builtin> {
+    BUILTINS.iter().find(|b| b.name.eq_ignore_ascii_case(name))
+}
+
+const fn builtin(name: &'static str, min_args: usize, max_args: Option<usize>, eval: BuiltinFn) -> Builtin {
+    Builtin { name, min_args, max_args, accepts_null: false, volatile: false, eval }
+}
+
+static BUILTINS: &[Builtin] = &[
+    // String functions
+    builtin("UPPER", 1, Some(1), fn_upper),
+    builtin("LOWER", 1, Some(1), fn_lower),
+    builtin("LENGTH", 1, Some(1), fn_length),
+    builtin("TRIM", 1, Some(1), fn_trim),
+    builtin("LTRIM", 1, Some(1), fn_ltrim),
+    builtin("RTRIM", 1, Some(1), fn_rtrim),
+    builtin("SUBSTRING", 2, Some(3), fn_substring),
+    builtin("REPLACE", 3, Some(3), fn_replace),
+    builtin("CONCAT", 1, None, fn_concat),
+
+    // Math functions
+    builtin("ABS", 1, Some(1), fn_abs),
+    builtin("ROUND", 1, Some(2), fn_round),
+    builtin("FLOOR", 1, Some(1), fn_floor),
+    builtin("CEIL", 1, Some(1), fn_ceil),
+    builtin("CEILING", 1, Some(1), fn_ceil),
+    builtin("SIGN", 1, Some(1), fn_sign),
+    builtin("SQRT", 1, Some(1), fn_sqrt),
+    builtin("POWER", 2, Some(2), fn_power),
+    builtin("MOD", 2, Some(2), fn_mod),
+
+    // Date and time functions
+    Builtin { name: "NOW", min_args: 0, max_args: Some(0), accepts_null: false, volatile: true, eval: fn_now },
+
+    // Collection functions
+    builtin("CARDINALITY", 1, Some(1), fn_cardinality),
+
+    // NULL handling functions
+    Builtin { name: "COALESCE", min_args: 1, max_args: None, accepts_null: true, volatile: false, eval: fn_coalesce },
+    Builtin { name: "NULLIF", min_args: 2, max_args: Some(2), accepts_null: true, volatile: false, eval: fn_nullif },
+];
+
+// ============================================================================
+// ARGUMENT HELPERS
+// ============================================================================
+
+fn type_name(val: &RuntimeValue) -> String {
+    match val {
+        RuntimeValue::Integer(_) => "integer".to_string(),
+        RuntimeValue::Float(_) => "float".to_string(),
+        RuntimeValue::Decimal(_) => "decimal".to_string(),
+        RuntimeValue::Date(_) => "date".to_string(),
+        RuntimeValue::Timestamp(_) => "timestamp".to_string(),
+        RuntimeValue::Interval(_) => "interval".to_string(),
+        RuntimeValue::String(_) => "string".to_string(),
+        RuntimeValue::Boolean(_) => "boolean".to_string(),
+        RuntimeValue::List(_) => "list".to_string(),
+        RuntimeValue::Map(_) => "map".to_string(),
+        RuntimeValue::Null => "NULL".to_string(),
+    }
+}
+
+fn arg_type_error(function: &str, expected: &str, index: usize, actual: &RuntimeValue) -> EvalError {
+    EvalError::TypeError {
+        operation: function.to_string(),
+        expected: expected.to_string(),
+        actual: type_name(actual),
+        context: format!("argument {}", index + 1),
+    }
+}
+
+fn string_arg<'a>(function: &str, args: &'a [RuntimeValue], index: usize) -> Result<&'a str, EvalError> {
+    match &args[index] {
+        RuntimeValue::String(s) => Ok(s),
+        other => Err(arg_type_error(function, "string", index, other)),
+    }
+}
+
+fn integer_arg(function: &str, args: &[RuntimeValue], index: usize) -> Result<i64, EvalError> {
+    match &args[index] {
+        RuntimeValue::Integer(i) => Ok(*i),
+        other => Err(arg_type_error(function, "integer", index, other)),
+    }
+}
+
+/// Integer or decimal argument as a decimal; other values are zero
+fn decimal_arg(value: &RuntimeValue) -> Decimal {
+    match value {
+        RuntimeValue::Integer(i) => Decimal::from(*i),
+        RuntimeValue::Decimal(d) => *d,
+        _ => Decimal::ZERO,
+    }
+}
+
+fn float_arg(function: &str, args: &[RuntimeValue], index: usize) -> Result<f64, EvalError> {
+    match &args[index] {
+        RuntimeValue::Integer(i) => Ok(*i as f64),
+        RuntimeValue::Float(f) => Ok(*f),
+        RuntimeValue::Decimal(d) => Ok(d.to_f64()),
+        other => Err(arg_type_error(function, "numeric", index, other)),
+    }
+}
+
+// ============================================================================
+// STRING FUNCTIONS
+// ============================================================================
+
+fn fn_upper(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    Ok(RuntimeValue::String(string_arg("UPPER", args, 0)?.to_uppercase()))
+}
+
+fn fn_lower(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    Ok(RuntimeValue::String(string_arg("LOWER", args, 0)?.to_lowercase()))
+}
+
+/// Length in characters, not bytes
+fn fn_length(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    Ok(RuntimeValue::Integer(string_arg("LENGTH", args, 0)?.chars().count() as i64))
+}
+
+fn fn_trim(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    Ok(RuntimeValue::String(string_arg("TRIM", args, 0)?.trim().to_string()))
+}
+
+fn fn_ltrim(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    Ok(RuntimeValue::String(string_arg("LTRIM", args, 0)?.trim_start().to_string()))
+}
+
+fn fn_rtrim(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    Ok(RuntimeValue::String(string_arg("RTRIM", args, 0)?.trim_end().to_string()))
+}
+
+/// SUBSTRING(s, start [, length]) with a 1-based start position.  As in SQL, a
+/// start position before the first character shortens the requested length.
+fn fn_substring(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    let s = string_arg("SUBSTRING", args, 0)?;
+    let start = integer_arg("SUBSTRING", args, 1)?;
+    let end = match args.get(2) {
+        Some(_) => {
+            let length = integer_arg("SUBSTRING", args, 2)?;
+            if length < 0 {
+                return Err(EvalError::TypeError {
+                    operation: "SUBSTRING".to_string(),
+                    expected: "non-negative length".to_string(),
+                    actual: length.to_string(),
+                    context: "argument 3".to_string(),
+                });
+            }
+            Some(start.saturating_add(length))
+        }
+        None => None,
+    };
+
+    let char_count = s.chars().count() as i64;
+    let from = start.clamp(1, char_count + 1) - 1;
+    let to = end.map_or(char_count, |e| e.clamp(1, char_count + 1) - 1);
+    let result = s.chars()
+        .skip(from as usize)
+        .take(to.saturating_sub(from).max(0) as usize)
+        .collect();
+    Ok(RuntimeValue::String(result))
+}
+
+fn fn_replace(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    let s = string_arg("REPLACE", args, 0)?;
+    let from = string_arg("REPLACE", args, 1)?;
+    let to = string_arg("REPLACE", args, 2)?;
+    if from.is_empty() {
+        return Ok(RuntimeValue::String(s.to_string()));
+    }
+    Ok(RuntimeValue::String(s.replace(from, to)))
+}
+
+fn fn_concat(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    let mut result = String::new();
+    for i in 0..args.len() {
+        result.push_str(string_arg("CONCAT", args, i)?);
+    }
+    Ok(RuntimeValue::String(result))
+}
+
+// ============================================================================
+// MATH FUNCTIONS
+// ============================================================================
+
+fn fn_abs(args: &[RuntimeValue], overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    match &args[0] {
+        RuntimeValue::Integer(i) => {
+            overflow.resolve(i.checked_abs(), i.saturating_abs(), (*i as f64).abs(), || {
+                EvalError::Overflow { operation: "ABS".to_string(), expression: format!("ABS({})", i) }
+            })
+        }
+        RuntimeValue::Float(f) => Ok(RuntimeValue::Float(f.abs())),
+        RuntimeValue::Decimal(d) => Ok(RuntimeValue::Decimal(d.abs())),
+        other => Err(arg_type_error("ABS", "numeric", 0, other)),
+    }
+}
+
+/// ROUND(x [, digits]) rounds half away from zero.  Integers are returned unchanged
+/// unless a negative number of digits is requested; decimals are rounded exactly.
+fn fn_round(args: &[RuntimeValue], overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    let digits = match args.get(1) {
+        Some(_) => integer_arg("ROUND", args, 1)?,
+        None => 0,
+    };
+    match &args[0] {
+        RuntimeValue::Integer(i) if digits >= 0 => Ok(RuntimeValue::Integer(*i)),
+        RuntimeValue::Integer(i) => {
+            let rounded = round_integer(*i, digits.unsigned_abs());
+            let saturated = if rounded < 0 { i64::MIN } else { i64::MAX };
+            overflow.resolve(i64::try_from(rounded).ok(), saturated, rounded as f64, || {
+                EvalError::Overflow { operation: "ROUND".to_string(), expression: format!("ROUND({}, {})", i, digits) }
+            })
+        }
+        RuntimeValue::Float(f) => Ok(RuntimeValue::Float(round_float(*f, digits))),
+        RuntimeValue::Decimal(d) => match d.round_dp(digits) {
+            Some(rounded) => Ok(RuntimeValue::Decimal(rounded)),
+            None => Err(EvalError::Overflow { operation: "ROUND".to_string(), expression: format!("ROUND({}, {})", d, digits) }),
+        },
+        other => Err(arg_type_error("ROUND", "numeric", 0, other)),
+    }
+}
+
+/// Round an integer half away from zero to a multiple of `10^places`.  The result
+/// is computed exactly in i128; it only leaves the i64 range for the few values
+/// that round up past i64::MAX or down past i64::MIN.
+fn round_integer(value: i64, places: u64) -> i128 {
+    // |value| < 5 * 10^19, so any coarser rounding gives zero
+    if places > 19 {
+        return 0;
+    }
+    let factor = 10i128.pow(places as u32);
+    let value = value as i128;
+    let quotient = value / factor;
+    let remainder = value % factor;
+    let quotient = if 2 * remainder.abs() >= factor { quotient + value.signum() } else { quotient };
+    quotient * factor
+}
+
+/// Round a float half away from zero to `digits` decimal places.  Rounding at a
+/// place finer than the float can represent leaves it unchanged, and rounding at a
+/// place coarser than any finite float gives a zero of the same sign.
+fn round_float(value: f64, digits: i64) -> f64 {
+    if !value.is_finite() {
+        return value;
+    }
+    // 10^400 is already infinite; the clamp only keeps powi's argument in range
+    let factor = 10f64.powi(digits.unsigned_abs().min(400) as i32);
+    if digits >= 0 {
+        let scaled = value * factor;
+        // From 2^52 on a float has no fractional digits left to round away
+        if !scaled.is_finite() || scaled.abs() >= 2f64.powi(52) {
+            return value;
+        }
+        scaled.round() / factor
+    } else if factor.is_infinite() {
+        0.0f64.copysign(value)
+    } else {
+        (value / factor).round() * factor
+    }
+}
+
+fn fn_floor(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    match &args[0] {
+        RuntimeValue::Integer(i) => Ok(RuntimeValue::Integer(*i)),
+        RuntimeValue::Float(f) => Ok(RuntimeValue::Float(f.floor())),
+        RuntimeValue::Decimal(d) => Ok(RuntimeValue::Decimal(d.floor())),
+        other => Err(arg_type_error("FLOOR", "numeric", 0, other)),
+    }
+}
+
+fn fn_ceil(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    match &args[0] {
+        RuntimeValue::Integer(i) => Ok(RuntimeValue::Integer(*i)),
+        RuntimeValue::Float(f) => Ok(RuntimeValue::Float(f.ceil())),
+        RuntimeValue::Decimal(d) => Ok(RuntimeValue::Decimal(d.ceil())),
+        other => Err(arg_type_error("CEIL", "numeric", 0, other)),
+    }
+}
+
+fn fn_sign(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    match &args[0] {
+        RuntimeValue::Integer(i) => Ok(RuntimeValue::Integer(i.signum())),
+        // NaN has no sign, so it stays NaN
+        RuntimeValue::Float(f) if f.is_nan() => Ok(RuntimeValue::Float(f64::NAN)),
+        RuntimeValue::Float(f) if *f == 0.0 => Ok(RuntimeValue::Integer(0)),
+        RuntimeValue::Float(f) => Ok(RuntimeValue::Integer(if *f > 0.0 { 1 } else { -1 })),
+        RuntimeValue::Decimal(d) => Ok(RuntimeValue::Integer(d.signum())),
+        other => Err(arg_type_error("SIGN", "numeric", 0, other)),
+    }
+}
+
+fn fn_sqrt(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    let x = float_arg("SQRT", args, 0)?;
+    if x < 0.0 {
+        return Err(EvalError::TypeError {
+            operation: "SQRT".to_string(),
+            expected: "non-negative number".to_string(),
+            actual: x.to_string(),
+            context: "argument 1".to_string(),
+        });
+    }
+    Ok(RuntimeValue::Float(x.sqrt()))
+}
+
+fn fn_power(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    let base = float_arg("POWER", args, 0)?;
+    let exponent = float_arg("POWER", args, 1)?;
+    Ok(RuntimeValue::Float(base.powf(exponent)))
+}
+
+/// MOD(a, b) follows the `%` operator: integer for integer operands, decimal for
+/// decimal and integer operands, float otherwise
+fn fn_mod(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    match (&args[0], &args[1]) {
+        (RuntimeValue::Integer(_), RuntimeValue::Integer(0)) => Err(EvalError::DivisionByZero {
+            expression: "MOD".to_string(),
+        }),
+        // The remainder always fits; wrapping_rem gives i64::MIN % -1 = 0 where % would panic
+        (RuntimeValue::Integer(a), RuntimeValue::Integer(b)) => Ok(RuntimeValue::Integer(a.wrapping_rem(*b))),
+        (RuntimeValue::Decimal(_), RuntimeValue::Integer(_) | RuntimeValue::Decimal(_))
+        | (RuntimeValue::Integer(_), RuntimeValue::Decimal(_)) => {
+            let (a, b) = (decimal_arg(&args[0]), decimal_arg(&args[1]));
+            // The remainder is never larger than the dividend, so only a zero divisor fails
+            a.checked_rem(b).map(RuntimeValue::Decimal).ok_or_else(|| EvalError::DivisionByZero {
+                expression: "MOD".to_string(),
+            })
+        }
+        _ => {
+            let a = float_arg("MOD", args, 0)?;
+            let b = float_arg("MOD", args, 1)?;
+            if b == 0.0 {
+                return Err(EvalError::DivisionByZero {
+                    expression: "MOD".to_string(),
+                });
+            }
+            Ok(RuntimeValue::Float(a % b))
+        }
+    }
+}
+
+// ============================================================================
+// DATE AND TIME FUNCTIONS
+// ============================================================================
+
+/// The current time in UTC, read from the system clock on every call
+fn fn_now(_args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    Ok(RuntimeValue::Timestamp(Timestamp::now()))
+}
+
+// ============================================================================
+// COLLECTION FUNCTIONS
+// ============================================================================
+
+/// Number of elements of a list or entries of a map
+fn fn_cardinality(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    let count = match &args[0] {
+        RuntimeValue::List(items) => items.len(),
+        RuntimeValue::Map(entries) => entries.len(),
+        other => return Err(arg_type_error("CARDINALITY", "list or map", 0, other)),
+    };
+    Ok(RuntimeValue::Integer(count as i64))
+}
+
+// ============================================================================
+// NULL HANDLING FUNCTIONS
+// ============================================================================
+
+/// First non-NULL argument, or NULL if all arguments are NULL
+fn fn_coalesce(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    Ok(args.iter()
+        .find(|v| !matches!(v, RuntimeValue::Null))
+        .cloned()
+        .unwrap_or(RuntimeValue::Null))
+}
+
+/// NULL if both arguments are equal, otherwise the first argument
+fn fn_nullif(args: &[RuntimeValue], _overflow: OverflowPolicy) -> Result<RuntimeValue, EvalError> {
+    let equal = match (&args[0], &args[1]) {
+        (RuntimeValue::Float(a), RuntimeValue::Float(b)) => numeric::cmp_f64(*a, *b).is_eq(),
+        (RuntimeValue::Integer(a), RuntimeValue::Float(b)) => numeric::cmp_i64_f64(*a, *b).is_eq(),
+        (RuntimeValue::Float(a), RuntimeValue::Integer(b)) => numeric::cmp_i64_f64(*b, *a).is_eq(),
+        (RuntimeValue::Decimal(a), RuntimeValue::Integer(b)) => *a == Decimal::from(*b),
+        (RuntimeValue::Integer(a), RuntimeValue::Decimal(b)) => Decimal::from(*a) == *b,
+        (RuntimeValue::Decimal(a), RuntimeValue::Float(b)) => numeric::cmp_decimal_f64(*a, *b).is_eq(),
+        (RuntimeValue::Float(a), RuntimeValue::Decimal(b)) => numeric::cmp_decimal_f64(*b, *a).is_eq(),
+        (RuntimeValue::Date(a), RuntimeValue::Timestamp(b)) => Timestamp::from(*a) == *b,
+        (RuntimeValue::Timestamp(a), RuntimeValue::Date(b)) => *a == Timestamp::from(*b),
+        (RuntimeValue::Null, _) | (_, RuntimeValue::Null) => false,
+        (a, b) => a == b,
+    };
+    Ok(if equal { RuntimeValue::Null } else { args[0].clone() })
+}
+
+// ============================================================================
+// USER-DEFINED FUNCTIONS
+// ============================================================================
+
+/// Declared type of a user-defined function's parameter or return value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    Integer,
+    /// Integer and decimal arguments are converted to float before the function
+    /// is called
+    Float,
+    /// Integer arguments are converted to decimal before the function is called
+    Decimal,
+    /// Integer, float or decimal
+    Numeric,
+    String,
+    Boolean,
+    Date,
+    /// Date arguments are converted to midnight UTC before the function is called
+    Timestamp,
+    Interval,
+    /// Any non-NULL value
+    Any,
+}
+
+impl ValueType {
+    /// Check whether a non-NULL value has this type
+    fn matches(self, value: &RuntimeValue) -> bool {
+        matches!(
+            (self, value),
+            (ValueType::Any, _)
+                | (ValueType::Integer, RuntimeValue::Integer(_))
+                | (ValueType::Float, RuntimeValue::Integer(_) | RuntimeValue::Float(_) | RuntimeValue::Decimal(_))
+                | (ValueType::Decimal, RuntimeValue::Integer(_) | RuntimeValue::Decimal(_))
+                | (ValueType::Numeric, RuntimeValue::Integer(_) | RuntimeValue::Float(_) | RuntimeValue::Decimal(_))
+                | (ValueType::String, RuntimeValue::String(_))
+                | (ValueType::Boolean, RuntimeValue::Boolean(_))
+                | (ValueType::Date, RuntimeValue::Date(_))
+                | (ValueType::Timestamp, RuntimeValue::Date(_) | RuntimeValue::Timestamp(_))
+                | (ValueType::Interval, RuntimeValue::Interval(_))
+        )
+    }
+
+    /// Convert a value of this type to its declared representation
+    fn convert(self, value: &RuntimeValue) -> RuntimeValue {
+        match (self, value) {
+            (ValueType::Float, RuntimeValue::Integer(n)) => RuntimeValue::Float(*n as f64),
+            (ValueType::Float, RuntimeValue::Decimal(d)) => RuntimeValue::Float(d.to_f64()),
+            (ValueType::Decimal, RuntimeValue::Integer(n)) => RuntimeValue::Decimal(Decimal::from(*n)),
+            (ValueType::Timestamp, RuntimeValue::Date(d)) => RuntimeValue::Timestamp(Timestamp::from(*d)),
+            _ => value.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueType::Integer => write!(f, "integer"),
+            ValueType::Float => write!(f, "float"),
+            ValueType::Decimal => write!(f, "decimal"),
+            ValueType::Numeric => write!(f, "numeric"),
+            ValueType::String => write!(f, "string"),
+            ValueType::Boolean => write!(f, "boolean"),
+            ValueType::Date => write!(f, "date"),
+            ValueType::Timestamp => write!(f, "timestamp"),
+            ValueType::Interval => write!(f, "interval"),
+            ValueType::Any => write!(f, "any"),
+        }
+    }
+}
+
+/// Signature shared by all user-defined function implementations
+type UserFn = dyn Fn(&[RuntimeValue]) -> Result<RuntimeValue, String> + Send + Sync;
+
+/// A function registered in a `FunctionRegistry`
+pub(crate) struct UserFunction {
+    /// Function name as registered
+    pub name: String,
+    /// Declared parameter types; the function takes exactly this many arguments
+    pub params: Vec<ValueType>,
+    /// Declared return type; NULL may always be returned
+    pub returns: ValueType,
+    /// Whether the result can differ between calls with the same arguments
+    pub volatile: bool,
+    eval: Box<UserFn>,
+}
+
+impl UserFunction {
+    /// Check argument types, call the function and check its result.  Arguments
+    /// must not be NULL; the evaluator handles NULL before calling.
+    pub fn call(&self, args: &[RuntimeValue]) -> Result<RuntimeValue, EvalError> {
+        let args = args.iter().zip(&self.params).enumerate()
+            .map(|(i, (arg, param))| match (param, arg) {
+                _ if param.matches(arg) => Ok(param.convert(arg)),
+                _ => Err(arg_type_error(&self.name, &param.to_string(), i, arg)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let result = (self.eval)(&args).map_err(|message| EvalError::FunctionError {
+            name: self.name.clone(),
+            message,
+        })?;
+
+        match (self.returns, &result) {
+            (_, RuntimeValue::Null) => Ok(result),
+            (returns, value) if returns.matches(value) => Ok(returns.convert(value)),
+            (returns, value) => Err(EvalError::FunctionError {
+                name: self.name.clone(),
+                message: format!("declared to return {}, returned {}", returns, type_name(value)),
+            }),
+        }
+    }
+}
+
+/// Application-defined functions that expressions can call like built-ins.
+///
+/// Each function is registered with its parameter and return types.  Pass the
+/// registry to [`parse_with_functions`](crate::parser::parse_with_functions) or
+/// [`CompiledExpr::compile_with_functions`](crate::CompiledExpr::compile_with_functions)
+/// so calls are checked at parse time, and to [`EvalOptions::functions`](crate::EvalOptions::functions)
+/// so they can be evaluated.  Function names are case-insensitive, and a registered
+/// function takes precedence over a built-in function of the same name.
+///
+/// As with built-ins, the function is not called when an argument is NULL: under
+/// strict NULL semantics this is an error, under three-valued semantics the call
+/// yields NULL.  An `Err` returned by the function is reported as
+/// [`EvalError::FunctionError`].
+///
+/// Partial evaluation computes calls whose arguments are all known.  Functions
+/// whose result can change between calls with the same arguments, such as a
+/// clock or a feature flag lookup, are registered with
+/// [`register_volatile`](FunctionRegistry::register_volatile) so their calls
+/// stay in the residual expression instead.
+///
+/// Cloning a registry is cheap; the functions themselves are shared.
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use sqlexpr_rust::{CompiledExpr, EvalOptions, FunctionRegistry, RuntimeValue, ValueType};
+///
+/// let mut functions = FunctionRegistry::new();
+/// functions.register("BUCKET", &[ValueType::String, ValueType::Integer], ValueType::Integer, |args| {
+///     match (&args[0], &args[1]) {
+///         (RuntimeValue::String(s), RuntimeValue::Integer(n)) if *n > 0 => {
+///             Ok(RuntimeValue::Integer(s.bytes().map(i64::from).sum::<i64>() % n))
+///         }
+///         _ => Err("bucket count must be positive".to_string()),
+///     }
+/// });
+///
+/// let expr = CompiledExpr::compile_with_functions("bucket(user_id, 4) = 2", &functions).unwrap();
+/// let options = EvalOptions::new().functions(functions);
+///
+/// let mut map = HashMap::new();
+/// map.insert("user_id".to_string(), RuntimeValue::String("ab".to_string()));
+/// assert_eq!(expr.eval_with(&map, &options).unwrap(), false);
+/// ```
+#[derive(Clone, Default)]
+pub struct FunctionRegistry {
+    functions: HashMap<String, Arc<UserFunction>>,
+}
+
+impl FunctionRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a function taking arguments of the given types.  Registering a
+    /// name again replaces the earlier function.
+    pub fn register<F>(&mut self, name: &str, params: &[ValueType], returns: ValueType, function: F) -> &mut Self
+    where
+        F: Fn(&[RuntimeValue]) -> Result<RuntimeValue, String> + Send + Sync + 'static,
+    {
+        self.insert(name, params, returns, false, Box::new(function))
+    }
+
+    /// Register a function whose result can differ between calls with the same
+    /// arguments.  Partial evaluation never computes its calls.
+    pub fn register_volatile<F>(&mut self, name: &str, params: &[ValueType], returns: ValueType, function: F)
+        -> &mut Self
+    where
+        F: Fn(&[RuntimeValue]) -> Result<RuntimeValue, String> + Send + Sync + 'static,
+    {
+        self.insert(name, params, returns, true, Box::new(function))
+    }
+
+    fn insert(&mut self, name: &str, params: &[ValueType], returns: ValueType, volatile: bool, eval: Box<UserFn>)
+        -> &mut Self
+    {
+        self.functions.insert(name.to_uppercase(), Arc::new(UserFunction {
+            name: name.to_string(),
+            params: params.to_vec(),
+            returns,
+            volatile,
+            eval,
+        }));
+        self
+    }
+
+    /// Check whether a function with this name is registered (case-insensitive)
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions.contains_key(&name.to_uppercase())
+    }
+
+    /// Look up a registered function by name (case-insensitive)
+    pub(crate) fn get(&self, name: &str) -> Option<&UserFunction> {
+        self.functions.get(&name.to_uppercase()).map(Arc::as_ref)
+    }
+}
+
+impl fmt::Debug for FunctionRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&str> = self.functions.values().map(|u| u.name.as_str()).collect();
+        names.sort_unstable();
+        f.debug_struct("FunctionRegistry").field("functions", &names).finish()
+    }
+}
@@ -3,12 +3,17 @@
 //! This internal module handles tokenization of SQL expressions, including:
 //!  - Case-insensitive keywords
 //!  - String literals with SQL-style escaping
-//!  - Numeric literals (decimal, hex, octal, floating-point)
+//!  - Numeric literals (decimal, hex, octal, floating-point, and exact decimals
+//!    in decimal literal mode)
+//!  - Parameter placeholders (`?` and `:name`)
 //! - Comments (line and block)
 //! - Whitespace handling
 
 use std::fmt;
 
+use crate::decimal::Decimal;
+use crate::span::Span;
+
 /// Token types for SQL Expression Parser.
 /// Includes keywords, operators, literals, and delimiters.
 
@@ -39,17 +44,26 @@ pub enum Token {
     Star,               // *
     Slash,              // /
     Percent,            // %
+    Concat,             // ||
 
     // Delimiters
     LeftParen,          // (
     RightParen,         // )
     Comma,              // ,
+    Dot,                // .
+    LeftBracket,        // [
+    RightBracket,       // ]
 
     // Literals
     Identifier(String),
     StringLiteral(String),
     IntegerLiteral(i64),
     FloatLiteral(f64),
+    DecimalLiteral(Decimal),
+
+    // Parameter placeholders
+    PositionalParameter(usize),     // ?, numbered from 1 in order of appearance
+    NamedParameter(String),         // :name
 
     // End of input
     Eof,
@@ -80,23 +94,75 @@ impl fmt::Display for Token {
             Token::Star => write!(f, "*"),
             Token::Slash => write!(f, "/"),
             Token::Percent => write!(f, "%"),
+            Token::Concat => write!(f, "||"),
             Token::LeftParen => write!(f, "("),
             Token::RightParen => write!(f, ")"),
             Token::Comma => write!(f, ","),
+            Token::Dot => write!(f, "."),
+            Token::LeftBracket => write!(f, "["),
+            Token::RightBracket => write!(f, "]"),
             Token::Identifier(s) => write!(f, "identifier '{}'", s),
             Token::StringLiteral(s) => write!(f, "string '{}'", s),
             Token::IntegerLiteral(n) => write!(f, "integer {}", n),
             Token::FloatLiteral(n) => write!(f, "float {}", n),
+            Token::DecimalLiteral(n) => write!(f, "decimal {}", n),
+            Token::PositionalParameter(n) => write!(f, "parameter {}", n),
+            Token::NamedParameter(s) => write!(f, "parameter :{}", s),
             Token::Eof => write!(f, "end of input"),
         }
     }
 }
 
+/// A token together with the range of the input it was read from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
+/// Kinds of tokenization errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexErrorKind {
+    /// A character that cannot start a token
+    UnexpectedCharacter,
+    /// A string literal without its closing quote
+    UnterminatedString,
+    /// A block comment without its closing `*/`
+    UnterminatedComment,
+    /// A numeric literal that is malformed or out of range
+    InvalidNumber,
+}
+
+/// Tokenization error with the range of the input where it was detected.
+/// The message does not include the location.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    pub message: String,
+    pub span: Span,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
 /// Lexer struct used during tokenization of an input string.
 pub struct Lexer {
     input: Vec<char>,
     position: usize,
     current_char: Option<char>,
+    /// Byte offset, line and column of the current character
+    offset: usize,
+    line: usize,
+    column: usize,
+    /// Start of the token being read
+    token_start: Span,
+    /// Number of positional parameters read so far
+    positional_parameters: usize,
+    /// Whether numbers with a decimal point and no exponent are exact decimals
+    decimal_literals: bool,
 }
 
 impl Lexer {
@@ -107,19 +173,56 @@ impl Lexer {
             input: chars,
             position: 0,
             current_char,
+            offset: 0,
+            line: 1,
+            column: 1,
+            token_start: Span::default(),
+            positional_parameters: 0,
+            decimal_literals: false,
+        }
+    }
+
+    /// Read numbers with a decimal point and no exponent, such as `0.1`, as
+    /// `DecimalLiteral` instead of `FloatLiteral`.  Numbers with an exponent are
+    /// always floats.
+    pub fn with_decimal_literals(mut self, enabled: bool) -> Self {
+        self.decimal_literals = enabled;
+        self
+    }
+
+    /// Build an error covering the token read so far
+    fn error(&self, kind: LexErrorKind, message: &str) -> LexError {
+        LexError {
+            kind,
+            message: message.to_string(),
+            span: self.token_start.to(self.location()),
         }
     }
 
     /// Format error message with position and input context
-    fn format_error(&self, message: &str) -> String {
+    fn format_error(&self, error: &LexError) -> String {
         format!("{} near position {} in:\n  {}",
-            message,
-            self.position,
+            error.message,
+            error.span.start,
             String::from_iter(&self.input))
     }
 
+    /// Empty span at the current character
+    fn location(&self) -> Span {
+        Span::new(self.offset, self.offset, self.line, self.column)
+    }
+
     /// Advance to the next character
     fn advance(&mut self) {
+        if let Some(ch) = self.current_char {
+            self.offset += ch.len_utf8();
+            if ch == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
         self.position += 1;
         self.current_char = self.input.get(self.position).copied();
     }
@@ -157,7 +260,7 @@ impl Lexer {
     }
 
     /// Skip block comment (/* ... */)
-    fn skip_block_comment(&mut self) -> Result<(), String> {
+    fn skip_block_comment(&mut self) -> Result<(), LexError> {
         // Skip the '/*'
         self.advance();
         self.advance();
@@ -172,7 +275,7 @@ impl Lexer {
             self.advance();
         }
 
-        Err(self.format_error("Unterminated block comment"))
+        Err(self.error(LexErrorKind::UnterminatedComment, "Unterminated block comment"))
     }
 
     /// Read an identifier or keyword
@@ -192,7 +295,7 @@ impl Lexer {
     }
 
     /// Check if identifier is a keyword (case-insensitive)
-    fn keyword_or_identifier(&self, s: &str) -> Token {
+    fn keyword_or_identifier(s: &str) -> Token {
         match s.to_uppercase().as_str() {
             "AND" => Token::And,
             "OR" => Token::Or,
@@ -209,8 +312,17 @@ impl Lexer {
         }
     }
 
+    /// Check whether `s` reads back as a single identifier rather than a keyword
+    /// or anything else
+    pub fn is_identifier(s: &str) -> bool {
+        let mut chars = s.chars();
+        chars.next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+            && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+            && matches!(Self::keyword_or_identifier(s), Token::Identifier(_))
+    }
+
     /// Read a string literal with SQL-style escaping
-    fn read_string_literal(&mut self) -> Result<String, String> {
+    fn read_string_literal(&mut self) -> Result<String, LexError> {
         let mut result = String::new();
 
         // Skip opening quote
@@ -234,11 +346,11 @@ impl Lexer {
             }
         }
 
-        Err(self.format_error("Unterminated string literal"))
+        Err(self.error(LexErrorKind::UnterminatedString, "Unterminated string literal"))
     }
 
     /// Read a numeric literal (integer, long, hex, octal, or float)
-    fn read_number(&mut self) -> Result<Token, String> {
+    fn read_number(&mut self) -> Result<Token, LexError> {
         // Check for hex (0x or 0X)
         if self.current_char == Some('0') && matches!(self.peek(), Some('x') | Some('X')) {
             return self.read_hex_literal();
@@ -252,6 +364,7 @@ impl Lexer {
         // Read decimal or floating point
         let mut num_str = String::new();
         let mut is_float = false;
+        let mut has_exponent = false;
 
         // Read integer part
         while let Some(ch) = self.current_char {
@@ -283,6 +396,7 @@ impl Lexer {
         // Check for exponent
         if matches!(self.current_char, Some('e') | Some('E')) {
             is_float = true;
+            has_exponent = true;
             num_str.push('e');
             self.advance();
 
@@ -307,24 +421,26 @@ impl Lexer {
         if matches!(self.current_char, Some('l') | Some('L')) && !is_float {
             self.advance();
             let value = num_str.parse::<i64>()
-                .map_err(|e| self.format_error(&format!("Invalid integer literal: {}", e)))?;
+                .map_err(|e| self.error(LexErrorKind::InvalidNumber, &format!("Invalid integer literal: {}", e)))?;
             return Ok(Token::IntegerLiteral(value));
         }
 
-        // Parse as float or integer
-        if is_float {
+        // Parse as decimal, float or integer
+        if is_float && self.decimal_literals && !has_exponent {
+            self.decimal_token(&num_str)
+        } else if is_float {
             let value = num_str.parse::<f64>()
-                .map_err(|e| self.format_error(&format!("Invalid float literal: {}", e)))?;
+                .map_err(|e| self.error(LexErrorKind::InvalidNumber, &format!("Invalid float literal: {}", e)))?;
             Ok(Token::FloatLiteral(value))
         } else {
             let value = num_str.parse::<i64>()
-                .map_err(|e| self.format_error(&format!("Invalid integer literal: {}", e)))?;
+                .map_err(|e| self.error(LexErrorKind::InvalidNumber, &format!("Invalid integer literal: {}", e)))?;
             Ok(Token::IntegerLiteral(value))
         }
     }
 
     /// Read hexadecimal literal (0x...)
-    fn read_hex_literal(&mut self) -> Result<Token, String> {
+    fn read_hex_literal(&mut self) -> Result<Token, LexError> {
         // Skip '0x' or '0X'
         self.advance();
         self.advance();
@@ -340,16 +456,16 @@ impl Lexer {
         }
 
         if hex_str.is_empty() {
-            return Err(self.format_error("Invalid hexadecimal literal: no digits after 0x"));
+            return Err(self.error(LexErrorKind::InvalidNumber, "Invalid hexadecimal literal: no digits after 0x"));
         }
 
         let value = i64::from_str_radix(&hex_str, 16)
-            .map_err(|e| self.format_error(&format!("Invalid hexadecimal literal: {}", e)))?;
+            .map_err(|e| self.error(LexErrorKind::InvalidNumber, &format!("Invalid hexadecimal literal: {}", e)))?;
         Ok(Token::IntegerLiteral(value))
     }
 
     /// Read octal literal (0...)
-    fn read_octal_literal(&mut self) -> Result<Token, String> {
+    fn read_octal_literal(&mut self) -> Result<Token, LexError> {
         let mut octal_str = String::new();
 
         while let Some(ch) = self.current_char {
@@ -362,12 +478,12 @@ impl Lexer {
         }
 
         let value = i64::from_str_radix(&octal_str, 8)
-            .map_err(|e| self.format_error(&format!("Invalid octal literal: {}", e)))?;
+            .map_err(|e| self.error(LexErrorKind::InvalidNumber, &format!("Invalid octal literal: {}", e)))?;
         Ok(Token::IntegerLiteral(value))
     }
 
     /// Read floating point literal starting with '.'
-    fn read_float_starting_with_dot(&mut self) -> Result<Token, String> {
+    fn read_float_starting_with_dot(&mut self) -> Result<Token, LexError> {
         let mut num_str = String::from("0.");
 
         // Skip the '.'
@@ -384,7 +500,8 @@ impl Lexer {
         }
 
         // Check for exponent
-        if matches!(self.current_char, Some('e') | Some('E')) {
+        let has_exponent = matches!(self.current_char, Some('e') | Some('E'));
+        if has_exponent {
             num_str.push('e');
             self.advance();
 
@@ -405,16 +522,32 @@ impl Lexer {
             }
         }
 
+        if self.decimal_literals && !has_exponent {
+            return self.decimal_token(&num_str);
+        }
+
         let value = num_str.parse::<f64>()
-            .map_err(|e| self.format_error(&format!("Invalid float literal: {}", e)))?;
+            .map_err(|e| self.error(LexErrorKind::InvalidNumber, &format!("Invalid float literal: {}", e)))?;
         Ok(Token::FloatLiteral(value))
     }
 
+    /// Build a decimal literal from the digits read
+    fn decimal_token(&self, num_str: &str) -> Result<Token, LexError> {
+        let value = num_str.parse::<Decimal>()
+            .map_err(|e| self.error(LexErrorKind::InvalidNumber, &format!("Invalid decimal literal: {}", e)))?;
+        Ok(Token::DecimalLiteral(value))
+    }
+
     /// Get the next token
     pub fn next_token(&mut self) -> Result<Token, String> {
+        self.read_token().map_err(|e| self.format_error(&e))
+    }
+
+    fn read_token(&mut self) -> Result<Token, LexError> {
         loop {
             // Skip whitespace
             self.skip_whitespace();
+            self.token_start = self.location();
 
             let ch = match self.current_char {
                 Some(c) => c,
@@ -446,6 +579,14 @@ impl Lexer {
                     self.advance();
                     return Ok(Token::Comma);
                 }
+                '[' => {
+                    self.advance();
+                    return Ok(Token::LeftBracket);
+                }
+                ']' => {
+                    self.advance();
+                    return Ok(Token::RightBracket);
+                }
                 '+' => {
                     self.advance();
                     return Ok(Token::Plus);
@@ -476,7 +617,15 @@ impl Lexer {
                         self.advance();
                         return Ok(Token::NotEqual);
                     }
-                    return Err(self.format_error(&format!("Unexpected character: '{}'", ch)));
+                    return Err(self.error(LexErrorKind::UnexpectedCharacter, &format!("Unexpected character: '{}'", ch)));
+                }
+                '|' => {
+                    if self.peek() == Some('|') {
+                        self.advance();
+                        self.advance();
+                        return Ok(Token::Concat);
+                    }
+                    return Err(self.error(LexErrorKind::UnexpectedCharacter, &format!("Unexpected character: '{}'", ch)));
                 }
                 '<' => {
                     self.advance();
@@ -497,22 +646,37 @@ impl Lexer {
                     }
                     return Ok(Token::GreaterThan);
                 }
+                '?' => {
+                    self.advance();
+                    self.positional_parameters += 1;
+                    return Ok(Token::PositionalParameter(self.positional_parameters));
+                }
+                ':' => {
+                    if self.peek().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$') {
+                        self.advance();
+                        let name = self.read_identifier();
+                        return Ok(Token::NamedParameter(name));
+                    }
+                    return Err(self.error(LexErrorKind::UnexpectedCharacter, &format!("Unexpected character: '{}'", ch)));
+                }
                 '\'' => {
                     let s = self.read_string_literal()?;
                     return Ok(Token::StringLiteral(s));
                 }
                 '.' => {
-                    // Check if this is a float starting with '.'
+                    // Check if this is a float starting with '.', otherwise it
+                    // separates the segments of a variable path
                     if self.peek().is_some_and(|c| c.is_ascii_digit()) {
                         return self.read_float_starting_with_dot();
                     }
-                    return Err(self.format_error(&format!("Unexpected character: '{}'", ch)));
+                    self.advance();
+                    return Ok(Token::Dot);
                 }
                 _ => {
                     // Identifiers and keywords
                     if ch.is_alphabetic() || ch == '_' || ch == '$' {
                         let ident = self.read_identifier();
-                        return Ok(self.keyword_or_identifier(&ident));
+                        return Ok(Self::keyword_or_identifier(&ident));
                     }
 
                     // Numbers
@@ -520,18 +684,35 @@ impl Lexer {
                         return self.read_number();
                     }
 
-                    return Err(self.format_error(&format!("Unexpected character: '{}'", ch)));
+                    return Err(self.error(LexErrorKind::UnexpectedCharacter, &format!("Unexpected character: '{}'", ch)));
                 }
             }
         }
     }
 
+    /// Get the next token and the range of the input it covers.  The span of
+    /// `Token::Eof` is the empty span at the end of the input.
+    pub fn next_spanned_token(&mut self) -> Result<SpannedToken, LexError> {
+        let token = self.read_token()?;
+        Ok(SpannedToken {
+            token,
+            span: self.token_start.to(self.location()),
+        })
+    }
+
     /// Tokenize the entire input
     pub fn tokenize(&mut self) -> Result<Vec<Token>, String> {
+        self.tokenize_spanned()
+            .map(|tokens| tokens.into_iter().map(|t| t.token).collect())
+            .map_err(|e| self.format_error(&e))
+    }
+
+    /// Tokenize the entire input, keeping the span of every token
+    pub fn tokenize_spanned(&mut self) -> Result<Vec<SpannedToken>, LexError> {
         let mut tokens = Vec::new();
         loop {
-            let token = self.next_token()?;
-            if token == Token::Eof {
+            let token = self.next_spanned_token()?;
+            if token.token == Token::Eof {
                 tokens.push(token);
                 break;
             }
@@ -542,6 +723,8 @@ impl Lexer {
 }
 
 #[cfg(test)]
+// test_numbers lexes 3.14, which is not meant as an approximation of pi
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
 
@@ -572,6 +755,15 @@ mod tests {
         assert_eq!(lexer.next_token().unwrap(), Token::IntegerLiteral(100)); // 100L treated as integer
     }
 
+    #[test]
+    fn test_decimal_literals() {
+        let mut lexer = Lexer::new("12.50 .5 1.5e3 7").with_decimal_literals(true);
+        assert_eq!(lexer.next_token().unwrap(), Token::DecimalLiteral("12.50".parse().unwrap()));
+        assert_eq!(lexer.next_token().unwrap(), Token::DecimalLiteral("0.5".parse().unwrap()));
+        assert_eq!(lexer.next_token().unwrap(), Token::FloatLiteral(1500.0));
+        assert_eq!(lexer.next_token().unwrap(), Token::IntegerLiteral(7));
+    }
+
     #[test]
     fn test_comments() {
         let mut lexer = Lexer::new("x -- comment\ny /* block */ z");
@@ -579,4 +771,60 @@ mod tests {
         assert!(matches!(lexer.next_token().unwrap(), Token::Identifier(_)));
         assert!(matches!(lexer.next_token().unwrap(), Token::Identifier(_)));
     }
+
+    #[test]
+    fn test_token_spans() {
+        let input = "x >= 'é'\n  AND y";
+        let tokens = Lexer::new(input).tokenize_spanned().unwrap();
+        let spans: Vec<_> = tokens.iter()
+            .map(|t| (&input[t.span.start..t.span.end], t.span.line, t.span.column))
+            .collect();
+        assert_eq!(spans, vec![("x", 1, 1), (">=", 1, 3), ("'é'", 1, 6), ("AND", 2, 3), ("y", 2, 7), ("", 2, 8)]);
+
+        let err = Lexer::new("a = 'open").tokenize_spanned().unwrap_err();
+        assert_eq!(err.kind, LexErrorKind::UnterminatedString);
+        assert_eq!((err.span.line, err.span.column), (1, 5));
+    }
+
+    #[test]
+    fn test_parameters() {
+        let mut lexer = Lexer::new("? :tenant ?:n_2");
+        assert_eq!(lexer.next_token().unwrap(), Token::PositionalParameter(1));
+        assert_eq!(lexer.next_token().unwrap(), Token::NamedParameter("tenant".to_string()));
+        assert_eq!(lexer.next_token().unwrap(), Token::PositionalParameter(2));
+        assert_eq!(lexer.next_token().unwrap(), Token::NamedParameter("n_2".to_string()));
+        assert!(Lexer::new(": x").next_token().is_err());
+    }
+
+    #[test]
+    fn test_path_tokens() {
+        let tokens = Lexer::new("items[0].sku .5").tokenize().unwrap();
+        assert_eq!(tokens, vec![
+            Token::Identifier("items".to_string()),
+            Token::LeftBracket,
+            Token::IntegerLiteral(0),
+            Token::RightBracket,
+            Token::Dot,
+            Token::Identifier("sku".to_string()),
+            Token::FloatLiteral(0.5),
+            Token::Eof,
+        ]);
+        assert!(Lexer::is_identifier("tier"));
+        assert!(!Lexer::is_identifier("x-id"));
+        assert!(!Lexer::is_identifier("between"));
+    }
+
+    #[test]
+    fn test_concat_operator() {
+        let tokens = Lexer::new("first||' '|| last").tokenize().unwrap();
+        assert_eq!(tokens, vec![
+            Token::Identifier("first".to_string()),
+            Token::Concat,
+            Token::StringLiteral(" ".to_string()),
+            Token::Concat,
+            Token::Identifier("last".to_string()),
+            Token::Eof,
+        ]);
+        assert!(Lexer::new("a | b").tokenize().is_err());
+    }
 }
@@ -8,17 +8,37 @@
 //! appear as operands to relational operators.
 
 pub mod ast;
+pub mod collation;
+pub mod decimal;
 pub mod lexer;
+pub mod numeric;
 pub mod parser;
 pub mod evaluator;
+pub mod pattern;
+pub mod pretty;
+pub mod span;
+pub mod temporal;
+mod functions;
 
 // Re-export main types for convenient access
 pub use ast::{
     BooleanExpr, RelationalExpr, ValueExpr, ValueLiteral,
-    EqualityOp, ComparisonOp,
+    EqualityOp, ComparisonOp, Quantifier, Parameter, PathSegment, CaseBranch, CaseWhen,
+};
+pub use parser::{
+    parse, parse_with_functions, parse_with_options, ParseError, ParseErrorKind, ParserOptions,
+};
+pub use collation::Collation;
+pub use decimal::Decimal;
+pub use functions::{FunctionRegistry, ValueType};
+pub use pattern::{LikePattern, RegexPattern, RegexSyntax};
+pub use span::Span;
+pub use temporal::{Date, Interval, Timestamp};
+pub use evaluator::{
+    evaluate, CompiledExpr, RuntimeValue, EvalError, ValueProvider,
+    EvalOptions, NullSemantics, OverflowPolicy, Truth, PartialResult, SpannedEvalError, Parameters,
+    MAX_EVAL_DEPTH,
 };
-pub use parser::{parse, ParseError};
-pub use evaluator::{evaluate, RuntimeValue, EvalError};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,149 @@
+//! Exact ordering of mixed numbers for SQL Expression Evaluation
+//!
+//! Integers, floats and decimals compare by their mathematical value, without
+//! first converting one side to the other's type: casting an `i64` to `f64`
+//! rounds every integer above 2^53, so `9007199254740993 = 9007199254740992.0`
+//! would otherwise hold.  The functions here are what equality, ordering,
+//! BETWEEN, IN and NULLIF use to compare numbers of different types.
+//!
+//! Together they give a total order over all numbers:
+//!
+//! - negative infinity is below every other number and positive infinity above
+//!   every finite number;
+//! - `-0.0` and `0.0` are equal;
+//! - NaN is equal to NaN and greater than every other number, including
+//!   positive infinity, so that sorting and deduplication keep working when
+//!   NaN values occur.
+//!
+//! # Examples
+//! ```
+//! use std::cmp::Ordering;
+//! use sqlexpr_rust::numeric::{cmp_f64, cmp_i64_f64};
+//!
+//! assert_eq!(cmp_i64_f64(9007199254740993, 9007199254740992.0), Ordering::Greater);
+//! assert_eq!(cmp_i64_f64(3, 3.0), Ordering::Equal);
+//! assert_eq!(cmp_f64(f64::NAN, f64::INFINITY), Ordering::Greater);
+//! assert_eq!(cmp_f64(-0.0, 0.0), Ordering::Equal);
+//! ```
+
+use std::cmp::Ordering;
+
+use crate::decimal::Decimal;
+
+/// 2^63 as a float, the first float above every `i64`
+const I64_LIMIT: f64 = 9_223_372_036_854_775_808.0;
+
+/// 2^127 as a float, the first float above every `i128` and so every decimal
+const I128_LIMIT: f64 = 170_141_183_460_469_231_731_687_303_715_884_105_728.0;
+
+/// Order two floats: `-0.0` equals `0.0`, and NaN equals NaN and is greater
+/// than every other float
+pub fn cmp_f64(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).expect("floats that are not NaN are ordered"),
+    }
+}
+
+/// Order an integer and a float exactly
+pub fn cmp_i64_f64(a: i64, b: f64) -> Ordering {
+    if b.is_nan() || b >= I64_LIMIT {
+        return Ordering::Less;
+    }
+    if b < -I64_LIMIT {
+        return Ordering::Greater;
+    }
+    // In range, the whole part of the float is exactly an i64
+    let whole = b.trunc();
+    a.cmp(&(whole as i64)).then_with(|| cmp_f64(0.0, b - whole))
+}
+
+/// Order a decimal and a float exactly
+pub fn cmp_decimal_f64(a: Decimal, b: f64) -> Ordering {
+    if b.is_nan() || b >= I128_LIMIT {
+        return Ordering::Less;
+    }
+    if b <= -I128_LIMIT {
+        return Ordering::Greater;
+    }
+    let unit = 10i128.pow(a.scale());
+    let whole = b.trunc();
+    (a.mantissa() / unit).cmp(&(whole as i128))
+        .then_with(|| cmp_fraction(a.mantissa() % unit, unit, b - whole))
+}
+
+/// Order the fraction `numerator / denominator` and the float fraction `b`,
+/// both between -1 and 1, by comparing their binary digits one at a time.  The
+/// float's digits run out after at most 1074 places, which ends the loop.
+fn cmp_fraction(numerator: i128, denominator: i128, b: f64) -> Ordering {
+    let (sign, b_sign) = (numerator.cmp(&0), cmp_f64(b, 0.0));
+    if sign != b_sign || sign == Ordering::Equal {
+        return sign.cmp(&b_sign);
+    }
+    let denominator = denominator as u128;
+    let (mut left, mut right) = (numerator.unsigned_abs(), b.abs());
+    let magnitude = loop {
+        if left == 0 || right == 0.0 {
+            break (left != 0).cmp(&(right != 0.0));
+        }
+        // Doubling is exact for both: left < denominator <= 10^38 and right < 1
+        left *= 2;
+        right *= 2.0;
+        let (left_digit, right_digit) = (left >= denominator, right >= 1.0);
+        if left_digit != right_digit {
+            break left_digit.cmp(&right_digit);
+        }
+        if left_digit {
+            left -= denominator;
+            right -= 1.0;
+        }
+    };
+    if sign == Ordering::Less { magnitude.reverse() } else { magnitude }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_integers_beyond_float_precision() {
+        let big = 9_007_199_254_740_993;
+        assert_eq!(cmp_i64_f64(big, 9_007_199_254_740_992.0), Ordering::Greater);
+        assert_eq!(cmp_i64_f64(big - 1, 9_007_199_254_740_992.0), Ordering::Equal);
+        assert_eq!(cmp_i64_f64(i64::MAX, I64_LIMIT), Ordering::Less);
+        assert_eq!(cmp_i64_f64(i64::MIN, -I64_LIMIT), Ordering::Equal);
+        assert_eq!(cmp_i64_f64(-3, -2.5), Ordering::Less);
+        assert_eq!(cmp_i64_f64(-2, -2.5), Ordering::Greater);
+        assert_eq!(cmp_i64_f64(0, -0.0), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_infinity_and_nan() {
+        assert_eq!(cmp_i64_f64(i64::MAX, f64::INFINITY), Ordering::Less);
+        assert_eq!(cmp_i64_f64(i64::MIN, f64::NEG_INFINITY), Ordering::Greater);
+        assert_eq!(cmp_i64_f64(i64::MAX, f64::NAN), Ordering::Less);
+        assert_eq!(cmp_f64(f64::NAN, f64::NAN), Ordering::Equal);
+        assert_eq!(cmp_f64(f64::INFINITY, f64::NAN), Ordering::Less);
+        assert_eq!(cmp_decimal_f64(dec("1"), f64::NAN), Ordering::Less);
+        assert_eq!(cmp_decimal_f64(dec("-1"), f64::NEG_INFINITY), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_decimals_against_floats() {
+        // 0.1 as a float is slightly above one tenth
+        assert_eq!(cmp_decimal_f64(dec("0.1"), 0.1), Ordering::Less);
+        assert_eq!(cmp_decimal_f64(dec("-0.1"), -0.1), Ordering::Greater);
+        assert_eq!(cmp_decimal_f64(dec("0.5"), 0.5), Ordering::Equal);
+        assert_eq!(cmp_decimal_f64(dec("-2.75"), -2.75), Ordering::Equal);
+        assert_eq!(cmp_decimal_f64(dec("-0.5"), 0.25), Ordering::Less);
+        assert_eq!(cmp_decimal_f64(dec("2.0"), 2.5), Ordering::Less);
+        assert_eq!(cmp_decimal_f64(dec("0.00000000000000000000000000000000000001"), 0.0), Ordering::Greater);
+        assert_eq!(cmp_decimal_f64(dec("99999999999999999999999999999999999999"), 1e38), Ordering::Greater);
+    }
+}
@@ -11,29 +11,300 @@
 //! The parser also performs as much type checking at parse time as possible. Additional 
 //! runtime type checking is necessary during evaluation after variable values are known.  
 //! 
-//! The parser also supports pretty-printing of the AST when the
-//! SQLEXPR_PRETTY environment variable is set to "true".
+//! Parsing is configured through `ParserOptions`.  The parser never prints; use the
+//! pretty module to render a parsed AST as a tree.
 
 use crate::ast::*;
-use crate::lexer::{Lexer, Token};
+use crate::collation::Collation;
+use crate::decimal::Decimal;
+use crate::functions::{lookup_builtin, FunctionRegistry};
+use crate::lexer::{LexErrorKind, Lexer, SpannedToken, Token};
+use crate::numeric;
+use crate::pattern::{LikePattern, RegexPattern};
+use crate::span::Span;
+use crate::temporal::{Interval, Timestamp};
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
 
 /// Parser struct used to track parsing state and options.
 pub struct Parser {
-    tokens: Vec<Token>,
+    tokens: Vec<SpannedToken>,
     position: usize,
-    pretty_print: bool,
-    input: String,
+    options: ParserOptions,
+    /// Number of parentheses, NOTs, unary signs and function calls the parser is
+    /// currently inside; bounds the parser's own recursion
+    nesting: usize,
+    /// Depth of the expression most recently parsed; bounds the depth of the AST
+    depth: usize,
+    /// Positions just after a `(` where the contents did not parse as a
+    /// parenthesized boolean expression, so a reparse goes straight to the
+    /// relational expression instead of trying again
+    not_boolean: HashSet<usize>,
 }
 
-/// Parse error type that defines specific error messages.
-#[derive(Debug)]
+/// Options controlling which language features the parser accepts and how large
+/// an input it accepts.
+///
+/// The size limits protect services that parse expressions from untrusted
+/// sources.  An accepted expression is at most `max_depth` levels deep, so
+/// parsing it, evaluating it and walking its AST recursively all need a bounded
+/// amount of stack.  A chain of one operator, such as `a OR b OR c`, counts as a
+/// single level and is walked with a loop, so its length is bounded only by
+/// `max_tokens`.  Parsing needs the most stack: up to about 24 KiB per level of
+/// nesting in an unoptimized build, or about 1.5 MiB for the deepest expressions
+/// the default limits accept.  That fits in the 2 MiB stack of a spawned thread;
+/// a higher `max_depth` needs a proportionally larger stack.
+///
+/// # Examples
+/// ```
+/// use sqlexpr_rust::{parse_with_options, ParseErrorKind, ParserOptions};
+///
+/// let options = ParserOptions::new().parameters(false);
+/// let err = parse_with_options("tenant = :tenant", &options).unwrap_err();
+/// assert_eq!(err.kind, ParseErrorKind::FeatureDisabled);
+///
+/// let options = ParserOptions::new().max_depth(3);
+/// let err = parse_with_options("NOT NOT NOT flag", &options).unwrap_err();
+/// assert_eq!(err.kind, ParseErrorKind::NestingTooDeep);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParserOptions {
+    parameters: bool,
+    functions: FunctionRegistry,
+    decimal_literals: bool,
+    max_input_length: usize,
+    max_tokens: usize,
+    max_depth: usize,
+    max_in_list: usize,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            parameters: true,
+            functions: FunctionRegistry::default(),
+            decimal_literals: false,
+            max_input_length: ParserOptions::DEFAULT_MAX_INPUT_LENGTH,
+            max_tokens: ParserOptions::DEFAULT_MAX_TOKENS,
+            max_depth: ParserOptions::DEFAULT_MAX_DEPTH,
+            max_in_list: ParserOptions::DEFAULT_MAX_IN_LIST,
+        }
+    }
+}
+
+impl ParserOptions {
+    /// Default limit on the length of the input, in bytes
+    pub const DEFAULT_MAX_INPUT_LENGTH: usize = 64 * 1024;
+    /// Default limit on the number of tokens in the input
+    pub const DEFAULT_MAX_TOKENS: usize = 10_000;
+    /// Default limit on the nesting depth of an expression
+    pub const DEFAULT_MAX_DEPTH: usize = 64;
+    /// Default limit on the number of values in an IN list
+    pub const DEFAULT_MAX_IN_LIST: usize = 1_000;
+
+    /// Default options: parameter placeholders allowed, built-in functions only,
+    /// and the default size limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow or reject `?` and `:name` parameter placeholders.
+    pub fn parameters(mut self, allowed: bool) -> Self {
+        self.parameters = allowed;
+        self
+    }
+
+    /// Accept calls to the functions in `functions` in addition to the built-ins.
+    pub fn functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = functions;
+        self
+    }
+
+    /// Read numeric literals with a decimal point and no exponent, such as `0.10`,
+    /// as exact [`Decimal`](crate::Decimal) values instead of floats.  Literals
+    /// with an exponent, such as `1.5e3`, are floats in either mode.
+    pub fn decimal_literals(mut self, enabled: bool) -> Self {
+        self.decimal_literals = enabled;
+        self
+    }
+
+    /// Reject inputs longer than `bytes` bytes before tokenizing them.
+    pub fn max_input_length(mut self, bytes: usize) -> Self {
+        self.max_input_length = bytes;
+        self
+    }
+
+    /// Reject inputs of more than `tokens` tokens, not counting the end of input.
+    pub fn max_tokens(mut self, tokens: usize) -> Self {
+        self.max_tokens = tokens;
+        self
+    }
+
+    /// Reject expressions nested more than `depth` levels deep.
+    ///
+    /// A variable, literal or parameter is one level deep and each operator adds
+    /// a level above its operands, so `NOT NOT NOT flag` is four levels deep (see
+    /// [`BooleanExpr::depth`]).  A chain of operators of the same precedence adds
+    /// a single level, however long, so `a + b - c > 0` is three levels deep and
+    /// `a * b + c > 0` four.  Parentheses add no level to the AST, but may only be
+    /// nested `depth` levels deep too, and each part of a CASE counts as nested a
+    /// level within it, or two levels for a WHEN condition.
+    ///
+    /// Parsing takes up to about 24 KiB of stack per level in an unoptimized
+    /// build, so a limit much above the default needs a thread with a stack
+    /// larger than 2 MiB.  Evaluation refuses expressions deeper than
+    /// [`MAX_EVAL_DEPTH`], so raising this limit above it only allows expressions
+    /// that cannot be evaluated.
+    ///
+    /// [`MAX_EVAL_DEPTH`]: crate::MAX_EVAL_DEPTH
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Reject IN lists of more than `values` values.
+    pub fn max_in_list(mut self, values: usize) -> Self {
+        self.max_in_list = values;
+        self
+    }
+}
+
+/// Kinds of parse errors, for handling errors without matching on messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A token other than one of the expected tokens was found
+    UnexpectedToken,
+    /// A character that cannot start a token
+    UnexpectedCharacter,
+    /// A string literal without its closing quote
+    UnterminatedString,
+    /// A block comment without its closing `*/`
+    UnterminatedComment,
+    /// A numeric literal that is malformed or out of range
+    InvalidNumber,
+    /// Unary minus applied to a string, NULL or boolean literal
+    InvalidUnaryMinus,
+    /// NULL or a boolean literal in an IN list
+    InvalidInListValue,
+    /// IN list values of different types
+    InListTypeMismatch,
+    /// NULL or a boolean literal as a BETWEEN bound
+    InvalidBetweenBound,
+    /// BETWEEN bounds of incompatible types
+    BetweenTypeMismatch,
+    /// BETWEEN lower bound greater than the upper bound
+    ReversedBetweenBounds,
+    /// CASE results of incompatible types
+    CaseTypeMismatch,
+    /// A LIKE pattern that cannot be compiled
+    InvalidPattern,
+    /// A DATE, TIMESTAMP, INTERVAL or DECIMAL literal whose text is not a valid value
+    InvalidTypedLiteral,
+    /// ANY(...) or ALL(...) other than as one operand of =, <>, <, <=, > or >=
+    MisplacedQuantifier,
+    /// A COLLATE clause naming a collation that does not exist
+    UnknownCollation,
+    /// A call to a function that is neither built in nor registered
+    UnknownFunction,
+    /// A function called with the wrong number of arguments
+    WrongArgumentCount,
+    /// Syntax that is turned off in the `ParserOptions`
+    FeatureDisabled,
+    /// An input longer than the `ParserOptions` allow
+    InputTooLong,
+    /// An input of more tokens than the `ParserOptions` allow
+    TooManyTokens,
+    /// An expression nested more deeply than the `ParserOptions` allow
+    NestingTooDeep,
+    /// An IN list of more values than the `ParserOptions` allow
+    InListTooLong,
+}
+
+impl From<LexErrorKind> for ParseErrorKind {
+    fn from(kind: LexErrorKind) -> Self {
+        match kind {
+            LexErrorKind::UnexpectedCharacter => ParseErrorKind::UnexpectedCharacter,
+            LexErrorKind::UnterminatedString => ParseErrorKind::UnterminatedString,
+            LexErrorKind::UnterminatedComment => ParseErrorKind::UnterminatedComment,
+            LexErrorKind::InvalidNumber => ParseErrorKind::InvalidNumber,
+        }
+    }
+}
+
+/// Parse error with its kind and the location in the input it refers to.
+///
+/// `Display` gives a one-line message with the line and column;
+/// [`ParseError::render`] gives a multi-line diagnostic that underlines the
+/// offending part of the input.
+#[derive(Debug, Clone, PartialEq)]
 pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// Description of the error, without the location
     pub message: String,
+    /// The part of the input the error refers to
+    pub span: Span,
+    /// Descriptions of the tokens that would have been accepted at `span`; only
+    /// set for `UnexpectedToken`
+    pub expected: Vec<String>,
+    /// The token found at `span`; only set for `UnexpectedToken`
+    pub found: Option<Token>,
+}
+
+impl ParseError {
+    /// Render the error as a compiler-style diagnostic: the message, the line and
+    /// column, and the line of `input` with the error's span underlined.  `input`
+    /// should be the text that was parsed; given other text, the rendering is
+    /// clipped to it rather than panicking.
+    ///
+    /// # Examples
+    /// ```
+    /// use sqlexpr_rust::parse;
+    ///
+    /// let input = "price > 10 AND\n  name LIKE 42";
+    /// let err = parse(input).unwrap_err();
+    /// assert_eq!(err.render(input), "\
+    /// error: Expected string literal, got integer 42
+    ///  --> line 2, column 13
+    ///   |
+    /// 2 |   name LIKE 42
+    ///   |             ^^
+    /// ");
+    /// ```
+    pub fn render(&self, input: &str) -> String {
+        let line_number = self.span.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let source_line = self.span.line.checked_sub(1)
+            .and_then(|index| input.lines().nth(index))
+            .unwrap_or("");
+
+        // Keep tabs so the underline lines up with the source line
+        let indent: String = source_line.chars()
+            .take(self.span.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        // Underline up to the end of the first line of the span, at least one caret
+        let width = input.get(self.span.start.min(input.len())..self.span.end.min(input.len()))
+            .unwrap_or("")
+            .lines()
+            .next()
+            .map_or(0, |text| text.chars().count())
+            .max(1);
+
+        format!(
+            "error: {}\n{}--> {}\n{} |\n{} | {}\n{} | {}{}\n",
+            self.message,
+            gutter, self.span,
+            gutter,
+            line_number, source_line,
+            gutter, indent, "^".repeat(width)
+        )
+    }
 }
 
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Parse error: {}", self.message)
+        write!(f, "Parse error: {} at {}", self.message, self.span)
     }
 }
 
@@ -43,31 +314,138 @@ type ParseResult<T> = Result<T, ParseError>;
 
 impl Parser {
     pub fn new(input: &str) -> Result<Self, ParseError> {
-        let mut lexer = Lexer::new(input);
-        let tokens = lexer.tokenize()
-            .map_err(|e| ParseError { message: e })?;
+        Self::with_options(input, &ParserOptions::default())
+    }
 
-        // Check SQLEXPR_PRETTY environment variable
-        let pretty_print = std::env::var("SQLEXPR_PRETTY")
-            .map(|v| v.to_lowercase() == "true")
-            .unwrap_or(false);
+    /// Create a parser for `input` that accepts the features allowed by `options`.
+    pub fn with_options(input: &str, options: &ParserOptions) -> Result<Self, ParseError> {
+        if input.len() > options.max_input_length {
+            return Err(Self::error(
+                ParseErrorKind::InputTooLong,
+                Span::new(0, input.len(), 1, 1),
+                format!("Input is {} bytes long, the limit is {}", input.len(), options.max_input_length),
+            ));
+        }
+
+        let mut lexer = Lexer::new(input).with_decimal_literals(options.decimal_literals);
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.next_spanned_token()
+                .map_err(|e| Self::error(e.kind.into(), e.span, e.message))?;
+            if token.token == Token::Eof {
+                tokens.push(token);
+                break;
+            }
+            if tokens.len() == options.max_tokens {
+                return Err(Self::error(
+                    ParseErrorKind::TooManyTokens,
+                    token.span,
+                    format!("Input has more than {} tokens", options.max_tokens),
+                ));
+            }
+            tokens.push(token);
+        }
 
         Ok(Parser {
             tokens,
             position: 0,
-            pretty_print,
-            input: input.to_string(),
+            options: options.clone(),
+            nesting: 0,
+            depth: 0,
+            not_boolean: HashSet::new(),
         })
     }
 
+    /// Accept calls to the functions in `functions` in addition to the built-ins.
+    pub fn with_functions(mut self, functions: &FunctionRegistry) -> Self {
+        self.options.functions = functions.clone();
+        self
+    }
+
     /// Get current token
     fn current_token(&self) -> &Token {
-        self.tokens.get(self.position).unwrap_or(&Token::Eof)
+        self.tokens.get(self.position).map_or(&Token::Eof, |t| &t.token)
     }
 
     /// Peek at next token
     fn peek_token(&self) -> &Token {
-        self.tokens.get(self.position + 1).unwrap_or(&Token::Eof)
+        self.tokens.get(self.position + 1).map_or(&Token::Eof, |t| &t.token)
+    }
+
+    /// Span of the current token; past the end this is the span of end of input
+    fn current_span(&self) -> Span {
+        self.tokens.get(self.position)
+            .or(self.tokens.last())
+            .map_or_else(Span::default, |t| t.span)
+    }
+
+    /// Span from `start` to the end of the last consumed token
+    fn span_from(&self, start: Span) -> Span {
+        match self.position.checked_sub(1).and_then(|i| self.tokens.get(i)) {
+            Some(previous) => start.to(previous.span),
+            None => start,
+        }
+    }
+
+    /// Build an error pointing at `span`
+    fn error(kind: ParseErrorKind, span: Span, message: String) -> ParseError {
+        ParseError {
+            kind,
+            message,
+            span,
+            expected: Vec::new(),
+            found: None,
+        }
+    }
+
+    /// Build an `UnexpectedToken` error for the current token
+    fn unexpected(&self, expected: &[&str], message: String) -> ParseError {
+        ParseError {
+            expected: expected.iter().map(|e| e.to_string()).collect(),
+            found: Some(self.current_token().clone()),
+            ..Self::error(ParseErrorKind::UnexpectedToken, self.current_span(), message)
+        }
+    }
+
+    /// Enter a parenthesis, NOT, unary sign or function call starting at `span`
+    fn enter(&mut self, span: Span) -> ParseResult<()> {
+        self.enter_levels(1, span)
+    }
+
+    /// Enter a construct starting at `span` that takes as much stack to parse
+    /// as `levels` nested parentheses
+    fn enter_levels(&mut self, levels: usize, span: Span) -> ParseResult<()> {
+        self.nesting += levels;
+        if self.nesting > self.options.max_depth {
+            return Err(self.too_deep(span));
+        }
+        Ok(())
+    }
+
+    /// Leave the construct most recently entered
+    fn leave(&mut self) {
+        self.leave_levels(1);
+    }
+
+    /// Leave the construct most recently entered with `enter_levels`
+    fn leave_levels(&mut self, levels: usize) {
+        self.nesting -= levels;
+    }
+
+    /// Record the depth of the expression just parsed, which spans `span`
+    fn set_depth(&mut self, depth: usize, span: Span) -> ParseResult<()> {
+        self.depth = depth;
+        if depth > self.options.max_depth {
+            return Err(self.too_deep(span));
+        }
+        Ok(())
+    }
+
+    fn too_deep(&self, span: Span) -> ParseError {
+        Self::error(ParseErrorKind::NestingTooDeep, span, format!(
+            "Expression is nested more than {} levels deep",
+            self.options.max_depth
+        ))
     }
 
     /// Advance to next token
@@ -83,9 +461,8 @@ impl Parser {
             self.advance();
             Ok(())
         } else {
-            Err(ParseError {
-                message: format!("Expected {}, got {} near position {} in:\n  {}", expected, self.current_token(), self.position, self.input),
-            })
+            let description = expected.to_string();
+            Err(self.unexpected(&[&description], format!("Expected {}, got {}", expected, self.current_token())))
         }
     }
 
@@ -93,179 +470,54 @@ impl Parser {
     pub fn parse(&mut self) -> ParseResult<BooleanExpr> {
         let expr = self.parse_boolean_expression()?;
         if self.current_token() != &Token::Eof {
-            return Err(ParseError {
-                message: format!("Unexpected token '{}' near position {} in:\n  {}", self.current_token(), self.position, self.input),
-            });
-        }
-
-        // Pretty print if enabled
-        if self.pretty_print {
-            self.print_ast(&expr);
+            return Err(self.unexpected(
+                &["AND", "OR", "end of input"],
+                format!("Unexpected token '{}'", self.current_token()),
+            ));
         }
 
         Ok(expr)
     }
 
-    /// Pretty print the AST with indentation
-    fn print_ast(&self, expr: &BooleanExpr) {
-        println!("Input: {}", self.input);
-        println!("AST:");
-        self.print_boolean_expr(expr, 0);
-        println!();
-    }
-
-    fn print_boolean_expr(&self, expr: &BooleanExpr, indent: usize) {
-        let prefix = " ".repeat(indent);
-        match expr {
-            BooleanExpr::Or(left, right) => {
-                println!("{}Or", prefix);
-                self.print_boolean_expr(left, indent + 3);
-                self.print_boolean_expr(right, indent + 3);
-            }
-            BooleanExpr::And(left, right) => {
-                println!("{}And", prefix);
-                self.print_boolean_expr(left, indent + 3);
-                self.print_boolean_expr(right, indent + 3);
-            }
-            BooleanExpr::Not(inner) => {
-                println!("{}Not", prefix);
-                self.print_boolean_expr(inner, indent + 3);
-            }
-            BooleanExpr::Literal(b) => {
-                println!("{}BooleanLiteral: {}", prefix, b);
-            }
-            BooleanExpr::Variable(name) => {
-                println!("{}Variable: {}", prefix, name);
-            }
-            BooleanExpr::Relational(rel) => {
-                println!("{}Relational", prefix);
-                self.print_relational_expr(rel, indent + 3);
-            }
-        }
-    }
-
-    fn print_relational_expr(&self, expr: &RelationalExpr, indent: usize) {
-        let prefix = " ".repeat(indent);
-        match expr {
-            RelationalExpr::Equality { left, op, right } => {
-                println!("{}Equality: {:?}", prefix, op);
-                self.print_value_expr(left, indent + 3);
-                self.print_value_expr(right, indent + 3);
-            }
-            RelationalExpr::Comparison { left, op, right } => {
-                println!("{}Comparison: {:?}", prefix, op);
-                self.print_value_expr(left, indent + 3);
-                self.print_value_expr(right, indent + 3);
-            }
-            RelationalExpr::Like { expr, pattern, escape, negated } => {
-                println!("{}Like: negated={}, pattern='{}', escape={:?}",
-                    prefix, negated, pattern, escape);
-                self.print_value_expr(expr, indent + 3);
-            }
-            RelationalExpr::Between { expr, lower, upper, negated } => {
-                println!("{}Between: negated={}", prefix, negated);
-                self.print_value_expr(expr, indent + 3);
-                self.print_value_expr(lower, indent + 3);
-                self.print_value_expr(upper, indent + 3);
-            }
-            RelationalExpr::In { expr, values, negated } => {
-                println!("{}In: negated={}, values={:?}", prefix, negated, values);
-                self.print_value_expr(expr, indent + 3);
-            }
-            RelationalExpr::IsNull { expr, negated } => {
-                println!("{}IsNull: negated={}", prefix, negated);
-                self.print_value_expr(expr, indent + 3);
-            }
-        }
-    }
-
-    #[allow(clippy::only_used_in_recursion)]
-    fn print_value_expr(&self, expr: &ValueExpr, indent: usize) {
-        let prefix = " ".repeat(indent);
-        match expr {
-            ValueExpr::Add(left, right) => {
-                println!("{}Add", prefix);
-                self.print_value_expr(left, indent + 3);
-                self.print_value_expr(right, indent + 3);
-            }
-            ValueExpr::Subtract(left, right) => {
-                println!("{}Subtract", prefix);
-                self.print_value_expr(left, indent + 3);
-                self.print_value_expr(right, indent + 3);
-            }
-            ValueExpr::Multiply(left, right) => {
-                println!("{}Multiply", prefix);
-                self.print_value_expr(left, indent + 3);
-                self.print_value_expr(right, indent + 3);
-            }
-            ValueExpr::Divide(left, right) => {
-                println!("{}Divide", prefix);
-                self.print_value_expr(left, indent + 3);
-                self.print_value_expr(right, indent + 3);
-            }
-            ValueExpr::Modulo(left, right) => {
-                println!("{}Modulo", prefix);
-                self.print_value_expr(left, indent + 3);
-                self.print_value_expr(right, indent + 3);
-            }
-            ValueExpr::UnaryPlus(inner) => {
-                println!("{}UnaryPlus", prefix);
-                self.print_value_expr(inner, indent + 3);
-            }
-            ValueExpr::UnaryMinus(inner) => {
-                println!("{}UnaryMinus", prefix);
-                self.print_value_expr(inner, indent + 3);
-            }
-            ValueExpr::Literal(lit) => {
-                println!("{}Literal: {:?}", prefix, lit);
-            }
-            ValueExpr::Variable(name) => {
-                println!("{}Variable: {}", prefix, name);
-            }
-        }
-    }
-
     // ========================================================================
     // TYPE CHECKING HELPER FUNCTIONS
     // ========================================================================
 
-    /// Extract literal from ValueExpr, returning error if not a literal
-    /// Special case: UnaryMinus/UnaryPlus of a literal is allowed (for negative/positive numbers)
-    fn extract_literal(expr: &ValueExpr) -> ParseResult<ValueLiteral> {
-        match expr {
-            ValueExpr::Literal(lit) => Ok(lit.clone()),
-            // Handle unary minus for negative numbers
-            ValueExpr::UnaryMinus(inner) => {
-                if let ValueExpr::Literal(lit) = inner.as_ref() {
-                    match lit {
-                        ValueLiteral::Integer(n) => Ok(ValueLiteral::Integer(-n)),
-                        ValueLiteral::Float(f) => Ok(ValueLiteral::Float(-f)),
-                        _ => Err(ParseError {
-                            message: "Unary minus can only be applied to numeric literals in BETWEEN bounds".to_string(),
-                        }),
-                    }
-                } else {
-                    Err(ParseError {
-                        message: "Complex expressions are not allowed here, only literal values".to_string(),
-                    })
-                }
-            }
-            // Handle unary plus (just unwrap it)
-            ValueExpr::UnaryPlus(inner) => {
-                if let ValueExpr::Literal(lit) = inner.as_ref() {
-                    Ok(lit.clone())
-                } else {
-                    Err(ParseError {
-                        message: "Complex expressions are not allowed here, only literal values".to_string(),
-                    })
-                }
-            }
-            ValueExpr::Variable(_) => Err(ParseError {
-                message: "Variables are not allowed here, only literal values".to_string(),
-            }),
-            _ => Err(ParseError {
-                message: "Complex expressions are not allowed here, only literal values".to_string(),
-            }),
+    /// Extract the literal a ValueExpr consists of, or `None` if it is not a literal.
+    /// Special case: UnaryMinus/UnaryPlus of a literal is a literal (for negative/positive numbers)
+    fn extract_literal(expr: &ValueExpr) -> ParseResult<Option<ValueLiteral>> {
+        let (negate, lit) = match expr {
+            ValueExpr::Literal(lit, _) => (false, lit),
+            ValueExpr::UnaryMinus(inner, _) => match inner.as_ref() {
+                ValueExpr::Literal(lit, _) => (true, lit),
+                _ => return Ok(None),
+            },
+            // Unary plus just unwraps the literal
+            ValueExpr::UnaryPlus(inner, _) => match inner.as_ref() {
+                ValueExpr::Literal(lit, _) => (false, lit),
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        if !negate {
+            return Ok(Some(lit.clone()));
+        }
+        match lit {
+            ValueLiteral::Integer(n) => Ok(Some(ValueLiteral::Integer(-n))),
+            ValueLiteral::Float(f) => Ok(Some(ValueLiteral::Float(-f))),
+            ValueLiteral::Decimal(d) => Ok(Some(ValueLiteral::Decimal(-*d))),
+            ValueLiteral::Interval(i) => i.checked_neg()
+                .map(|i| Some(ValueLiteral::Interval(i)))
+                .ok_or_else(|| Self::error(
+                    ParseErrorKind::InvalidTypedLiteral,
+                    expr.span(),
+                    "Negated interval is out of range".to_string(),
+                )),
+            _ => Err(Self::error(
+                ParseErrorKind::InvalidUnaryMinus,
+                expr.span(),
+                format!("Cannot apply unary minus to {}", Self::literal_type_name(lit)),
+            )),
         }
     }
 
@@ -274,6 +526,10 @@ impl Parser {
         match lit {
             ValueLiteral::Integer(_) => "integer",
             ValueLiteral::Float(_) => "float",
+            ValueLiteral::Decimal(_) => "decimal",
+            ValueLiteral::Date(_) => "date",
+            ValueLiteral::Timestamp(_) => "timestamp",
+            ValueLiteral::Interval(_) => "interval",
             ValueLiteral::String(_) => "string",
             ValueLiteral::Null => "NULL",
             ValueLiteral::Boolean(_) => "boolean",
@@ -281,14 +537,18 @@ impl Parser {
     }
 
     /// Check if two literals are type-compatible for BETWEEN
-    /// Both must be numeric (Integer or Float) OR both must be String
+    /// Both must be numeric (Integer, Float or Decimal), both dates or timestamps,
+    /// both intervals OR both must be String
     fn are_between_compatible(lower: &ValueLiteral, upper: &ValueLiteral) -> bool {
         match (lower, upper) {
             // Both numeric
-            (ValueLiteral::Integer(_), ValueLiteral::Integer(_)) => true,
-            (ValueLiteral::Integer(_), ValueLiteral::Float(_)) => true,
-            (ValueLiteral::Float(_), ValueLiteral::Integer(_)) => true,
-            (ValueLiteral::Float(_), ValueLiteral::Float(_)) => true,
+            (ValueLiteral::Integer(_) | ValueLiteral::Float(_) | ValueLiteral::Decimal(_),
+             ValueLiteral::Integer(_) | ValueLiteral::Float(_) | ValueLiteral::Decimal(_)) => true,
+            // Both points in time
+            (ValueLiteral::Date(_) | ValueLiteral::Timestamp(_),
+             ValueLiteral::Date(_) | ValueLiteral::Timestamp(_)) => true,
+            // Both intervals
+            (ValueLiteral::Interval(_), ValueLiteral::Interval(_)) => true,
             // Both string
             (ValueLiteral::String(_), ValueLiteral::String(_)) => true,
             // Everything else incompatible
@@ -297,94 +557,104 @@ impl Parser {
     }
 
     /// Validate literal for IN list (reject Null and Boolean)
-    fn validate_in_literal(&self, lit: &ValueLiteral) -> ParseResult<()> {
+    fn validate_in_literal(lit: &ValueLiteral, span: Span) -> ParseResult<()> {
         match lit {
-            ValueLiteral::Null => Err(ParseError {
-                message: format!(
-                    "NULL is not allowed in IN list near position {} in:\n  {}",
-                    self.position, self.input
-                ),
-            }),
-            ValueLiteral::Boolean(_) => Err(ParseError {
-                message: format!(
-                    "Boolean literals are not allowed in IN list near position {} in:\n  {}",
-                    self.position, self.input
-                ),
-            }),
-            ValueLiteral::Integer(_) | ValueLiteral::Float(_) | ValueLiteral::String(_) => Ok(()),
+            ValueLiteral::Null => Err(Self::error(
+                ParseErrorKind::InvalidInListValue,
+                span,
+                "NULL is not allowed in IN list".to_string(),
+            )),
+            ValueLiteral::Boolean(_) => Err(Self::error(
+                ParseErrorKind::InvalidInListValue,
+                span,
+                "Boolean literals are not allowed in IN list".to_string(),
+            )),
+            ValueLiteral::Integer(_) | ValueLiteral::Float(_) | ValueLiteral::Decimal(_) | ValueLiteral::String(_) |
+            ValueLiteral::Date(_) | ValueLiteral::Timestamp(_) | ValueLiteral::Interval(_) => Ok(()),
         }
     }
 
+    /// Check if two literals are type-compatible as CASE results: booleans,
+    /// or the types BETWEEN bounds may mix
+    fn are_case_compatible(a: &ValueLiteral, b: &ValueLiteral) -> bool {
+        matches!((a, b), (ValueLiteral::Boolean(_), ValueLiteral::Boolean(_))) || Self::are_between_compatible(a, b)
+    }
+
     /// Check if two literals are exactly the same type (for IN list)
     /// No mixing of Integer and Float allowed
     fn are_exact_same_type(a: &ValueLiteral, b: &ValueLiteral) -> bool {
         matches!((a, b), 
             (ValueLiteral::Integer(_), ValueLiteral::Integer(_)) |
             (ValueLiteral::Float(_), ValueLiteral::Float(_))     |
+            (ValueLiteral::Decimal(_), ValueLiteral::Decimal(_)) |
+            (ValueLiteral::Date(_), ValueLiteral::Date(_))       |
+            (ValueLiteral::Timestamp(_), ValueLiteral::Timestamp(_)) |
+            (ValueLiteral::Interval(_), ValueLiteral::Interval(_)) |
             (ValueLiteral::String(_), ValueLiteral::String(_)))
     }
 
     /// Validate BETWEEN bounds: lower must be <= upper
-    fn validate_between_bounds(lower: &ValueLiteral, upper: &ValueLiteral, input: &str, position: usize) -> ParseResult<()> {
+    fn validate_between_bounds(lower: &ValueLiteral, upper: &ValueLiteral, span: Span) -> ParseResult<()> {
         match (lower, upper) {
-            // Integer comparison
-            (ValueLiteral::Integer(l), ValueLiteral::Integer(u)) => {
-                if l > u {
-                    return Err(ParseError {
-                        message: format!(
-                            "BETWEEN lower bound ({}) must be less than or equal to upper bound ({}) near position {} in:\n  {}",
-                            l, u, position, input
-                        ),
-                    });
-                }
-            }
-            // Float comparison
-            (ValueLiteral::Float(l), ValueLiteral::Float(u)) => {
-                if l > u {
-                    return Err(ParseError {
-                        message: format!(
-                            "BETWEEN lower bound ({}) must be less than or equal to upper bound ({}) near position {} in:\n  {}",
-                            l, u, position, input
-                        ),
-                    });
-                }
-            }
-            // Mixed numeric: Integer and Float
-            (ValueLiteral::Integer(l), ValueLiteral::Float(u)) => {
-                if (*l as f64) > *u {
-                    return Err(ParseError {
-                        message: format!(
-                            "BETWEEN lower bound ({}) must be less than or equal to upper bound ({}) near position {} in:\n  {}",
-                            l, u, position, input
-                        ),
-                    });
-                }
-            }
-            (ValueLiteral::Float(l), ValueLiteral::Integer(u)) => {
-                if *l > (*u as f64) {
-                    return Err(ParseError {
-                        message: format!(
-                            "BETWEEN lower bound ({}) must be less than or equal to upper bound ({}) near position {} in:\n  {}",
-                            l, u, position, input
-                        ),
-                    });
-                }
+            // Numeric bounds, compared exactly across integers, floats and decimals
+            // as the evaluator compares them
+            (ValueLiteral::Integer(_) | ValueLiteral::Float(_) | ValueLiteral::Decimal(_), _)
+                if Self::numeric_literal_order(lower, upper) == Some(Ordering::Greater) =>
+            {
+                Err(Self::error(ParseErrorKind::ReversedBetweenBounds, span, format!(
+                    "BETWEEN lower bound ({}) must be less than or equal to upper bound ({})",
+                    lower, upper
+                )))
+            }
+            // Dates, timestamps and intervals
+            (ValueLiteral::Date(_) | ValueLiteral::Timestamp(_) | ValueLiteral::Interval(_), _)
+                if Self::temporal_literal_order(lower, upper) == Some(Ordering::Greater) =>
+            {
+                Err(Self::error(ParseErrorKind::ReversedBetweenBounds, span, format!(
+                    "BETWEEN lower bound ({}) must be less than or equal to upper bound ({})",
+                    lower, upper
+                )))
             }
             // String comparison
-            (ValueLiteral::String(l), ValueLiteral::String(u)) => {
-                if l > u {
-                    return Err(ParseError {
-                        message: format!(
-                            "BETWEEN lower bound ('{}') must be less than or equal to upper bound ('{}') near position {} in:\n  {}",
-                            l, u, position, input
-                        ),
-                    });
-                }
-            }
-            // Other combinations should have been caught by type compatibility check
-            _ => {}
+            (ValueLiteral::String(l), ValueLiteral::String(u)) if l > u => {
+                Err(Self::error(ParseErrorKind::ReversedBetweenBounds, span, format!(
+                    "BETWEEN lower bound ('{}') must be less than or equal to upper bound ('{}')",
+                    l, u
+                )))
+            }
+            // Bounds are ordered, or other combinations that should have been
+            // caught by the type compatibility check
+            _ => Ok(()),
+        }
+    }
+
+    /// Order of two numeric literals, exact across integers, floats and decimals
+    fn numeric_literal_order(a: &ValueLiteral, b: &ValueLiteral) -> Option<Ordering> {
+        match (a, b) {
+            (ValueLiteral::Integer(a), ValueLiteral::Integer(b)) => Some(a.cmp(b)),
+            (ValueLiteral::Float(a), ValueLiteral::Float(b)) => Some(numeric::cmp_f64(*a, *b)),
+            (ValueLiteral::Decimal(a), ValueLiteral::Decimal(b)) => Some(a.cmp(b)),
+            (ValueLiteral::Integer(a), ValueLiteral::Float(b)) => Some(numeric::cmp_i64_f64(*a, *b)),
+            (ValueLiteral::Float(a), ValueLiteral::Integer(b)) => Some(numeric::cmp_i64_f64(*b, *a).reverse()),
+            (ValueLiteral::Decimal(a), ValueLiteral::Integer(b)) => Some(a.cmp(&Decimal::from(*b))),
+            (ValueLiteral::Integer(a), ValueLiteral::Decimal(b)) => Some(Decimal::from(*a).cmp(b)),
+            (ValueLiteral::Decimal(a), ValueLiteral::Float(b)) => Some(numeric::cmp_decimal_f64(*a, *b)),
+            (ValueLiteral::Float(a), ValueLiteral::Decimal(b)) => Some(numeric::cmp_decimal_f64(*b, *a).reverse()),
+            _ => None,
+        }
+    }
+
+    /// Order of two date, timestamp or interval literals; a date stands for
+    /// midnight UTC when compared with a timestamp
+    fn temporal_literal_order(a: &ValueLiteral, b: &ValueLiteral) -> Option<Ordering> {
+        match (a, b) {
+            (ValueLiteral::Date(a), ValueLiteral::Date(b)) => Some(a.cmp(b)),
+            (ValueLiteral::Timestamp(a), ValueLiteral::Timestamp(b)) => Some(a.cmp(b)),
+            (ValueLiteral::Date(a), ValueLiteral::Timestamp(b)) => Some(Timestamp::from(*a).cmp(b)),
+            (ValueLiteral::Timestamp(a), ValueLiteral::Date(b)) => Some(a.cmp(&Timestamp::from(*b))),
+            (ValueLiteral::Interval(a), ValueLiteral::Interval(b)) => Some(a.cmp(b)),
+            _ => None,
         }
-        Ok(())
     }
 
     // ========================================================================
@@ -398,12 +668,17 @@ impl Parser {
 
     /// BooleanOrExpression = BooleanAndExpression { "OR" BooleanAndExpression }
     fn parse_boolean_or_expression(&mut self) -> ParseResult<BooleanExpr> {
+        let start = self.current_span();
         let mut left = self.parse_boolean_and_expression()?;
+        // The links of the chain share a level (see `BooleanExpr::depth`)
+        let mut depth = if left.continues_chain(LogicalOp::Or) { self.depth } else { self.depth + 1 };
 
         while self.current_token() == &Token::Or {
             self.advance();
             let right = self.parse_boolean_and_expression()?;
-            left = BooleanExpr::Or(Box::new(left), Box::new(right));
+            depth = depth.max(self.depth + 1);
+            left = BooleanExpr::Or(Box::new(left), Box::new(right), self.span_from(start));
+            self.set_depth(depth, left.span())?;
         }
 
         Ok(left)
@@ -411,12 +686,16 @@ impl Parser {
 
     /// BooleanAndExpression = BooleanTerm { "AND" BooleanTerm }
     fn parse_boolean_and_expression(&mut self) -> ParseResult<BooleanExpr> {
+        let start = self.current_span();
         let mut left = self.parse_boolean_term()?;
+        let mut depth = if left.continues_chain(LogicalOp::And) { self.depth } else { self.depth + 1 };
 
         while self.current_token() == &Token::And {
             self.advance();
             let right = self.parse_boolean_term()?;
-            left = BooleanExpr::And(Box::new(left), Box::new(right));
+            depth = depth.max(self.depth + 1);
+            left = BooleanExpr::And(Box::new(left), Box::new(right), self.span_from(start));
+            self.set_depth(depth, left.span())?;
         }
 
         Ok(left)
@@ -426,13 +705,20 @@ impl Parser {
     ///             | "(" BooleanExpression ")"
     ///             | BooleanLiteral
     ///             | Variable
+    ///             | Path
     ///             | RelationalExpression
     fn parse_boolean_term(&mut self) -> ParseResult<BooleanExpr> {
+        let start = self.current_span();
+        self.set_depth(1, start)?;
         match self.current_token() {
             Token::Not => {
                 self.advance();
+                self.enter(start)?;
                 let expr = self.parse_boolean_term()?;
-                Ok(BooleanExpr::Not(Box::new(expr)))
+                self.leave();
+                let span = self.span_from(start);
+                self.set_depth(self.depth + 1, span)?;
+                Ok(BooleanExpr::Not(Box::new(expr), span))
             }
             Token::LeftParen => {
                 // Need to distinguish between:
@@ -447,49 +733,73 @@ impl Parser {
                 // Special case: check if this is a parenthesized boolean expression
                 // by looking for boolean operators or seeing if it's a complete relational expr
                 let saved_pos = self.position;
-
-                // Try parsing as a boolean expression first
-                match self.parse_boolean_expression() {
-                    Ok(expr) => {
-                        if self.current_token() == &Token::RightParen {
+                let saved_nesting = self.nesting;
+
+                // Try parsing as a boolean expression first, unless that already
+                // failed here; a CASE condition inside the parentheses would
+                // otherwise be parsed twice per level of nesting
+                if !self.not_boolean.contains(&saved_pos) {
+                    self.enter(start)?;
+                    match self.parse_boolean_expression() {
+                        Ok(expr) if self.current_token() == &Token::RightParen => {
                             self.advance(); // consume ')'
-                            Ok(expr)
-                        } else {
-                            // Failed to find closing paren, might be (value_expr) OP ...
-                            // Backtrack and try as relational
-                            self.position = saved_pos - 1; // go back before '('
-                            let rel = self.parse_relational_expression()?;
-                            Ok(BooleanExpr::Relational(rel))
+                            self.leave();
+                            return Ok(expr);
+                        }
+                        // Too deep as a boolean expression is too deep as a value expression
+                        Err(e) if e.kind == ParseErrorKind::NestingTooDeep => return Err(e),
+                        // Not a parenthesized boolean expression, might be (value_expr) OP ...
+                        _ => {
+                            self.not_boolean.insert(saved_pos);
                         }
-                    }
-                    Err(_) => {
-                        // Failed to parse as boolean, try as relational
-                        self.position = saved_pos - 1; // go back before '('
-                        let rel = self.parse_relational_expression()?;
-                        Ok(BooleanExpr::Relational(rel))
                     }
                 }
+
+                // Backtrack and try as relational
+                self.position = saved_pos - 1; // go back before '('
+                self.nesting = saved_nesting;
+                let rel = self.parse_relational_expression()?;
+                Ok(BooleanExpr::Relational(Box::new(rel)))
             }
             Token::True => {
                 self.advance();
-                Ok(BooleanExpr::Literal(true))
+                Ok(BooleanExpr::Literal(true, start))
             }
             Token::False => {
                 self.advance();
-                Ok(BooleanExpr::Literal(false))
+                Ok(BooleanExpr::Literal(false, start))
+            }
+            Token::Identifier(_) if matches!(self.peek_token(), Token::Dot | Token::LeftBracket) => {
+                // A path is a boolean variable unless an operator follows it
+                let saved_pos = self.position;
+                self.advance();
+                let segments = self.parse_path_segments()?;
+                if Self::is_relational_operator(self.current_token()) || Self::is_arithmetic_operator(self.current_token()) {
+                    self.position = saved_pos;
+                    let rel = self.parse_relational_expression()?;
+                    Ok(BooleanExpr::Relational(Box::new(rel)))
+                } else {
+                    let Token::Identifier(root) = &self.tokens[saved_pos].token else { unreachable!() };
+                    Ok(BooleanExpr::Path(root.clone(), segments, self.span_from(start)))
+                }
             }
             Token::Identifier(_) => {
                 // Could be a variable or start of relational expression
                 // We need to look ahead to determine which
-                if self.is_relational_operator_ahead() || self.is_arithmetic_operator_ahead() {
+                if self.is_relational_operator_ahead()
+                    || self.is_arithmetic_operator_ahead()
+                    || self.peek_token() == &Token::LeftParen
+                    || self.typed_literal_ahead()
+                    || self.case_ahead()
+                {
                     let rel = self.parse_relational_expression()?;
-                    Ok(BooleanExpr::Relational(rel))
+                    Ok(BooleanExpr::Relational(Box::new(rel)))
                 } else {
                     // It's a variable (boolean at runtime)
                     if let Token::Identifier(name) = self.current_token() {
                         let name = name.clone();
                         self.advance();
-                        Ok(BooleanExpr::Variable(name))
+                        Ok(BooleanExpr::Variable(name, start))
                     } else {
                         unreachable!()
                     }
@@ -499,7 +809,7 @@ impl Parser {
                 // Default case: try to parse as relational expression
                 // This includes literals, etc.
                 let rel = self.parse_relational_expression()?;
-                Ok(BooleanExpr::Relational(rel))
+                Ok(BooleanExpr::Relational(Box::new(rel)))
             }
         }
     }
@@ -507,22 +817,95 @@ impl Parser {
     /// Check if a relational operator follows
     fn is_relational_operator_ahead(&self) -> bool {
         // Look ahead to see if there's a relational operator
-        let next = self.peek_token();
-        matches!(next,
+        Self::is_relational_operator(self.peek_token())
+    }
+
+    /// Check if a token starts a relational operator
+    fn is_relational_operator(token: &Token) -> bool {
+        matches!(token,
             Token::Equal | Token::NotEqual |
             Token::GreaterThan | Token::GreaterOrEqual |
             Token::LessThan | Token::LessOrEqual |
             Token::Like | Token::Between | Token::In | Token::Is |
-            Token::Not  // For NOT LIKE, NOT BETWEEN, NOT IN
-        )
+            Token::Not  // For NOT LIKE, NOT BETWEEN, NOT IN, NOT CONTAINS
+        ) || Self::is_contains(token) || Self::is_pattern_operator(token)
+    }
+
+    /// Check if a token starts a pattern match: LIKE, ILIKE, REGEXP or SIMILAR
+    /// TO.  Only LIKE is reserved; the others are keywords after a value
+    /// expression alone, so they remain usable as variable names.
+    fn is_pattern_operator(token: &Token) -> bool {
+        token == &Token::Like || matches!(token, Token::Identifier(word)
+            if ["ILIKE", "REGEXP", "SIMILAR"].iter().any(|keyword| word.eq_ignore_ascii_case(keyword)))
+    }
+
+    /// Check if a token is the identifier `keyword`, ignoring case
+    fn is_keyword(token: &Token, keyword: &str) -> bool {
+        matches!(token, Token::Identifier(word) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    /// Check if a token is CONTAINS.  It is only a keyword after a value
+    /// expression, so it remains usable as a variable name.
+    fn is_contains(token: &Token) -> bool {
+        matches!(token, Token::Identifier(word) if word.eq_ignore_ascii_case("CONTAINS"))
+    }
+
+    /// Check if the current token starts a quantifier: ANY or ALL followed by
+    /// an opening parenthesis
+    fn quantifier_ahead(&self) -> bool {
+        matches!(self.current_token(), Token::Identifier(word)
+            if word.eq_ignore_ascii_case("ANY") || word.eq_ignore_ascii_case("ALL"))
+            && self.peek_token() == &Token::LeftParen
+    }
+
+    /// Check if the current token starts a typed literal: DATE, TIMESTAMP,
+    /// INTERVAL or DECIMAL followed by a string.  These words are only keywords in front of
+    /// a string, so they remain usable as variable names.
+    fn typed_literal_ahead(&self) -> bool {
+        let keyword = match self.current_token() {
+            Token::Identifier(name) => name,
+            _ => return false,
+        };
+        ["DATE", "TIMESTAMP", "INTERVAL", "DECIMAL"].iter().any(|k| keyword.eq_ignore_ascii_case(k))
+            && matches!(self.peek_token(), Token::StringLiteral(_))
+    }
+
+    /// Check if the current token starts a CASE expression: CASE followed by
+    /// WHEN or by the start of an operand.  Followed by an operator, CASE is a
+    /// variable name, and followed by "(" it is a function name if a function
+    /// of that name is registered.
+    fn case_ahead(&self) -> bool {
+        if !Self::is_keyword(self.current_token(), "CASE") {
+            return false;
+        }
+        match self.peek_token() {
+            Token::Identifier(word) => !["CONTAINS", "ILIKE", "REGEXP", "SIMILAR", "COLLATE", "THEN", "ELSE", "END"]
+                .iter()
+                .any(|keyword| word.eq_ignore_ascii_case(keyword)),
+            Token::LeftParen => self.options.functions.get("CASE").is_none(),
+            Token::IntegerLiteral(_) | Token::FloatLiteral(_) | Token::DecimalLiteral(_) | Token::StringLiteral(_)
+            | Token::PositionalParameter(_) | Token::NamedParameter(_)
+            | Token::Null | Token::True | Token::False => true,
+            _ => false,
+        }
+    }
+
+    /// Check if a token ends part of a CASE expression: WHEN, THEN, ELSE or END
+    fn is_case_keyword(token: &Token) -> bool {
+        matches!(token, Token::Identifier(word)
+            if ["WHEN", "THEN", "ELSE", "END"].iter().any(|keyword| word.eq_ignore_ascii_case(keyword)))
     }
 
     /// Check if an arithmetic operator follows
     fn is_arithmetic_operator_ahead(&self) -> bool {
         // Look ahead to see if there's an arithmetic operator
-        let next = self.peek_token();
-        matches!(next,
-            Token::Plus | Token::Minus | Token::Star | Token::Slash | Token::Percent
+        Self::is_arithmetic_operator(self.peek_token())
+    }
+
+    /// Check if a token is an arithmetic operator, counting `||` as one
+    fn is_arithmetic_operator(token: &Token) -> bool {
+        matches!(token,
+            Token::Plus | Token::Minus | Token::Star | Token::Slash | Token::Percent | Token::Concat
         )
     }
 
@@ -534,257 +917,182 @@ impl Parser {
     ///                      | ComparisonExpression
     ///                      | IsNullExpression
     fn parse_relational_expression(&mut self) -> ParseResult<RelationalExpr> {
+        let start = self.current_span();
         let left = self.parse_value_expression()?;
+        // Deepest operand so far
+        let mut depth = self.depth;
 
-        match self.current_token() {
+        let rel = match self.current_token() {
             Token::Equal => {
                 self.advance();
                 let right = self.parse_value_expression()?;
+                depth = depth.max(self.depth);
                 Ok(RelationalExpr::Equality {
                     left,
                     op: EqualityOp::Equal,
                     right,
+                    span: self.span_from(start),
                 })
             }
             Token::NotEqual => {
                 self.advance();
                 let right = self.parse_value_expression()?;
+                depth = depth.max(self.depth);
                 Ok(RelationalExpr::Equality {
                     left,
                     op: EqualityOp::NotEqual,
                     right,
+                    span: self.span_from(start),
                 })
             }
             Token::GreaterThan => {
                 self.advance();
                 let right = self.parse_value_expression()?;
+                depth = depth.max(self.depth);
                 Ok(RelationalExpr::Comparison {
                     left,
                     op: ComparisonOp::GreaterThan,
                     right,
+                    span: self.span_from(start),
                 })
             }
             Token::GreaterOrEqual => {
                 self.advance();
                 let right = self.parse_value_expression()?;
+                depth = depth.max(self.depth);
                 Ok(RelationalExpr::Comparison {
                     left,
                     op: ComparisonOp::GreaterOrEqual,
                     right,
+                    span: self.span_from(start),
                 })
             }
             Token::LessThan => {
                 self.advance();
                 let right = self.parse_value_expression()?;
+                depth = depth.max(self.depth);
                 Ok(RelationalExpr::Comparison {
                     left,
                     op: ComparisonOp::LessThan,
                     right,
+                    span: self.span_from(start),
                 })
             }
             Token::LessOrEqual => {
                 self.advance();
                 let right = self.parse_value_expression()?;
+                depth = depth.max(self.depth);
                 Ok(RelationalExpr::Comparison {
                     left,
                     op: ComparisonOp::LessOrEqual,
                     right,
+                    span: self.span_from(start),
                 })
             }
-            Token::Like => {
-                self.advance();
-                let pattern = self.expect_string_literal()?;
-                let escape = if self.current_token() == &Token::Escape {
-                    self.advance();
-                    Some(self.expect_string_literal()?)
-                } else {
-                    None
-                };
-                Ok(RelationalExpr::Like {
-                    expr: left,
-                    pattern,
-                    escape,
-                    negated: false,
-                })
-            }
+            token if Self::is_pattern_operator(token) => self.parse_pattern_match(left, false, start),
             Token::Not => {
                 self.advance();
                 match self.current_token() {
-                    Token::Like => {
+                    token if Self::is_pattern_operator(token) => self.parse_pattern_match(left, true, start),
+                    Token::Between => {
                         self.advance();
-                        let pattern = self.expect_string_literal()?;
-                        let escape = if self.current_token() == &Token::Escape {
-                            self.advance();
-                            Some(self.expect_string_literal()?)
-                        } else {
-                            None
-                        };
-                        Ok(RelationalExpr::Like {
+                        let (lower, upper) = self.parse_between_bounds("NOT BETWEEN")?;
+                        depth = depth.max(self.depth);
+                        Ok(RelationalExpr::Between {
                             expr: left,
-                            pattern,
-                            escape,
+                            lower,
+                            upper,
                             negated: true,
+                            span: self.span_from(start),
                         })
                     }
-                    Token::Between => {
+                    Token::In if self.peek_token() != &Token::LeftParen => {
                         self.advance();
-                        let lower_expr = self.parse_value_expression()?;
-                        self.expect(Token::And)?;
-                        let upper_expr = self.parse_value_expression()?;
-
-                        // Extract literals from expressions
-                        let lower_lit = Self::extract_literal(&lower_expr)?;
-                        let upper_lit = Self::extract_literal(&upper_expr)?;
-
-                        // Reject NULL
-                        if matches!(lower_lit, ValueLiteral::Null) {
-                            return Err(ParseError {
-                                message: format!(
-                                    "NULL is not allowed as lower bound in NOT BETWEEN near position {} in:\n  {}",
-                                    self.position, self.input
-                                ),
-                            });
-                        }
-                        if matches!(upper_lit, ValueLiteral::Null) {
-                            return Err(ParseError {
-                                message: format!(
-                                    "NULL is not allowed as upper bound in NOT BETWEEN near position {} in:\n  {}",
-                                    self.position, self.input
-                                ),
-                            });
-                        }
-
-                        // Reject Boolean
-                        if matches!(lower_lit, ValueLiteral::Boolean(_)) {
-                            return Err(ParseError {
-                                message: format!(
-                                    "Boolean literals are not allowed as lower bound in NOT BETWEEN near position {} in:\n  {}",
-                                    self.position, self.input
-                                ),
-                            });
-                        }
-                        if matches!(upper_lit, ValueLiteral::Boolean(_)) {
-                            return Err(ParseError {
-                                message: format!(
-                                    "Boolean literals are not allowed as upper bound in NOT BETWEEN near position {} in:\n  {}",
-                                    self.position, self.input
-                                ),
-                            });
-                        }
-
-                        // Check type compatibility
-                        if !Self::are_between_compatible(&lower_lit, &upper_lit) {
-                            return Err(ParseError {
-                                message: format!(
-                                    "NOT BETWEEN bounds must be both numeric or both string, found {} and {} near position {} in:\n  {}",
-                                    Self::literal_type_name(&lower_lit),
-                                    Self::literal_type_name(&upper_lit),
-                                    self.position,
-                                    self.input
-                                ),
-                            });
-                        }
-
-                        // Validate bounds order: lower <= upper
-                        Self::validate_between_bounds(&lower_lit, &upper_lit, &self.input, self.position)?;
-
-                        Ok(RelationalExpr::Between {
+                        let collection = self.parse_value_expression()?;
+                        depth = depth.max(self.depth);
+                        Ok(RelationalExpr::InCollection {
                             expr: left,
-                            lower: lower_expr,
-                            upper: upper_expr,
+                            collection,
                             negated: true,
+                            span: self.span_from(start),
                         })
                     }
                     Token::In => {
                         self.advance();
-                        let values = self.parse_string_list()?;
+                        let values = self.parse_in_list()?;
+                        depth = depth.max(self.depth);
                         Ok(RelationalExpr::In {
                             expr: left,
                             values,
                             negated: true,
+                            span: self.span_from(start),
                         })
                     }
-                    _ => Err(ParseError {
-                        message: format!("Expected LIKE, BETWEEN, or IN after NOT, got {} near position {} in:\n  {}", self.current_token(), self.position, self.input),
-                    }),
+                    token if Self::is_contains(token) => {
+                        self.advance();
+                        let value = self.parse_value_expression()?;
+                        depth = depth.max(self.depth);
+                        Ok(RelationalExpr::Contains {
+                            collection: left,
+                            value,
+                            negated: true,
+                            span: self.span_from(start),
+                        })
+                    }
+                    _ => Err(self.unexpected(
+                        &["LIKE", "ILIKE", "REGEXP", "SIMILAR", "BETWEEN", "IN", "CONTAINS"],
+                        format!(
+                            "Expected LIKE, ILIKE, REGEXP, SIMILAR TO, BETWEEN, IN, or CONTAINS after NOT, got {}",
+                            self.current_token(),
+                        ),
+                    )),
                 }
             }
             Token::Between => {
                 self.advance();
-                let lower_expr = self.parse_value_expression()?;
-                self.expect(Token::And)?;
-                let upper_expr = self.parse_value_expression()?;
-
-                // Extract literals from expressions
-                let lower_lit = Self::extract_literal(&lower_expr)?;
-                let upper_lit = Self::extract_literal(&upper_expr)?;
-
-                // Reject NULL
-                if matches!(lower_lit, ValueLiteral::Null) {
-                    return Err(ParseError {
-                        message: format!(
-                            "NULL is not allowed as lower bound in BETWEEN near position {} in:\n  {}",
-                            self.position, self.input
-                        ),
-                    });
-                }
-                if matches!(upper_lit, ValueLiteral::Null) {
-                    return Err(ParseError {
-                        message: format!(
-                            "NULL is not allowed as upper bound in BETWEEN near position {} in:\n  {}",
-                            self.position, self.input
-                        ),
-                    });
-                }
-
-                // Reject Boolean
-                if matches!(lower_lit, ValueLiteral::Boolean(_)) {
-                    return Err(ParseError {
-                        message: format!(
-                            "Boolean literals are not allowed as lower bound in BETWEEN near position {} in:\n  {}",
-                            self.position, self.input
-                        ),
-                    });
-                }
-                if matches!(upper_lit, ValueLiteral::Boolean(_)) {
-                    return Err(ParseError {
-                        message: format!(
-                            "Boolean literals are not allowed as upper bound in BETWEEN near position {} in:\n  {}",
-                            self.position, self.input
-                        ),
-                    });
-                }
-
-                // Check type compatibility
-                if !Self::are_between_compatible(&lower_lit, &upper_lit) {
-                    return Err(ParseError {
-                        message: format!(
-                            "BETWEEN bounds must be both numeric or both string, found {} and {} near position {} in:\n  {}",
-                            Self::literal_type_name(&lower_lit),
-                            Self::literal_type_name(&upper_lit),
-                            self.position,
-                            self.input
-                        ),
-                    });
-                }
-
-                // Validate bounds order: lower <= upper
-                Self::validate_between_bounds(&lower_lit, &upper_lit, &self.input, self.position)?;
-
+                let (lower, upper) = self.parse_between_bounds("BETWEEN")?;
+                depth = depth.max(self.depth);
                 Ok(RelationalExpr::Between {
                     expr: left,
-                    lower: lower_expr,
-                    upper: upper_expr,
+                    lower,
+                    upper,
+                    negated: false,
+                    span: self.span_from(start),
+                })
+            }
+            // IN followed by anything but a parenthesized list tests membership
+            // in the list or map a value expression evaluates to
+            Token::In if self.peek_token() != &Token::LeftParen => {
+                self.advance();
+                let collection = self.parse_value_expression()?;
+                depth = depth.max(self.depth);
+                Ok(RelationalExpr::InCollection {
+                    expr: left,
+                    collection,
                     negated: false,
+                    span: self.span_from(start),
                 })
             }
             Token::In => {
                 self.advance();
-                let values = self.parse_string_list()?;
+                let values = self.parse_in_list()?;
+                depth = depth.max(self.depth);
                 Ok(RelationalExpr::In {
                     expr: left,
                     values,
                     negated: false,
+                    span: self.span_from(start),
+                })
+            }
+            token if Self::is_contains(token) => {
+                self.advance();
+                let value = self.parse_value_expression()?;
+                depth = depth.max(self.depth);
+                Ok(RelationalExpr::Contains {
+                    collection: left,
+                    value,
+                    negated: false,
+                    span: self.span_from(start),
                 })
             }
             Token::Is => {
@@ -799,11 +1107,172 @@ impl Parser {
                 Ok(RelationalExpr::IsNull {
                     expr: left,
                     negated,
+                    span: self.span_from(start),
                 })
             }
-            _ => Err(ParseError {
-                message: format!("Expected relational operator, got {} near position {} in:\n  {}", self.current_token(), self.position, self.input),
-            }),
+            _ => Err(self.unexpected(
+                &[
+                    "=", "<>", "!=", ">", ">=", "<", "<=", "LIKE", "ILIKE", "REGEXP", "SIMILAR",
+                    "BETWEEN", "IN", "CONTAINS", "IS", "NOT",
+                ],
+                format!("Expected relational operator, got {}", self.current_token()),
+            )),
+        }?;
+
+        Self::check_quantifiers(&rel)?;
+        let rel = self.parse_collate(rel, start)?;
+        self.set_depth(depth + 1, rel.span())?;
+        Ok(rel)
+    }
+
+    /// Parse an optional `COLLATE name` ending a predicate.  COLLATE is only a
+    /// keyword in this position, so it remains usable as a variable name.
+    fn parse_collate(&mut self, rel: RelationalExpr, start: Span) -> ParseResult<RelationalExpr> {
+        if !matches!(self.current_token(), Token::Identifier(word) if word.eq_ignore_ascii_case("COLLATE")) {
+            return Ok(rel);
+        }
+        self.advance();
+        let name = match self.current_token() {
+            Token::Identifier(name) => name.clone(),
+            _ => return Err(self.unexpected(
+                &["collation name"],
+                format!("Expected collation name after COLLATE, got {}", self.current_token()),
+            )),
+        };
+        let collation = Collation::from_name(&name).ok_or_else(|| Self::error(
+            ParseErrorKind::UnknownCollation,
+            self.current_span(),
+            format!("Unknown collation '{}', expected BINARY, UNICODE or NOCASE", name),
+        ))?;
+        self.advance();
+        Ok(RelationalExpr::Collated {
+            expr: Box::new(rel),
+            collation,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse the bounds of BETWEEN or NOT BETWEEN, named `operator` in errors.
+    ///
+    /// Bounds may be any value expressions.  A bound that is a literal cannot
+    /// be NULL or boolean, and when both bounds are literals they must be of
+    /// compatible types and in order.  Other bounds are checked when evaluated.
+    fn parse_between_bounds(&mut self, operator: &str) -> ParseResult<(ValueExpr, ValueExpr)> {
+        let lower = self.parse_value_expression()?;
+        let depth = self.depth;
+        self.expect(Token::And)?;
+        let upper = self.parse_value_expression()?;
+        self.depth = self.depth.max(depth);
+
+        let lower_lit = Self::constant_between_bound(&lower, "lower", operator)?;
+        let upper_lit = Self::constant_between_bound(&upper, "upper", operator)?;
+        if let (Some(lower_lit), Some(upper_lit)) = (lower_lit, upper_lit) {
+            // Check type compatibility
+            let bounds_span = lower.span().to(upper.span());
+            if !Self::are_between_compatible(&lower_lit, &upper_lit) {
+                return Err(Self::error(ParseErrorKind::BetweenTypeMismatch, bounds_span, format!(
+                    "{} bounds must be both numeric or both string, found {} and {}",
+                    operator, Self::literal_type_name(&lower_lit), Self::literal_type_name(&upper_lit)
+                )));
+            }
+
+            // Validate bounds order: lower <= upper
+            Self::validate_between_bounds(&lower_lit, &upper_lit, bounds_span)?;
+        }
+        Ok((lower, upper))
+    }
+
+    /// The value of a BETWEEN bound that is a literal, rejecting NULL and Boolean
+    fn constant_between_bound(bound: &ValueExpr, which: &str, operator: &str) -> ParseResult<Option<ValueLiteral>> {
+        match Self::extract_literal(bound)? {
+            Some(ValueLiteral::Null) => Err(Self::error(
+                ParseErrorKind::InvalidBetweenBound,
+                bound.span(),
+                format!("NULL is not allowed as {} bound in {}", which, operator),
+            )),
+            Some(ValueLiteral::Boolean(_)) => Err(Self::error(
+                ParseErrorKind::InvalidBetweenBound,
+                bound.span(),
+                format!("Boolean literals are not allowed as {} bound in {}", which, operator),
+            )),
+            lit => Ok(lit),
+        }
+    }
+
+    /// Check that ANY(...) and ALL(...) only appear as one operand of =, <>, <,
+    /// <=, > or >=
+    fn check_quantifiers(rel: &RelationalExpr) -> ParseResult<()> {
+        let operands: Vec<&ValueExpr> = match rel {
+            RelationalExpr::Equality { left, right, .. } | RelationalExpr::Comparison { left, right, .. } => {
+                Self::check_single_quantifier(left, right)?;
+                vec![Self::quantified_collection(left), Self::quantified_collection(right)]
+            }
+            RelationalExpr::Between { expr, lower, upper, .. } => vec![expr, lower, upper],
+            RelationalExpr::In { expr, values, .. } => std::iter::once(expr).chain(values).collect(),
+            RelationalExpr::InCollection { expr: left, collection: right, .. }
+            | RelationalExpr::Contains { collection: left, value: right, .. } => vec![left, right],
+            RelationalExpr::Like { expr, .. }
+            | RelationalExpr::Regex { expr, .. }
+            | RelationalExpr::IsNull { expr, .. } => vec![expr],
+            RelationalExpr::Collated { expr, .. } => return Self::check_quantifiers(expr),
+        };
+        Self::check_unquantified(operands)
+    }
+
+    /// Check that the operands of a comparison are not both ANY(...) or ALL(...)
+    fn check_single_quantifier(left: &ValueExpr, right: &ValueExpr) -> ParseResult<()> {
+        if let (ValueExpr::Quantified(..), ValueExpr::Quantified(_, _, span)) = (left, right) {
+            return Err(Self::error(
+                ParseErrorKind::MisplacedQuantifier,
+                *span,
+                "Only one operand of a comparison can be ANY(...) or ALL(...)".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The collection of a comparison operand that is ANY(...) or ALL(...),
+    /// or else the operand itself
+    fn quantified_collection(operand: &ValueExpr) -> &ValueExpr {
+        match operand {
+            ValueExpr::Quantified(_, collection, _) => collection,
+            other => other,
+        }
+    }
+
+    /// Check that there is no ANY(...) or ALL(...) within the operands
+    fn check_unquantified<'a>(operands: impl IntoIterator<Item = &'a ValueExpr>) -> ParseResult<()> {
+        match operands.into_iter().find_map(Self::find_quantifier) {
+            Some(span) => Err(Self::error(
+                ParseErrorKind::MisplacedQuantifier,
+                span,
+                "ANY(...) and ALL(...) can only be compared with =, <>, <, <=, > or >=".to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// The span of the first ANY(...) or ALL(...) in a value expression
+    fn find_quantifier(expr: &ValueExpr) -> Option<Span> {
+        match expr {
+            ValueExpr::Quantified(_, _, span) => Some(*span),
+            ValueExpr::Add(..)
+            | ValueExpr::Subtract(..)
+            | ValueExpr::Multiply(..)
+            | ValueExpr::Divide(..)
+            | ValueExpr::Modulo(..)
+            | ValueExpr::Concat(..) => {
+                let (first, links) = expr.chain();
+                Self::find_quantifier(first).or_else(|| links.iter().find_map(|(_, right, _)| Self::find_quantifier(right)))
+            }
+            ValueExpr::UnaryPlus(inner, _) | ValueExpr::UnaryMinus(inner, _) => Self::find_quantifier(inner),
+            ValueExpr::FunctionCall { args, .. } => args.iter().find_map(Self::find_quantifier),
+            // Operands and conditions are checked as they are parsed
+            ValueExpr::Case { branches, else_result, .. } => branches.iter()
+                .map(|branch| &branch.result)
+                .chain(else_result.as_deref())
+                .find_map(Self::find_quantifier),
+            ValueExpr::Literal(..) | ValueExpr::Variable(..) | ValueExpr::Path(..) | ValueExpr::Parameter(..) => None,
         }
     }
 
@@ -815,52 +1284,150 @@ impl Parser {
                 self.advance();
                 Ok(s)
             }
-            _ => Err(ParseError {
-                message: format!("Expected string literal, got {} near position {} in:\n  {}", self.current_token(), self.position, self.input),
-            }),
+            _ => Err(self.unexpected(
+                &["string literal"],
+                format!("Expected string literal, got {}", self.current_token()),
+            )),
         }
     }
 
-    /// Parse value literal list for IN operator with strict type checking
-    /// All values must be the same exact type (Integer, Float, or String)
-    /// NULL and Boolean are rejected
-    fn parse_string_list(&mut self) -> ParseResult<Vec<ValueLiteral>> {
-        self.expect(Token::LeftParen)?;
+    /// Parse LIKE, ILIKE, REGEXP or SIMILAR TO, the current token, and its
+    /// pattern, compiling the pattern
+    fn parse_pattern_match(&mut self, expr: ValueExpr, negated: bool, start: Span) -> ParseResult<RelationalExpr> {
+        let operator = self.current_token().clone();
+        self.advance();
+
+        if Self::is_keyword(&operator, "REGEXP") || Self::is_keyword(&operator, "SIMILAR") {
+            let similar_to = Self::is_keyword(&operator, "SIMILAR");
+            if similar_to {
+                if !Self::is_keyword(self.current_token(), "TO") {
+                    return Err(self.unexpected(
+                        &["TO"],
+                        format!("Expected TO after SIMILAR, got {}", self.current_token()),
+                    ));
+                }
+                self.advance();
+            }
+            let (pattern, escape, pattern_span) = self.parse_pattern_text(similar_to)?;
+            let pattern = if similar_to {
+                RegexPattern::similar_to(&pattern, escape.as_deref())
+            } else {
+                RegexPattern::regexp(&pattern)
+            };
+            return Ok(RelationalExpr::Regex {
+                expr,
+                pattern: pattern.map_err(|e| Self::error(ParseErrorKind::InvalidPattern, pattern_span, e))?,
+                negated,
+                span: self.span_from(start),
+            });
+        }
 
-        let first = self.expect_value_literal()?;
+        let (pattern, escape, pattern_span) = self.parse_pattern_text(true)?;
+        let pattern = LikePattern::new(&pattern, escape.as_deref())
+            .map_err(|e| Self::error(ParseErrorKind::InvalidPattern, pattern_span, e))?;
+        let case_insensitive = operator != Token::Like;
+        if case_insensitive {
+            pattern.prepare(Collation::NoCase);
+        }
+        Ok(RelationalExpr::Like {
+            expr,
+            pattern,
+            negated,
+            case_insensitive,
+            span: self.span_from(start),
+        })
+    }
 
-        // Validate first literal (reject NULL and Boolean)
-        self.validate_in_literal(&first)?;
+    /// Parse a pattern string and, if `escape_allowed`, an optional ESCAPE
+    /// clause; gives the pattern, the escape string and the pattern's span
+    fn parse_pattern_text(&mut self, escape_allowed: bool) -> ParseResult<(String, Option<String>, Span)> {
+        let pattern_span = self.current_span();
+        let pattern = self.expect_string_literal()?;
+        let escape = if escape_allowed && self.current_token() == &Token::Escape {
+            self.advance();
+            Some(self.expect_string_literal()?)
+        } else {
+            None
+        };
+        Ok((pattern, escape, pattern_span))
+    }
 
-        let mut values = vec![first.clone()];
+    /// InList = "(" ValueExpression { "," ValueExpression } ")"
+    ///
+    /// Members that are literals, including signed numbers, are checked here:
+    /// NULL and Boolean are rejected, and all literal members must be the same
+    /// exact type.  Other members are checked against the left operand when
+    /// they are evaluated.
+    fn parse_in_list(&mut self) -> ParseResult<Vec<ValueExpr>> {
+        self.expect(Token::LeftParen)?;
 
-        while self.current_token() == &Token::Comma {
-            self.advance();
-            let next = self.expect_value_literal()?;
-
-            // Validate this literal (reject NULL and Boolean)
-            self.validate_in_literal(&next)?;
-
-            // Check type consistency with first value
-            if !Self::are_exact_same_type(&first, &next) {
-                return Err(ParseError {
-                    message: format!(
-                        "IN list values must all be the same type, found {} and {} near position {} in:\n  {}",
-                        Self::literal_type_name(&first),
-                        Self::literal_type_name(&next),
-                        self.position,
-                        self.input
-                    ),
-                });
+        let mut values = Vec::new();
+        // First literal member, whose type the other literal members must have
+        let mut first_literal: Option<ValueLiteral> = None;
+        // Deepest member so far
+        let mut depth = 0;
+        loop {
+            let start = self.current_span();
+            let member = match self.in_list_literal()? {
+                Some(lit) => {
+                    let span = self.span_from(start);
+                    Self::validate_in_literal(&lit, span)?;
+                    match &first_literal {
+                        Some(first) if !Self::are_exact_same_type(first, &lit) => {
+                            return Err(Self::error(ParseErrorKind::InListTypeMismatch, span, format!(
+                                "IN list values must all be the same type, found {} and {}",
+                                Self::literal_type_name(first),
+                                Self::literal_type_name(&lit)
+                            )));
+                        }
+                        Some(_) => {}
+                        None => first_literal = Some(lit.clone()),
+                    }
+                    depth = depth.max(1);
+                    ValueExpr::Literal(lit, span)
+                }
+                None => {
+                    let member = self.parse_value_expression()?;
+                    depth = depth.max(self.depth);
+                    member
+                }
+            };
+
+            if values.len() == self.options.max_in_list {
+                return Err(Self::error(ParseErrorKind::InListTooLong, member.span(), format!(
+                    "IN list has more than {} values",
+                    self.options.max_in_list
+                )));
             }
+            values.push(member);
 
-            values.push(next);
+            if self.current_token() != &Token::Comma {
+                break;
+            }
+            self.advance();
         }
 
         self.expect(Token::RightParen)?;
+        self.depth = depth;
         Ok(values)
     }
 
+    /// Parse an IN list member that is a single literal, possibly signed.
+    /// Returns `None`, having consumed nothing, if the member is any other
+    /// value expression.
+    fn in_list_literal(&mut self) -> ParseResult<Option<ValueLiteral>> {
+        let saved_pos = self.position;
+        match self.expect_value_literal() {
+            Ok(lit) if matches!(self.current_token(), Token::Comma | Token::RightParen) => Ok(Some(lit)),
+            // Invalid literals such as -'x' are errors, not expressions
+            Err(e) if e.kind != ParseErrorKind::UnexpectedToken => Err(e),
+            _ => {
+                self.position = saved_pos;
+                Ok(None)
+            }
+        }
+    }
+
     /// Expect a value literal token (string, integer, float, etc.)
     /// Also handles unary minus for negative numbers
     fn expect_value_literal(&mut self) -> ParseResult<ValueLiteral> {
@@ -872,12 +1439,30 @@ impl Parser {
             false
         };
 
+        if self.typed_literal_ahead() {
+            let start = self.current_span();
+            return match (self.parse_typed_literal()?, is_negative) {
+                (lit, false) => Ok(lit),
+                (ValueLiteral::Decimal(d), true) => Ok(ValueLiteral::Decimal(-d)),
+                (ValueLiteral::Interval(i), true) => i.checked_neg().map(ValueLiteral::Interval).ok_or_else(|| {
+                    Self::error(ParseErrorKind::InvalidTypedLiteral, self.span_from(start), "Negated interval is out of range".to_string())
+                }),
+                (lit, true) => Err(Self::error(
+                    ParseErrorKind::InvalidUnaryMinus,
+                    self.span_from(start),
+                    format!("Cannot apply unary minus to {}", Self::literal_type_name(&lit)),
+                )),
+            };
+        }
+
         match self.current_token().clone() {
             Token::StringLiteral(s) => {
                 if is_negative {
-                    return Err(ParseError {
-                        message: format!("Cannot apply unary minus to string literal near position {} in:\n  {}", self.position, self.input),
-                    });
+                    return Err(Self::error(
+                        ParseErrorKind::InvalidUnaryMinus,
+                        self.current_span(),
+                        "Cannot apply unary minus to string literal".to_string(),
+                    ));
                 }
                 self.advance();
                 Ok(ValueLiteral::String(s))
@@ -890,66 +1475,129 @@ impl Parser {
                 self.advance();
                 Ok(ValueLiteral::Float(if is_negative { -f } else { f }))
             }
+            Token::DecimalLiteral(d) => {
+                self.advance();
+                Ok(ValueLiteral::Decimal(if is_negative { -d } else { d }))
+            }
             Token::Null => {
                 if is_negative {
-                    return Err(ParseError {
-                        message: format!("Cannot apply unary minus to NULL near position {} in:\n  {}", self.position, self.input),
-                    });
+                    return Err(Self::error(
+                        ParseErrorKind::InvalidUnaryMinus,
+                        self.current_span(),
+                        "Cannot apply unary minus to NULL".to_string(),
+                    ));
                 }
                 self.advance();
                 Ok(ValueLiteral::Null)
             }
             Token::True => {
                 if is_negative {
-                    return Err(ParseError {
-                        message: format!("Cannot apply unary minus to boolean near position {} in:\n  {}", self.position, self.input),
-                    });
+                    return Err(Self::error(
+                        ParseErrorKind::InvalidUnaryMinus,
+                        self.current_span(),
+                        "Cannot apply unary minus to boolean".to_string(),
+                    ));
                 }
                 self.advance();
                 Ok(ValueLiteral::Boolean(true))
             }
             Token::False => {
                 if is_negative {
-                    return Err(ParseError {
-                        message: format!("Cannot apply unary minus to boolean near position {} in:\n  {}", self.position, self.input),
-                    });
+                    return Err(Self::error(
+                        ParseErrorKind::InvalidUnaryMinus,
+                        self.current_span(),
+                        "Cannot apply unary minus to boolean".to_string(),
+                    ));
                 }
                 self.advance();
                 Ok(ValueLiteral::Boolean(false))
             }
-            _ => Err(ParseError {
-                message: format!("Expected literal value, got {} near position {} in:\n  {}", self.current_token(), self.position, self.input),
-            }),
+            _ => Err(self.unexpected(&["literal"], format!("Expected literal value, got {}", self.current_token()))),
         }
     }
 
+    /// TypedLiteral = "DATE" StringLiteral
+    ///              | "TIMESTAMP" StringLiteral
+    ///              | "INTERVAL" StringLiteral [ Identifier ]
+    ///              | "DECIMAL" StringLiteral
+    ///
+    /// Only called when `typed_literal_ahead` holds.  An identifier after an
+    /// interval's string is its unit, so `INTERVAL '7' DAY` is `INTERVAL '7 DAY'`,
+    /// unless it continues a CASE expression.
+    fn parse_typed_literal(&mut self) -> ParseResult<ValueLiteral> {
+        let start = self.current_span();
+        let keyword = match self.current_token() {
+            Token::Identifier(name) => name.to_ascii_uppercase(),
+            _ => unreachable!(),
+        };
+        self.advance();
+        let mut text = match self.current_token() {
+            Token::StringLiteral(text) => text.clone(),
+            _ => unreachable!(),
+        };
+        self.advance();
+
+        let parsed = match keyword.as_str() {
+            "DATE" => text.parse().map(ValueLiteral::Date),
+            "TIMESTAMP" => text.parse().map(ValueLiteral::Timestamp),
+            "DECIMAL" => text.parse().map(ValueLiteral::Decimal),
+            _ => {
+                if let Token::Identifier(unit) = self.current_token()
+                    && !Self::is_case_keyword(self.current_token())
+                {
+                    text = format!("{} {}", text, unit);
+                    self.advance();
+                }
+                text.parse::<Interval>().map(ValueLiteral::Interval)
+            }
+        };
+        parsed.map_err(|message| Self::error(ParseErrorKind::InvalidTypedLiteral, self.span_from(start), message))
+    }
+
     // ========================================================================
     // VALUE EXPRESSION PARSING
     // ========================================================================
 
-    /// ValueExpression = AddExpression
+    /// ValueExpression = ConcatExpression
     fn parse_value_expression(&mut self) -> ParseResult<ValueExpr> {
-        self.parse_add_expression()
+        self.parse_concat_expression()
+    }
+
+    /// ConcatExpression = AddExpression { "||" AddExpression }
+    fn parse_concat_expression(&mut self) -> ParseResult<ValueExpr> {
+        let start = self.current_span();
+        let mut left = self.parse_add_expression()?;
+        // The links of the chain share a level (see `BooleanExpr::depth`)
+        let mut depth = if left.continues_chain(BinaryOp::Concat) { self.depth } else { self.depth + 1 };
+
+        while self.current_token() == &Token::Concat {
+            self.advance();
+            let right = self.parse_add_expression()?;
+            depth = depth.max(self.depth + 1);
+            left = ValueExpr::Concat(Box::new(left), Box::new(right), self.span_from(start));
+            self.set_depth(depth, left.span())?;
+        }
+
+        Ok(left)
     }
 
     /// AddExpression = MultExpression { ( "+" | "-" ) MultExpression }
     fn parse_add_expression(&mut self) -> ParseResult<ValueExpr> {
+        let start = self.current_span();
         let mut left = self.parse_mult_expression()?;
+        let mut depth = if left.continues_chain(BinaryOp::Add) { self.depth } else { self.depth + 1 };
 
         loop {
-            match self.current_token() {
-                Token::Plus => {
-                    self.advance();
-                    let right = self.parse_mult_expression()?;
-                    left = ValueExpr::Add(Box::new(left), Box::new(right));
-                }
-                Token::Minus => {
-                    self.advance();
-                    let right = self.parse_mult_expression()?;
-                    left = ValueExpr::Subtract(Box::new(left), Box::new(right));
-                }
+            let op = match self.current_token() {
+                Token::Plus => BinaryOp::Add,
+                Token::Minus => BinaryOp::Subtract,
                 _ => break,
-            }
+            };
+            self.advance();
+            let right = self.parse_mult_expression()?;
+            depth = depth.max(self.depth + 1);
+            left = ValueExpr::binary(op, left, right, self.span_from(start));
+            self.set_depth(depth, left.span())?;
         }
 
         Ok(left)
@@ -957,27 +1605,22 @@ impl Parser {
 
     /// MultExpression = UnaryValueExpression { ( "*" | "/" | "%" ) UnaryValueExpression }
     fn parse_mult_expression(&mut self) -> ParseResult<ValueExpr> {
+        let start = self.current_span();
         let mut left = self.parse_unary_value_expression()?;
+        let mut depth = if left.continues_chain(BinaryOp::Multiply) { self.depth } else { self.depth + 1 };
 
         loop {
-            match self.current_token() {
-                Token::Star => {
-                    self.advance();
-                    let right = self.parse_unary_value_expression()?;
-                    left = ValueExpr::Multiply(Box::new(left), Box::new(right));
-                }
-                Token::Slash => {
-                    self.advance();
-                    let right = self.parse_unary_value_expression()?;
-                    left = ValueExpr::Divide(Box::new(left), Box::new(right));
-                }
-                Token::Percent => {
-                    self.advance();
-                    let right = self.parse_unary_value_expression()?;
-                    left = ValueExpr::Modulo(Box::new(left), Box::new(right));
-                }
+            let op = match self.current_token() {
+                Token::Star => BinaryOp::Multiply,
+                Token::Slash => BinaryOp::Divide,
+                Token::Percent => BinaryOp::Modulo,
                 _ => break,
-            }
+            };
+            self.advance();
+            let right = self.parse_unary_value_expression()?;
+            depth = depth.max(self.depth + 1);
+            left = ValueExpr::binary(op, left, right, self.span_from(start));
+            self.set_depth(depth, left.span())?;
         }
 
         Ok(left)
@@ -987,64 +1630,328 @@ impl Parser {
     ///                      | "-" UnaryValueExpression
     ///                      | ValuePrimary
     fn parse_unary_value_expression(&mut self) -> ParseResult<ValueExpr> {
+        let start = self.current_span();
         match self.current_token() {
             Token::Plus => {
                 self.advance();
+                self.enter(start)?;
                 let expr = self.parse_unary_value_expression()?;
-                Ok(ValueExpr::UnaryPlus(Box::new(expr)))
+                self.leave();
+                let span = self.span_from(start);
+                self.set_depth(self.depth + 1, span)?;
+                Ok(ValueExpr::UnaryPlus(Box::new(expr), span))
             }
             Token::Minus => {
                 self.advance();
+                self.enter(start)?;
                 let expr = self.parse_unary_value_expression()?;
-                Ok(ValueExpr::UnaryMinus(Box::new(expr)))
+                self.leave();
+                let span = self.span_from(start);
+                self.set_depth(self.depth + 1, span)?;
+                Ok(ValueExpr::UnaryMinus(Box::new(expr), span))
             }
             _ => self.parse_value_primary(),
         }
     }
 
     /// ValuePrimary = ValueLiteral
+    ///              | TypedLiteral
+    ///              | FunctionCall
+    ///              | Path
     ///              | Variable
+    ///              | Parameter
     ///              | "(" ValueExpression ")"
     fn parse_value_primary(&mut self) -> ParseResult<ValueExpr> {
+        let start = self.current_span();
+        self.set_depth(1, start)?;
         match self.current_token().clone() {
             Token::IntegerLiteral(n) => {
                 self.advance();
-                Ok(ValueExpr::Literal(ValueLiteral::Integer(n)))
+                Ok(ValueExpr::Literal(ValueLiteral::Integer(n), start))
             }
             Token::FloatLiteral(n) => {
                 self.advance();
-                Ok(ValueExpr::Literal(ValueLiteral::Float(n)))
+                Ok(ValueExpr::Literal(ValueLiteral::Float(n), start))
+            }
+            Token::DecimalLiteral(n) => {
+                self.advance();
+                Ok(ValueExpr::Literal(ValueLiteral::Decimal(n), start))
             }
             Token::StringLiteral(s) => {
                 self.advance();
-                Ok(ValueExpr::Literal(ValueLiteral::String(s)))
+                Ok(ValueExpr::Literal(ValueLiteral::String(s), start))
             }
             Token::Null => {
                 self.advance();
-                Ok(ValueExpr::Literal(ValueLiteral::Null))
+                Ok(ValueExpr::Literal(ValueLiteral::Null, start))
             }
             Token::True => {
                 self.advance();
-                Ok(ValueExpr::Literal(ValueLiteral::Boolean(true)))
+                Ok(ValueExpr::Literal(ValueLiteral::Boolean(true), start))
             }
             Token::False => {
                 self.advance();
-                Ok(ValueExpr::Literal(ValueLiteral::Boolean(false)))
+                Ok(ValueExpr::Literal(ValueLiteral::Boolean(false), start))
+            }
+            Token::Identifier(_) if self.typed_literal_ahead() => {
+                let lit = self.parse_typed_literal()?;
+                Ok(ValueExpr::Literal(lit, self.span_from(start)))
             }
+            Token::Identifier(_) if self.quantifier_ahead() => self.parse_quantified(),
+            Token::Identifier(_) if self.case_ahead() => self.parse_case(),
             Token::Identifier(name) => {
                 self.advance();
-                Ok(ValueExpr::Variable(name))
+                match self.current_token() {
+                    Token::LeftParen => self.parse_function_call(name, start),
+                    Token::Dot | Token::LeftBracket => {
+                        let segments = self.parse_path_segments()?;
+                        Ok(ValueExpr::Path(name, segments, self.span_from(start)))
+                    }
+                    _ => Ok(ValueExpr::Variable(name, start)),
+                }
+            }
+            Token::PositionalParameter(_) | Token::NamedParameter(_) if !self.options.parameters => {
+                Err(Self::error(
+                    ParseErrorKind::FeatureDisabled,
+                    start,
+                    "Parameter placeholders are not enabled".to_string(),
+                ))
+            }
+            Token::PositionalParameter(n) => {
+                self.advance();
+                Ok(ValueExpr::Parameter(Parameter::Positional(n), start))
+            }
+            Token::NamedParameter(name) => {
+                self.advance();
+                Ok(ValueExpr::Parameter(Parameter::Named(name), start))
             }
             Token::LeftParen => {
                 self.advance();
+                self.enter(start)?;
                 let expr = self.parse_value_expression()?;
                 self.expect(Token::RightParen)?;
+                self.leave();
                 Ok(expr)
             }
-            _ => Err(ParseError {
-                message: format!("Expected value expression, got {}", self.current_token()),
-            }),
+            _ => Err(self.unexpected(
+                &["value expression"],
+                format!("Expected value expression, got {}", self.current_token()),
+            )),
+        }
+    }
+
+    /// CaseExpression = "CASE" [ ValueExpression ] CaseBranch { CaseBranch }
+    ///                  [ "ELSE" ValueExpression ] "END"
+    /// CaseBranch = "WHEN" ( BooleanExpression | ValueExpression ) "THEN" ValueExpression
+    ///
+    /// Only called when `case_ahead` holds.  A searched CASE has boolean
+    /// conditions; a simple CASE has an operand, which may be ANY(...) or
+    /// ALL(...), and WHEN values to compare it with.  Results that are
+    /// literals must be of compatible types; the others are checked against
+    /// them when evaluated.
+    ///
+    /// Each part of a CASE counts as nested a level within it, and a condition
+    /// as two, since parsing one starts again from the top of the grammar.
+    fn parse_case(&mut self) -> ParseResult<ValueExpr> {
+        let start = self.current_span();
+        self.advance();
+        self.enter(start)?;
+        // Deepest operand, WHEN or result so far
+        let mut depth = 0;
+        let operand = if Self::is_keyword(self.current_token(), "WHEN") {
+            None
+        } else {
+            let operand = self.parse_case_part(1, Self::parse_value_expression)?;
+            Self::check_unquantified([Self::quantified_collection(&operand)])?;
+            depth = self.depth;
+            Some(Box::new(operand))
+        };
+
+        let mut branches = Vec::new();
+        while Self::is_keyword(self.current_token(), "WHEN") {
+            self.advance();
+            let when = match &operand {
+                None => CaseWhen::Condition(self.parse_case_part(2, Self::parse_boolean_expression)?),
+                Some(operand) => {
+                    let value = self.parse_case_part(1, Self::parse_value_expression)?;
+                    Self::check_single_quantifier(operand, &value)?;
+                    Self::check_unquantified([Self::quantified_collection(&value)])?;
+                    CaseWhen::Value(value)
+                }
+            };
+            depth = depth.max(self.depth);
+            if !Self::is_keyword(self.current_token(), "THEN") {
+                return Err(self.unexpected(&["THEN"], format!("Expected THEN in CASE, got {}", self.current_token())));
+            }
+            self.advance();
+            let result = self.parse_case_part(1, Self::parse_value_expression)?;
+            depth = depth.max(self.depth);
+            branches.push(CaseBranch { when, result });
+        }
+        if branches.is_empty() {
+            return Err(self.unexpected(&["WHEN"], format!("Expected WHEN in CASE, got {}", self.current_token())));
+        }
+
+        let else_result = if Self::is_keyword(self.current_token(), "ELSE") {
+            self.advance();
+            let result = self.parse_case_part(1, Self::parse_value_expression)?;
+            depth = depth.max(self.depth);
+            Some(Box::new(result))
+        } else {
+            None
+        };
+        if !Self::is_keyword(self.current_token(), "END") {
+            let expected: &[&str] = if else_result.is_some() { &["END"] } else { &["WHEN", "ELSE", "END"] };
+            return Err(self.unexpected(expected, format!("Expected END of CASE, got {}", self.current_token())));
+        }
+        self.advance();
+        self.leave();
+        let span = self.span_from(start);
+        self.set_depth(depth + 1, span)?;
+
+        Self::check_case_results(&branches, else_result.as_deref())?;
+        Ok(ValueExpr::Case { operand, branches, else_result, span })
+    }
+
+    /// Parse an operand, WHEN or result of a CASE with `parse`, counting it as
+    /// nested `levels` levels within the CASE
+    fn parse_case_part<T>(&mut self, levels: usize, parse: impl FnOnce(&mut Self) -> ParseResult<T>)
+        -> ParseResult<T>
+    {
+        let span = self.current_span();
+        self.enter_levels(levels, span)?;
+        let part = parse(self)?;
+        self.leave_levels(levels);
+        Ok(part)
+    }
+
+    /// Check that the CASE results that are literals, including signed
+    /// numbers, are of compatible types.  NULL is compatible with every type.
+    fn check_case_results(branches: &[CaseBranch], else_result: Option<&ValueExpr>) -> ParseResult<()> {
+        // First literal result other than NULL, whose type the others must be compatible with
+        let mut first: Option<ValueLiteral> = None;
+        for result in branches.iter().map(|branch| &branch.result).chain(else_result) {
+            match (Self::extract_literal(result)?, &first) {
+                (None | Some(ValueLiteral::Null), _) => {}
+                (Some(lit), Some(first)) if !Self::are_case_compatible(first, &lit) => {
+                    return Err(Self::error(ParseErrorKind::CaseTypeMismatch, result.span(), format!(
+                        "CASE results must be of compatible types, found {} and {}",
+                        Self::literal_type_name(first),
+                        Self::literal_type_name(&lit)
+                    )));
+                }
+                (Some(_), Some(_)) => {}
+                (Some(lit), None) => first = Some(lit),
+            }
+        }
+        Ok(())
+    }
+
+    /// Quantified = ( "ANY" | "ALL" ) "(" ValueExpression ")"
+    ///
+    /// ANY and ALL are only keywords in front of a parenthesis, so they remain
+    /// usable as variable names.
+    fn parse_quantified(&mut self) -> ParseResult<ValueExpr> {
+        let start = self.current_span();
+        let quantifier = match self.current_token() {
+            Token::Identifier(word) if word.eq_ignore_ascii_case("ANY") => Quantifier::Any,
+            _ => Quantifier::All,
+        };
+        self.advance();
+        self.expect(Token::LeftParen)?;
+        self.enter(start)?;
+        let collection = self.parse_value_expression()?;
+        self.expect(Token::RightParen)?;
+        self.leave();
+        let span = self.span_from(start);
+        self.set_depth(self.depth + 1, span)?;
+        Ok(ValueExpr::Quantified(quantifier, Box::new(collection), span))
+    }
+
+    /// Path = Identifier PathSegment { PathSegment }
+    /// PathSegment = "." Identifier
+    ///             | "[" ( IntegerLiteral | StringLiteral ) "]"
+    ///
+    /// The root identifier has already been consumed.
+    fn parse_path_segments(&mut self) -> ParseResult<Vec<PathSegment>> {
+        let mut segments = Vec::new();
+        loop {
+            match self.current_token() {
+                Token::Dot => {
+                    self.advance();
+                    let Token::Identifier(name) = self.current_token() else {
+                        return Err(self.unexpected(
+                            &["field name"],
+                            format!("Expected field name after '.', got {}", self.current_token()),
+                        ));
+                    };
+                    segments.push(PathSegment::Field(name.clone()));
+                    self.advance();
+                }
+                Token::LeftBracket => {
+                    self.advance();
+                    let segment = match self.current_token() {
+                        Token::StringLiteral(name) => PathSegment::Field(name.clone()),
+                        Token::IntegerLiteral(index) => PathSegment::Index(usize::try_from(*index).map_err(|_| {
+                            Self::error(ParseErrorKind::InvalidNumber, self.current_span(), format!("Invalid list index {}", index))
+                        })?),
+                        _ => return Err(self.unexpected(
+                            &["list index", "quoted field name"],
+                            format!("Expected list index or quoted field name, got {}", self.current_token()),
+                        )),
+                    };
+                    segments.push(segment);
+                    self.advance();
+                    self.expect(Token::RightBracket)?;
+                }
+                _ => return Ok(segments),
+            }
+        }
+    }
+
+    /// FunctionCall = Identifier "(" [ ValueExpression { "," ValueExpression } ] ")"
+    ///
+    /// The function name, which starts at `start`, has already been consumed.  The function must be a built-in
+    /// or registered with the parser, and accept the number of arguments given.
+    fn parse_function_call(&mut self, name: String, start: Span) -> ParseResult<ValueExpr> {
+        self.expect(Token::LeftParen)?;
+
+        self.enter(start)?;
+        let mut args = Vec::new();
+        // Deepest argument so far
+        let mut depth = 0;
+        if self.current_token() != &Token::RightParen {
+            args.push(self.parse_value_expression()?);
+            depth = self.depth;
+            while self.current_token() == &Token::Comma {
+                self.advance();
+                args.push(self.parse_value_expression()?);
+                depth = depth.max(self.depth);
+            }
         }
+        self.expect(Token::RightParen)?;
+        self.leave();
+        let span = self.span_from(start);
+        self.set_depth(depth + 1, span)?;
+
+        // Registered functions take precedence over built-ins of the same name
+        let arity_mismatch = if let Some(function) = self.options.functions.get(&name) {
+            (function.params.len() != args.len())
+                .then(|| (function.name.clone(), function.params.len().to_string()))
+        } else if let Some(builtin) = lookup_builtin(&name) {
+            (!builtin.accepts_arity(args.len()))
+                .then(|| (builtin.name.to_string(), builtin.arity_description()))
+        } else {
+            return Err(Self::error(ParseErrorKind::UnknownFunction, start, format!("Unknown function '{}'", name)));
+        };
+        if let Some((function, expected)) = arity_mismatch {
+            return Err(Self::error(ParseErrorKind::WrongArgumentCount, span, format!(
+                "Function {} expects {} argument(s), got {}",
+                function, expected, args.len()
+            )));
+        }
+
+        Ok(ValueExpr::FunctionCall { name, args, span })
     }
 }
 
@@ -1052,20 +1959,46 @@ impl Parser {
 ///
 /// # Examples
 /// ```
-/// use sqlexpr_rust::parse;
+/// use sqlexpr_rust::{parse, BooleanExpr, ParseError};
 ///
 /// let result: Result<BooleanExpr, ParseError> = parse("x > 5 OR y < 10");
 /// assert!(result.is_ok());
-/// 
+///
 /// let result: Result<BooleanExpr, ParseError> = parse("name LIKE '%test%'");
 /// assert!(result.is_ok());
 /// ```
-
 pub fn parse(input: &str) -> Result<BooleanExpr, ParseError> {
     let mut parser = Parser::new(input)?;
     parser.parse()
 }
 
+/// Parse a SQL boolean expression that may call the user-defined functions in
+/// `functions`.  Calls are checked against the registered arity at parse time.
+///
+/// # Examples
+/// ```
+/// use sqlexpr_rust::{parse_with_functions, FunctionRegistry, RuntimeValue, ValueType};
+///
+/// let mut functions = FunctionRegistry::new();
+/// functions.register("is_enabled", &[ValueType::String], ValueType::Boolean, |_| {
+///     Ok(RuntimeValue::Boolean(true))
+/// });
+///
+/// assert!(parse_with_functions("is_enabled('beta') = TRUE", &functions).is_ok());
+/// assert!(parse_with_functions("is_enabled('beta', 1) = TRUE", &functions).is_err());
+/// assert!(parse_with_functions("is_disabled('beta') = TRUE", &functions).is_err());
+/// ```
+pub fn parse_with_functions(input: &str, functions: &FunctionRegistry) -> Result<BooleanExpr, ParseError> {
+    let mut parser = Parser::new(input)?.with_functions(functions);
+    parser.parse()
+}
+
+/// Parse a SQL boolean expression, accepting the features allowed by `options`.
+pub fn parse_with_options(input: &str, options: &ParserOptions) -> Result<BooleanExpr, ParseError> {
+    let mut parser = Parser::with_options(input, options)?;
+    parser.parse()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
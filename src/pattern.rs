@@ -0,0 +1,520 @@
+//! Pattern matching for SQL Expression Evaluation
+//!
+//! LIKE, ILIKE, REGEXP and SIMILAR TO patterns are always string literals, so
+//! they are compiled once when the expression is parsed and stored in the AST;
+//! a pattern that does not compile is a parse error.
+//!
+//! # LIKE and ILIKE
+//!
+//! LIKE patterns are compiled to [`LikePattern`]s.  Patterns with a single run of
+//! literal text and `%` wildcards only at its ends (exact, prefix, suffix and
+//! contains patterns) are matched with plain string operations.  All other
+//! patterns are translated to an anchored regular expression.
+//!
+//! `%` matches any sequence of characters (including none) and `_` matches exactly
+//! one character.  The ESCAPE character, if given, makes the character following
+//! it match literally.
+//!
+//! Under a collation other than [`Collation::Binary`] the input is matched by its
+//! collation key against a pattern whose literal text has been mapped the same
+//! way.  That pattern is compiled the first time it is needed, and `_` then
+//! matches one character of the key.  ILIKE matches under [`Collation::NoCase`]
+//! and its pattern is compiled for it when parsed.
+//!
+//! # REGEXP and SIMILAR TO
+//!
+//! Both compile to a [`RegexPattern`].  A REGEXP pattern uses the syntax of the
+//! `regex` crate and matches if it matches any part of the input; anchor it
+//! with `^` and `$` to match the whole input.  A SIMILAR TO pattern is the SQL
+//! standard's mix of LIKE and regular expressions: it must match the whole
+//! input, `%` and `_` are LIKE's wildcards, `|`, `*`, `+`, `?`, `{m,n}`,
+//! parentheses and bracket expressions work as in regular expressions, and every
+//! other character, `.` included, matches itself.  Collations do not apply to
+//! either; `(?i)` makes a REGEXP pattern case-insensitive.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::collation::Collation;
+
+/// A compiled LIKE pattern together with the source text it was built from.
+///
+/// Two patterns are equal when their pattern text and ESCAPE character are equal.
+///
+/// # Examples
+/// ```
+/// use sqlexpr_rust::LikePattern;
+///
+/// let pattern = LikePattern::new("50!%%", Some("!")).unwrap();
+/// assert!(pattern.matches("50% off"));
+/// assert!(!pattern.matches("500 off"));
+/// ```
+#[derive(Clone)]
+pub struct LikePattern {
+    pattern: String,
+    escape: Option<String>,
+    matcher: Matcher,
+    /// Matchers for the pattern under the Unicode and NoCase collations
+    collated: [OnceLock<Matcher>; 2],
+}
+
+/// Strategy used to match a pattern
+#[derive(Clone)]
+enum Matcher {
+    /// No wildcards
+    Exact(String),
+    /// `abc%`
+    Prefix(String),
+    /// `%abc`
+    Suffix(String),
+    /// `%abc%`
+    Contains(String),
+    /// Only `%` wildcards: matches every string
+    Any,
+    /// Anything else
+    Regex(Regex),
+}
+
+/// Pattern element after ESCAPE processing
+#[derive(Clone, Copy, PartialEq)]
+enum Piece {
+    Literal(char),
+    AnyChar,
+    AnySequence,
+}
+
+impl LikePattern {
+    /// Compile a LIKE pattern.  Only the first character of `escape` is used as the
+    /// escape character; an escape character at the end of the pattern is ignored.
+    pub fn new(pattern: &str, escape: Option<&str>) -> Result<Self, String> {
+        let pieces = Self::pieces(pattern, escape);
+        let matcher = Self::compile(&pieces)
+            .map_err(|e| format!("Invalid LIKE pattern '{}': {}", pattern, e))?;
+
+        Ok(LikePattern {
+            pattern: pattern.to_string(),
+            escape: escape.map(str::to_string),
+            matcher,
+            collated: Default::default(),
+        })
+    }
+
+    /// The pattern text as written in the expression
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// The ESCAPE string, if one was given
+    pub fn escape(&self) -> Option<&str> {
+        self.escape.as_deref()
+    }
+
+    /// Check whether the whole of `s` matches the pattern
+    pub fn matches(&self, s: &str) -> bool {
+        self.matcher.matches(s)
+    }
+
+    /// Check whether the whole of `s` matches the pattern under a collation
+    ///
+    /// # Examples
+    /// ```
+    /// use sqlexpr_rust::{Collation, LikePattern};
+    ///
+    /// let pattern = LikePattern::new("jo%", None).unwrap();
+    /// assert!(!pattern.matches("JOHN"));
+    /// assert!(pattern.matches_collated("JOHN", Collation::NoCase));
+    /// ```
+    pub fn matches_collated(&self, s: &str, collation: Collation) -> bool {
+        match collation {
+            Collation::Binary => self.matches(s),
+            _ => self.collated_matcher(collation).matches(&collation.key(s)),
+        }
+    }
+
+    /// Compile the pattern for a collation now rather than when it is first
+    /// matched under that collation
+    pub fn prepare(&self, collation: Collation) {
+        if collation != Collation::Binary {
+            self.collated_matcher(collation);
+        }
+    }
+
+    fn collated_matcher(&self, collation: Collation) -> &Matcher {
+        let slot = match collation {
+            Collation::Binary => return &self.matcher,
+            Collation::Unicode => &self.collated[0],
+            Collation::NoCase => &self.collated[1],
+        };
+        slot.get_or_init(|| {
+            let pieces = Self::collate_pieces(&Self::pieces(&self.pattern, self.escape()), collation);
+            // Mapping literal text never makes a pattern that compiled invalid
+            Self::compile(&pieces).expect("a compiled LIKE pattern compiles under any collation")
+        })
+    }
+
+    fn compile(pieces: &[Piece]) -> Result<Matcher, regex::Error> {
+        match Self::fast_path(pieces) {
+            Some(matcher) => Ok(matcher),
+            None => Self::regex(pieces).map(Matcher::Regex),
+        }
+    }
+
+    /// Map each run of literal characters to its collation key
+    fn collate_pieces(pieces: &[Piece], collation: Collation) -> Vec<Piece> {
+        let mut collated = Vec::new();
+        let mut literal = String::new();
+        for piece in pieces {
+            match piece {
+                Piece::Literal(ch) => literal.push(*ch),
+                wildcard => {
+                    collated.extend(collation.key(&literal).chars().map(Piece::Literal));
+                    literal.clear();
+                    collated.push(*wildcard);
+                }
+            }
+        }
+        collated.extend(collation.key(&literal).chars().map(Piece::Literal));
+        collated
+    }
+
+    fn pieces(pattern: &str, escape: Option<&str>) -> Vec<Piece> {
+        let escape_char = escape.and_then(|e| e.chars().next());
+        let mut pieces = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(ch) = chars.next() {
+            if Some(ch) == escape_char {
+                // Escaped character - treat next character literally
+                if let Some(next) = chars.next() {
+                    pieces.push(Piece::Literal(next));
+                }
+            } else if ch == '%' {
+                pieces.push(Piece::AnySequence);
+            } else if ch == '_' {
+                pieces.push(Piece::AnyChar);
+            } else {
+                pieces.push(Piece::Literal(ch));
+            }
+        }
+        pieces
+    }
+
+    /// Match patterns of the form `[%]literal[%]` without the regex engine
+    fn fast_path(pieces: &[Piece]) -> Option<Matcher> {
+        let leading = pieces.iter().take_while(|p| **p == Piece::AnySequence).count();
+        let trailing = pieces[leading..].iter().rev().take_while(|p| **p == Piece::AnySequence).count();
+        let middle = &pieces[leading..pieces.len() - trailing];
+
+        let mut literal = String::new();
+        for piece in middle {
+            match piece {
+                Piece::Literal(ch) => literal.push(*ch),
+                Piece::AnyChar | Piece::AnySequence => return None,
+            }
+        }
+
+        Some(match (leading > 0, trailing > 0) {
+            _ if literal.is_empty() && leading > 0 => Matcher::Any,
+            (false, false) => Matcher::Exact(literal),
+            (false, true) => Matcher::Prefix(literal),
+            (true, false) => Matcher::Suffix(literal),
+            (true, true) => Matcher::Contains(literal),
+        })
+    }
+
+    fn regex(pieces: &[Piece]) -> Result<Regex, regex::Error> {
+        // (?s) lets wildcards match newlines, consistent with the fast paths
+        let mut regex_pattern = String::from("(?s)^");
+        for piece in pieces {
+            match piece {
+                Piece::Literal(ch) => regex_pattern.push_str(&regex::escape(&ch.to_string())),
+                Piece::AnyChar => regex_pattern.push('.'),
+                Piece::AnySequence => regex_pattern.push_str(".*"),
+            }
+        }
+        regex_pattern.push('$');
+        Regex::new(&regex_pattern)
+    }
+}
+
+impl Matcher {
+    fn matches(&self, s: &str) -> bool {
+        match self {
+            Matcher::Exact(lit) => s == lit,
+            Matcher::Prefix(lit) => s.starts_with(lit.as_str()),
+            Matcher::Suffix(lit) => s.ends_with(lit.as_str()),
+            Matcher::Contains(lit) => s.contains(lit.as_str()),
+            Matcher::Any => true,
+            Matcher::Regex(re) => re.is_match(s),
+        }
+    }
+}
+
+impl PartialEq for LikePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern && self.escape == other.escape
+    }
+}
+
+impl fmt::Debug for LikePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LikePattern")
+            .field("pattern", &self.pattern)
+            .field("escape", &self.escape)
+            .finish()
+    }
+}
+
+/// Syntax of a [`RegexPattern`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegexSyntax {
+    /// `x REGEXP 'pattern'`: a regular expression matching any part of the input
+    Regexp,
+    /// `x SIMILAR TO 'pattern'`: an SQL regular expression matching the whole input
+    SimilarTo,
+}
+
+/// A compiled REGEXP or SIMILAR TO pattern together with the source text it was
+/// built from.
+///
+/// Two patterns are equal when their syntax, pattern text and ESCAPE character
+/// are equal.
+///
+/// # Examples
+/// ```
+/// use sqlexpr_rust::RegexPattern;
+///
+/// let regexp = RegexPattern::regexp("^[A-Z]{2}-[0-9]+$").unwrap();
+/// assert!(regexp.matches("DE-1234"));
+///
+/// let similar = RegexPattern::similar_to("(DE|FR)-%", None).unwrap();
+/// assert!(similar.matches("FR-99"));
+/// assert!(!similar.matches("ES-99"));
+/// ```
+#[derive(Clone)]
+pub struct RegexPattern {
+    syntax: RegexSyntax,
+    pattern: String,
+    escape: Option<String>,
+    regex: Regex,
+}
+
+impl RegexPattern {
+    /// Compile a REGEXP pattern
+    pub fn regexp(pattern: &str) -> Result<Self, String> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| format!("Invalid REGEXP pattern '{}': {}", pattern, e))?;
+        Ok(RegexPattern { syntax: RegexSyntax::Regexp, pattern: pattern.to_string(), escape: None, regex })
+    }
+
+    /// Compile a SIMILAR TO pattern.  As with LIKE, only the first character of
+    /// `escape` is used as the escape character.
+    pub fn similar_to(pattern: &str, escape: Option<&str>) -> Result<Self, String> {
+        let escape_char = escape.and_then(|e| e.chars().next());
+        let regex = Self::translate_similar_to(pattern, escape_char)
+            .and_then(|translated| Regex::new(&translated).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Invalid SIMILAR TO pattern '{}': {}", pattern, e))?;
+        Ok(RegexPattern {
+            syntax: RegexSyntax::SimilarTo,
+            pattern: pattern.to_string(),
+            escape: escape.map(str::to_string),
+            regex,
+        })
+    }
+
+    /// Whether this is a REGEXP or SIMILAR TO pattern
+    pub fn syntax(&self) -> RegexSyntax {
+        self.syntax
+    }
+
+    /// The pattern text as written in the expression
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// The ESCAPE string of a SIMILAR TO pattern, if one was given
+    pub fn escape(&self) -> Option<&str> {
+        self.escape.as_deref()
+    }
+
+    /// Check whether `s` matches the pattern
+    pub fn matches(&self, s: &str) -> bool {
+        self.regex.is_match(s)
+    }
+
+    /// Translate a SIMILAR TO pattern to an anchored regular expression
+    fn translate_similar_to(pattern: &str, escape_char: Option<char>) -> Result<String, String> {
+        // (?s) lets wildcards match newlines, as in LIKE
+        let mut regex_pattern = String::from("(?s)^(?:");
+        // Inside a bracket expression, whether a `]` would be its first member
+        let mut bracket_start: Option<bool> = None;
+        let mut chars = pattern.chars();
+        while let Some(ch) = chars.next() {
+            if Some(ch) == escape_char {
+                let next = chars.next().ok_or("pattern ends with the escape character")?;
+                regex_pattern.push_str(&regex::escape(&next.to_string()));
+                bracket_start = bracket_start.map(|_| false);
+                continue;
+            }
+            match (bracket_start, ch) {
+                (None, '%') => regex_pattern.push_str(".*"),
+                (None, '_') => regex_pattern.push('.'),
+                (None, '[') => {
+                    bracket_start = Some(true);
+                    regex_pattern.push('[');
+                    if chars.as_str().starts_with('^') {
+                        chars.next();
+                        regex_pattern.push('^');
+                    }
+                    continue;
+                }
+                (None, '|' | '*' | '+' | '?' | '{' | '}' | '(' | ')') => regex_pattern.push(ch),
+                (None, _) => regex_pattern.push_str(&regex::escape(&ch.to_string())),
+                (Some(false), ']') => {
+                    bracket_start = None;
+                    regex_pattern.push(']');
+                    continue;
+                }
+                // A class such as [:alpha:] keeps its meaning
+                (Some(_), '[') if chars.as_str().starts_with(':') => {
+                    let end = chars.as_str().find(":]").ok_or("unterminated character class")? + 2;
+                    regex_pattern.push('[');
+                    regex_pattern.push_str(&chars.as_str()[..end]);
+                    chars = chars.as_str()[end..].chars();
+                }
+                // Characters the regex crate treats specially in brackets match
+                // themselves, as does a `]` that is the first member
+                (Some(_), '\\' | '[' | ']' | '&' | '~') => {
+                    regex_pattern.push('\\');
+                    regex_pattern.push(ch);
+                }
+                (Some(_), _) => regex_pattern.push(ch),
+            }
+            bracket_start = bracket_start.map(|_| false);
+        }
+        if bracket_start.is_some() {
+            return Err("unterminated bracket expression".to_string());
+        }
+        regex_pattern.push_str(")$");
+        Ok(regex_pattern)
+    }
+}
+
+impl PartialEq for RegexPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.syntax == other.syntax && self.pattern == other.pattern && self.escape == other.escape
+    }
+}
+
+impl fmt::Debug for RegexPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegexPattern")
+            .field("syntax", &self.syntax)
+            .field("pattern", &self.pattern)
+            .field("escape", &self.escape)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher_kind(pattern: &str, escape: Option<&str>) -> &'static str {
+        match LikePattern::new(pattern, escape).unwrap().matcher {
+            Matcher::Exact(_) => "exact",
+            Matcher::Prefix(_) => "prefix",
+            Matcher::Suffix(_) => "suffix",
+            Matcher::Contains(_) => "contains",
+            Matcher::Any => "any",
+            Matcher::Regex(_) => "regex",
+        }
+    }
+
+    #[test]
+    fn test_fast_path_selection() {
+        assert_eq!(matcher_kind("abc", None), "exact");
+        assert_eq!(matcher_kind("", None), "exact");
+        assert_eq!(matcher_kind("abc%", None), "prefix");
+        assert_eq!(matcher_kind("%abc", None), "suffix");
+        assert_eq!(matcher_kind("%%abc%%", None), "contains");
+        assert_eq!(matcher_kind("%", None), "any");
+        assert_eq!(matcher_kind("%%", None), "any");
+        assert_eq!(matcher_kind("50!%%", Some("!")), "prefix");
+        assert_eq!(matcher_kind("a_c", None), "regex");
+        assert_eq!(matcher_kind("a%c", None), "regex");
+    }
+
+    #[test]
+    fn test_matching() {
+        let cases = [
+            ("abc%", "abcdef", true),
+            ("abc%", "xabc", false),
+            ("%abc", "xxabc", true),
+            ("%b%", "abc", true),
+            ("%b%", "ac", false),
+            ("a_c", "abc", true),
+            ("a_c", "abbc", false),
+            ("a%c", "a\nc", true),
+            ("%", "", true),
+            ("h_llo", "héllo", true),
+            ("a.c", "abc", false),
+        ];
+        for (pattern, input, expected) in cases {
+            let like = LikePattern::new(pattern, None).unwrap();
+            assert_eq!(like.matches(input), expected, "'{}' LIKE '{}'", input, pattern);
+        }
+    }
+
+    #[test]
+    fn test_collated_matching() {
+        let cases = [
+            ("JO%", "john", Collation::NoCase, true),
+            ("%SON", "Johnson", Collation::NoCase, true),
+            ("j_hn", "JÖHN", Collation::NoCase, true),
+            ("J!%%", "j%k", Collation::NoCase, true),
+            ("J!%%", "jxk", Collation::NoCase, false),
+            ("caf\u{e9}", "cafe\u{301}", Collation::Unicode, true),
+            ("caf\u{e9}", "CAFE\u{301}", Collation::Unicode, false),
+            ("caf\u{e9}", "cafe\u{301}", Collation::Binary, false),
+        ];
+        for (pattern, input, collation, expected) in cases {
+            let like = LikePattern::new(pattern, Some("!")).unwrap();
+            assert_eq!(like.matches_collated(input, collation), expected, "'{}' LIKE '{}' COLLATE {}", input, pattern, collation);
+        }
+    }
+
+    #[test]
+    fn test_similar_to() {
+        let cases = [
+            ("abc", "abc", true),
+            ("abc", "xabcx", false),
+            ("a%", "abc", true),
+            ("a_c", "abc", true),
+            ("(ab|cd)+", "abcdab", true),
+            ("(ab|cd)+", "abce", false),
+            ("[0-9]{3}-x?", "123-", true),
+            ("[^0-9]%", "1ab", false),
+            ("[]a]b", "]b", true),
+            ("[[:digit:]]+", "42", true),
+            ("a.c", "abc", false),
+            ("a.c", "a.c", true),
+            ("a$^\\", "a$^\\", true),
+            ("[&&x]", "&", true),
+        ];
+        for (pattern, input, expected) in cases {
+            let similar = RegexPattern::similar_to(pattern, None).unwrap();
+            assert_eq!(similar.matches(input), expected, "'{}' SIMILAR TO '{}'", input, pattern);
+        }
+
+        let escaped = RegexPattern::similar_to("100!%|!(x!)", Some("!")).unwrap();
+        assert!(escaped.matches("100%"));
+        assert!(escaped.matches("(x)"));
+        assert!(!escaped.matches("1000"));
+
+        for invalid in ["[abc", "(ab", "a{2", "ab!"] {
+            assert!(RegexPattern::similar_to(invalid, Some("!")).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Tree rendering of parsed expressions for SQL Expression Parser
+//!
+//! Renders an AST with one node per line, giving each node's type name and
+//! contents and indenting children three spaces further than their parent.  The
+//! output can be collected into a `String` or written to any `fmt::Write` or
+//! `io::Write` destination; nothing is printed unless the caller asks for it.
+
+use std::fmt;
+use std::io;
+
+use crate::ast::*;
+
+/// Render the tree of `expr` into a `String`.
+///
+/// # Examples
+/// ```
+/// use sqlexpr_rust::{parse, pretty};
+///
+/// let ast = parse("x > 5 AND NOT deleted").unwrap();
+/// assert_eq!(pretty::tree_string(&ast), "\
+/// And
+///    Relational
+///       Comparison: GreaterThan
+///          Variable: x
+///          Literal: Integer(5)
+///    Not
+///       Variable: deleted
+/// ");
+/// ```
+pub fn tree_string(expr: &BooleanExpr) -> String {
+    let mut out = String::new();
+    write_tree(&mut out, expr).expect("writing to a String cannot fail");
+    out
+}
+
+/// Write the tree of `expr` to a formatter, `String` or other `fmt::Write`.
+pub fn write_tree<W: fmt::Write + ?Sized>(out: &mut W, expr: &BooleanExpr) -> fmt::Result {
+    write_boolean_expr(out, expr, 0)
+}
+
+/// Write the tree of `expr` to a file, stream or other `io::Write`.
+pub fn write_tree_io<W: io::Write + ?Sized>(out: &mut W, expr: &BooleanExpr) -> io::Result<()> {
+    out.write_all(tree_string(expr).as_bytes())
+}
+
+fn write_boolean_expr<W: fmt::Write + ?Sized>(out: &mut W, expr: &BooleanExpr, indent: usize) -> fmt::Result {
+    let prefix = " ".repeat(indent);
+    match expr {
+        // A chain such as `a OR b OR c` is written with a loop, as it nests as
+        // deep as it is long (see `BooleanExpr::chain`)
+        BooleanExpr::Or(..) | BooleanExpr::And(..) => {
+            let (first, links) = expr.chain();
+            for (level, (op, ..)) in links.iter().rev().enumerate() {
+                writeln!(out, "{}{:?}", " ".repeat(indent + 3 * level), op)?;
+            }
+            write_boolean_expr(out, first, indent + 3 * links.len())?;
+            for (level, (_, right, _)) in (1..=links.len()).rev().zip(&links) {
+                write_boolean_expr(out, right, indent + 3 * level)?;
+            }
+            Ok(())
+        }
+        BooleanExpr::Not(inner, _) => {
+            writeln!(out, "{}Not", prefix)?;
+            write_boolean_expr(out, inner, indent + 3)
+        }
+        BooleanExpr::Literal(b, _) => {
+            writeln!(out, "{}BooleanLiteral: {}", prefix, b)
+        }
+        BooleanExpr::Variable(name, _) => {
+            writeln!(out, "{}Variable: {}", prefix, name)
+        }
+        BooleanExpr::Path(..) => {
+            writeln!(out, "{}Path: {}", prefix, expr)
+        }
+        BooleanExpr::Relational(rel) => {
+            writeln!(out, "{}Relational", prefix)?;
+            write_relational_expr(out, rel, indent + 3)
+        }
+    }
+}
+
+fn write_relational_expr<W: fmt::Write + ?Sized>(out: &mut W, expr: &RelationalExpr, indent: usize) -> fmt::Result {
+    let prefix = " ".repeat(indent);
+    match expr {
+        RelationalExpr::Equality { left, op, right, .. } => {
+            writeln!(out, "{}Equality: {:?}", prefix, op)?;
+            write_value_expr(out, left, indent + 3)?;
+            write_value_expr(out, right, indent + 3)
+        }
+        RelationalExpr::Comparison { left, op, right, .. } => {
+            writeln!(out, "{}Comparison: {:?}", prefix, op)?;
+            write_value_expr(out, left, indent + 3)?;
+            write_value_expr(out, right, indent + 3)
+        }
+        RelationalExpr::Like { expr, pattern, negated, case_insensitive, .. } => {
+            let name = if *case_insensitive { "ILike" } else { "Like" };
+            writeln!(out, "{}{}: negated={}, pattern='{}', escape={:?}",
+                prefix, name, negated, pattern.pattern().replace('\'', "''"), pattern.escape())?;
+            write_value_expr(out, expr, indent + 3)
+        }
+        RelationalExpr::Regex { expr, pattern, negated, .. } => {
+            writeln!(out, "{}{:?}: negated={}, pattern='{}', escape={:?}",
+                prefix, pattern.syntax(), negated, pattern.pattern().replace('\'', "''"), pattern.escape())?;
+            write_value_expr(out, expr, indent + 3)
+        }
+        RelationalExpr::Between { expr, lower, upper, negated, .. } => {
+            writeln!(out, "{}Between: negated={}", prefix, negated)?;
+            write_value_expr(out, expr, indent + 3)?;
+            write_value_expr(out, lower, indent + 3)?;
+            write_value_expr(out, upper, indent + 3)
+        }
+        RelationalExpr::In { expr, values, negated, .. } => {
+            writeln!(out, "{}In: negated={}", prefix, negated)?;
+            write_value_expr(out, expr, indent + 3)?;
+            for value in values {
+                write_value_expr(out, value, indent + 3)?;
+            }
+            Ok(())
+        }
+        RelationalExpr::InCollection { expr, collection, negated, .. } => {
+            writeln!(out, "{}InCollection: negated={}", prefix, negated)?;
+            write_value_expr(out, expr, indent + 3)?;
+            write_value_expr(out, collection, indent + 3)
+        }
+        RelationalExpr::Contains { collection, value, negated, .. } => {
+            writeln!(out, "{}Contains: negated={}", prefix, negated)?;
+            write_value_expr(out, collection, indent + 3)?;
+            write_value_expr(out, value, indent + 3)
+        }
+        RelationalExpr::IsNull { expr, negated, .. } => {
+            writeln!(out, "{}IsNull: negated={}", prefix, negated)?;
+            write_value_expr(out, expr, indent + 3)
+        }
+        RelationalExpr::Collated { expr, collation, .. } => {
+            writeln!(out, "{}Collated: {}", prefix, collation)?;
+            write_relational_expr(out, expr, indent + 3)
+        }
+    }
+}
+
+fn write_value_expr<W: fmt::Write + ?Sized>(out: &mut W, expr: &ValueExpr, indent: usize) -> fmt::Result {
+    let prefix = " ".repeat(indent);
+    match expr {
+        ValueExpr::Add(..)
+        | ValueExpr::Subtract(..)
+        | ValueExpr::Multiply(..)
+        | ValueExpr::Divide(..)
+        | ValueExpr::Modulo(..)
+        | ValueExpr::Concat(..) => {
+            let (first, links) = expr.chain();
+            for (level, (op, ..)) in links.iter().rev().enumerate() {
+                writeln!(out, "{}{:?}", " ".repeat(indent + 3 * level), op)?;
+            }
+            write_value_expr(out, first, indent + 3 * links.len())?;
+            for (level, (_, right, _)) in (1..=links.len()).rev().zip(&links) {
+                write_value_expr(out, right, indent + 3 * level)?;
+            }
+            Ok(())
+        }
+        ValueExpr::UnaryPlus(inner, _) => {
+            writeln!(out, "{}UnaryPlus", prefix)?;
+            write_value_expr(out, inner, indent + 3)
+        }
+        ValueExpr::UnaryMinus(inner, _) => {
+            writeln!(out, "{}UnaryMinus", prefix)?;
+            write_value_expr(out, inner, indent + 3)
+        }
+        ValueExpr::Literal(lit, _) => {
+            writeln!(out, "{}Literal: {:?}", prefix, lit)
+        }
+        ValueExpr::Variable(name, _) => {
+            writeln!(out, "{}Variable: {}", prefix, name)
+        }
+        ValueExpr::Path(..) => {
+            writeln!(out, "{}Path: {}", prefix, expr)
+        }
+        ValueExpr::Parameter(param, _) => {
+            writeln!(out, "{}Parameter: {:?}", prefix, param)
+        }
+        ValueExpr::FunctionCall { name, args, .. } => {
+            writeln!(out, "{}FunctionCall: {}", prefix, name)?;
+            for arg in args {
+                write_value_expr(out, arg, indent + 3)?;
+            }
+            Ok(())
+        }
+        ValueExpr::Quantified(quantifier, collection, _) => {
+            writeln!(out, "{}Quantified: {:?}", prefix, quantifier)?;
+            write_value_expr(out, collection, indent + 3)
+        }
+        ValueExpr::Case { operand, branches, else_result, .. } => {
+            writeln!(out, "{}Case", prefix)?;
+            if let Some(operand) = operand {
+                writeln!(out, "{}   Operand", prefix)?;
+                write_value_expr(out, operand, indent + 6)?;
+            }
+            for branch in branches {
+                writeln!(out, "{}   When", prefix)?;
+                match &branch.when {
+                    CaseWhen::Condition(condition) => write_boolean_expr(out, condition, indent + 6)?,
+                    CaseWhen::Value(value) => write_value_expr(out, value, indent + 6)?,
+                }
+                writeln!(out, "{}   Then", prefix)?;
+                write_value_expr(out, &branch.result, indent + 6)?;
+            }
+            if let Some(result) = else_result {
+                writeln!(out, "{}   Else", prefix)?;
+                write_value_expr(out, result, indent + 6)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_tree_string_nested_values() {
+        let ast = parse("UPPER(name) IN ('A', 'B') OR -(a + :b) IS NULL").unwrap();
+        assert_eq!(tree_string(&ast), "\
+Or
+   Relational
+      In: negated=false
+         FunctionCall: UPPER
+            Variable: name
+         Literal: String(\"A\")
+         Literal: String(\"B\")
+   Relational
+      IsNull: negated=false
+         UnaryMinus
+            Add
+               Variable: a
+               Parameter: Named(\"b\")
+");
+    }
+
+    #[test]
+    fn test_write_tree_io() {
+        let ast = parse("flag").unwrap();
+        let mut out = Vec::new();
+        write_tree_io(&mut out, &ast).unwrap();
+        assert_eq!(out, b"Variable: flag\n");
+    }
+}
@@ -0,0 +1,77 @@
+//! Source locations for SQL Expression Parser
+//!
+//! Every token produced by the lexer and every AST node built by the parser
+//! records the range of the input it was read from, so errors can point at the
+//! exact part of an expression that caused them.
+
+use std::fmt;
+
+/// A range of the source text.
+///
+/// `start` and `end` are byte offsets into the input (`end` is exclusive), so
+/// `&input[span.start..span.end]` is the spanned text.  `line` and `column` give
+/// the 1-based position of `start`; columns count characters, not bytes.
+///
+/// # Examples
+/// ```
+/// use sqlexpr_rust::{parse, BooleanExpr};
+///
+/// let input = "a > 1 AND b < 2";
+/// let ast = parse(input).unwrap();
+/// let BooleanExpr::And(_, right, _) = &ast else { unreachable!() };
+/// let span = right.span();
+/// assert_eq!(&input[span.start..span.end], "b < 2");
+/// assert_eq!((span.line, span.column), (1, 11));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// Byte offset of the first character
+    pub start: usize,
+    /// Byte offset just past the last character
+    pub end: usize,
+    /// Line of the first character, starting at 1
+    pub line: usize,
+    /// Column of the first character, starting at 1
+    pub column: usize,
+}
+
+impl Span {
+    /// Create a span from byte offsets and the line and column of its start
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Span { start, end, line, column }
+    }
+
+    /// The smallest span covering both `self` and `other`
+    pub fn to(self, other: Span) -> Span {
+        let (first, last) = if self.start <= other.start { (self, other) } else { (other, self) };
+        Span {
+            start: first.start,
+            end: first.end.max(last.end),
+            line: first.line,
+            column: first.column,
+        }
+    }
+
+    /// Number of bytes covered by the span
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Check whether the span covers no text (e.g. the end of input)
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl Default for Span {
+    /// An empty span at the start of the input
+    fn default() -> Self {
+        Span { start: 0, end: 0, line: 1, column: 1 }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
@@ -0,0 +1,664 @@
+//! Dates, timestamps and intervals for SQL Expression Evaluation
+//!
+//! `Date` is a calendar date and `Timestamp` an instant with the UTC offset it
+//! was written with, both in the proleptic Gregorian calendar for the years 1 to
+//! 9999.  Timestamps compare by instant, so `10:00:00+02:00` equals
+//! `08:00:00+00:00`; the offset is kept for display and for calendar arithmetic.
+//! A date compared with a timestamp stands for midnight UTC at its start.
+//!
+//! `Interval` is a number of months, days and microseconds, kept apart because a
+//! month has no fixed number of days and, across a daylight saving change, a day
+//! no fixed number of hours.  Adding an interval adds the months first, clamping
+//! the day to the end of a shorter month, then the days, then the time.
+//! Intervals are ordered by their length counting a month as 30 days.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+
+const MIN_YEAR: i32 = 1;
+const MAX_YEAR: i32 = 9999;
+
+// ============================================================================
+// DATE
+// ============================================================================
+
+/// A calendar date between 0001-01-01 and 9999-12-31.
+///
+/// # Examples
+/// ```
+/// use sqlexpr_rust::Date;
+///
+/// let date: Date = "2024-01-31".parse().unwrap();
+/// assert_eq!(date.year_month_day(), (2024, 1, 31));
+/// assert_eq!(date.add_months_and_days(1, 0).unwrap().to_string(), "2024-02-29");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    /// Days since 1970-01-01
+    days: i32,
+}
+
+impl Date {
+    /// The date with the given year, month (1-12) and day (1-31), if it exists
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Date> {
+        let valid = (MIN_YEAR..=MAX_YEAR).contains(&year)
+            && (1..=12).contains(&month)
+            && day >= 1
+            && day <= days_in_month(year, month);
+        valid.then(|| Date { days: days_from_civil(year, month, day) })
+    }
+
+    /// Year, month (1-12) and day (1-31)
+    pub fn year_month_day(&self) -> (i32, u32, u32) {
+        civil_from_days(self.days)
+    }
+
+    /// Days since 1970-01-01, negative for earlier dates
+    pub fn days_since_epoch(&self) -> i32 {
+        self.days
+    }
+
+    /// Add calendar months, then days.  A day past the end of the resulting
+    /// month becomes its last day, so 2024-01-31 plus one month is 2024-02-29.
+    /// Returns `None` outside the years 1 to 9999.
+    pub fn add_months_and_days(self, months: i32, days: i32) -> Option<Date> {
+        let (year, month, day) = self.year_month_day();
+        let total = (year as i64) * 12 + (month as i64 - 1) + months as i64;
+        let year = i32::try_from(total.div_euclid(12)).ok()?;
+        let month = total.rem_euclid(12) as u32 + 1;
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+            return None;
+        }
+        let date = Date::from_ymd(year, month, day.min(days_in_month(year, month)))?;
+        Date::from_days(date.days.checked_add(days)?)
+    }
+
+    fn from_days(days: i32) -> Option<Date> {
+        let (year, _, _) = civil_from_days(days);
+        (MIN_YEAR..=MAX_YEAR).contains(&year).then_some(Date { days })
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.year_month_day();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+impl fmt::Debug for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// Parse a date written as `YYYY-MM-DD`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        let date = cursor.date().ok_or_else(|| format!("Invalid date '{}', expected YYYY-MM-DD", s))?;
+        if !cursor.is_done() {
+            return Err(format!("Invalid date '{}', expected YYYY-MM-DD", s));
+        }
+        Ok(date)
+    }
+}
+
+// ============================================================================
+// TIMESTAMP
+// ============================================================================
+
+/// An instant between 0001-01-01 and 9999-12-31 with microsecond precision,
+/// together with the UTC offset it is displayed in.
+///
+/// # Examples
+/// ```
+/// use sqlexpr_rust::Timestamp;
+///
+/// let local: Timestamp = "2024-01-31 10:00:00+02:00".parse().unwrap();
+/// let utc: Timestamp = "2024-01-31T08:00:00Z".parse().unwrap();
+/// assert_eq!(local, utc);
+/// assert_eq!(local.to_string(), "2024-01-31 10:00:00+02:00");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Timestamp {
+    /// Microseconds since 1970-01-01 00:00:00 UTC
+    micros: i64,
+    /// Offset from UTC in seconds
+    offset: i32,
+}
+
+impl Timestamp {
+    /// The instant `micros` microseconds after 1970-01-01 00:00:00 UTC, displayed
+    /// in UTC.  Returns `None` outside the years 1 to 9999.
+    pub fn from_unix_micros(micros: i64) -> Option<Timestamp> {
+        Date::from_days(i32::try_from(micros.div_euclid(MICROS_PER_DAY)).ok()?)?;
+        Some(Timestamp { micros, offset: 0 })
+    }
+
+    /// The current time, displayed in UTC
+    pub fn now() -> Timestamp {
+        let micros = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(since) => since.as_micros() as i64,
+            Err(before) => -(before.duration().as_micros() as i64),
+        };
+        Timestamp { micros, offset: 0 }
+    }
+
+    /// Microseconds since 1970-01-01 00:00:00 UTC
+    pub fn unix_micros(&self) -> i64 {
+        self.micros
+    }
+
+    /// Offset from UTC in seconds that the timestamp is displayed in
+    pub fn offset_seconds(&self) -> i32 {
+        self.offset
+    }
+
+    /// The same instant displayed with another UTC offset, of less than a day
+    pub fn with_offset_seconds(self, offset: i32) -> Option<Timestamp> {
+        ((offset as i64).abs() < MICROS_PER_DAY / MICROS_PER_SECOND)
+            .then_some(Timestamp { micros: self.micros, offset })
+    }
+
+    /// Add an interval: its months and days in the calendar of the timestamp's
+    /// own offset, then its time.  Returns `None` outside the years 1 to 9999.
+    pub fn checked_add(self, interval: Interval) -> Option<Timestamp> {
+        let local = self.local_micros();
+        let date = Date::from_days(local.div_euclid(MICROS_PER_DAY) as i32)?
+            .add_months_and_days(interval.months, interval.days)?;
+        let local = (date.days as i64 * MICROS_PER_DAY + local.rem_euclid(MICROS_PER_DAY))
+            .checked_add(interval.micros)?;
+        let micros = local - self.offset as i64 * MICROS_PER_SECOND;
+        Timestamp::from_unix_micros(micros)?.with_offset_seconds(self.offset)
+    }
+
+    /// Subtract an interval; see [`Timestamp::checked_add`]
+    pub fn checked_sub(self, interval: Interval) -> Option<Timestamp> {
+        self.checked_add(interval.checked_neg()?)
+    }
+
+    /// The time from `earlier` to `self`, as an interval of microseconds
+    pub fn since(self, earlier: Timestamp) -> Interval {
+        // Both instants lie within years 1 to 9999, so the difference fits
+        Interval::new(0, 0, self.micros - earlier.micros)
+    }
+
+    fn local_micros(&self) -> i64 {
+        self.micros + self.offset as i64 * MICROS_PER_SECOND
+    }
+}
+
+impl From<Date> for Timestamp {
+    /// Midnight UTC at the start of the date
+    fn from(date: Date) -> Self {
+        Timestamp { micros: date.days as i64 * MICROS_PER_DAY, offset: 0 }
+    }
+}
+
+impl PartialEq for Timestamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.micros == other.micros
+    }
+}
+
+impl Eq for Timestamp {}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.micros.cmp(&other.micros)
+    }
+}
+
+impl Hash for Timestamp {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.micros.hash(state);
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let local = self.local_micros();
+        let date = Date { days: local.div_euclid(MICROS_PER_DAY) as i32 };
+        let time = local.rem_euclid(MICROS_PER_DAY);
+        write!(f, "{} {:02}:{:02}:{:02}",
+            date,
+            time / MICROS_PER_HOUR,
+            time % MICROS_PER_HOUR / MICROS_PER_MINUTE,
+            time % MICROS_PER_MINUTE / MICROS_PER_SECOND)?;
+        let fraction = time % MICROS_PER_SECOND;
+        if fraction != 0 {
+            write!(f, ".{}", format!("{:06}", fraction).trim_end_matches('0'))?;
+        }
+        let sign = if self.offset < 0 { '-' } else { '+' };
+        let offset = self.offset.unsigned_abs();
+        write!(f, "{}{:02}:{:02}", sign, offset / 3600, offset % 3600 / 60)
+    }
+}
+
+impl fmt::Debug for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = String;
+
+    /// Parse a timestamp written as `YYYY-MM-DD HH:MM[:SS[.ffffff]]` followed by
+    /// an optional UTC offset (`Z`, `+HH`, `+HH:MM` or `-HH:MM`), or as a date
+    /// alone for midnight.  `T` may separate the date and time.  A timestamp
+    /// without an offset is in UTC.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Invalid timestamp '{}', expected YYYY-MM-DD HH:MM:SS with an optional UTC offset", s);
+        let mut cursor = Cursor::new(s);
+        let date = cursor.date().ok_or_else(error)?;
+        let mut time = 0;
+        if cursor.eat(' ') || cursor.eat('T') || cursor.eat('t') {
+            time = cursor.time().ok_or_else(error)?;
+        }
+        cursor.eat(' ');
+        let offset = cursor.offset().ok_or_else(error)?;
+        if !cursor.is_done() {
+            return Err(error());
+        }
+        let local = date.days as i64 * MICROS_PER_DAY + time;
+        Timestamp::from_unix_micros(local - offset as i64 * MICROS_PER_SECOND)
+            .and_then(|t| t.with_offset_seconds(offset))
+            .ok_or_else(error)
+    }
+}
+
+// ============================================================================
+// INTERVAL
+// ============================================================================
+
+/// A span of time in months, days and microseconds.
+///
+/// # Examples
+/// ```
+/// use sqlexpr_rust::Interval;
+///
+/// let interval: Interval = "1 month 2 days 3.5 seconds".parse().unwrap();
+/// assert_eq!((interval.months(), interval.days(), interval.micros()), (1, 2, 3_500_000));
+/// assert_eq!("30 days".parse::<Interval>().unwrap(), "1 month".parse().unwrap());
+/// ```
+#[derive(Clone, Copy)]
+pub struct Interval {
+    months: i32,
+    days: i32,
+    micros: i64,
+}
+
+impl Interval {
+    pub fn new(months: i32, days: i32, micros: i64) -> Interval {
+        Interval { months, days, micros }
+    }
+
+    pub fn months(&self) -> i32 {
+        self.months
+    }
+
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
+    /// The time part, in microseconds
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+
+    pub fn checked_add(self, other: Interval) -> Option<Interval> {
+        Some(Interval {
+            months: self.months.checked_add(other.months)?,
+            days: self.days.checked_add(other.days)?,
+            micros: self.micros.checked_add(other.micros)?,
+        })
+    }
+
+    pub fn checked_sub(self, other: Interval) -> Option<Interval> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_neg(self) -> Option<Interval> {
+        Some(Interval {
+            months: self.months.checked_neg()?,
+            days: self.days.checked_neg()?,
+            micros: self.micros.checked_neg()?,
+        })
+    }
+
+    /// Multiply each part by `factor`
+    pub fn checked_mul(self, factor: i64) -> Option<Interval> {
+        let factor32 = i32::try_from(factor).ok();
+        Some(Interval {
+            months: if self.months == 0 { 0 } else { self.months.checked_mul(factor32?)? },
+            days: if self.days == 0 { 0 } else { self.days.checked_mul(factor32?)? },
+            micros: self.micros.checked_mul(factor)?,
+        })
+    }
+
+    /// Length used for ordering, with 30-day months
+    fn approximate_micros(&self) -> i128 {
+        (self.months as i128 * 30 + self.days as i128) * MICROS_PER_DAY as i128 + self.micros as i128
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.approximate_micros() == other.approximate_micros()
+    }
+}
+
+impl Eq for Interval {}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.approximate_micros().cmp(&other.approximate_micros())
+    }
+}
+
+impl Hash for Interval {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.approximate_micros().hash(state);
+    }
+}
+
+impl fmt::Display for Interval {
+    /// Writes the parts as `1 year 2 months 3 days 4 hours 5 minutes 6.5 seconds`,
+    /// leaving out zero parts, which reads back as the same interval
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        let mut push = |value: i64, unit: &str| {
+            if value != 0 {
+                parts.push(format!("{} {}{}", value, unit, if value.abs() == 1 { "" } else { "s" }));
+            }
+        };
+        push(self.months as i64 / 12, "year");
+        push(self.months as i64 % 12, "month");
+        push(self.days as i64, "day");
+        push(self.micros / MICROS_PER_HOUR, "hour");
+        push(self.micros % MICROS_PER_HOUR / MICROS_PER_MINUTE, "minute");
+
+        let micros = self.micros % MICROS_PER_MINUTE;
+        if micros % MICROS_PER_SECOND == 0 {
+            push(micros / MICROS_PER_SECOND, "second");
+        } else {
+            let sign = if micros < 0 { "-" } else { "" };
+            let fraction = format!("{:06}", micros.abs() % MICROS_PER_SECOND);
+            parts.push(format!("{}{}.{} seconds", sign, micros.abs() / MICROS_PER_SECOND, fraction.trim_end_matches('0')));
+        }
+
+        if parts.is_empty() {
+            write!(f, "0 seconds")
+        } else {
+            write!(f, "{}", parts.join(" "))
+        }
+    }
+}
+
+impl fmt::Debug for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for Interval {
+    type Err = String;
+
+    /// Parse one or more `<number> <unit>` pairs such as `1 year 6 months` or
+    /// `-90 minutes`.  Units are years, months, weeks, days, hours, minutes and
+    /// seconds, singular or plural and in any case; only seconds may have a
+    /// fraction, of up to six digits.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |detail: &str| format!("Invalid interval '{}': {}", s, detail);
+        let words: Vec<&str> = s.split_whitespace().collect();
+        if words.is_empty() || !words.len().is_multiple_of(2) {
+            return Err(error("expected pairs of a number and a unit"));
+        }
+
+        let mut interval = Interval::new(0, 0, 0);
+        for pair in words.chunks(2) {
+            let (number, unit) = (pair[0], pair[1].to_ascii_lowercase());
+            let unit = unit.strip_suffix('s').unwrap_or(&unit);
+            let part = if matches!(unit, "second" | "sec") {
+                let micros = parse_seconds(number).ok_or_else(|| error(&format!("invalid number of seconds '{}'", number)))?;
+                Interval::new(0, 0, micros)
+            } else {
+                let n: i64 = number.parse().map_err(|_| error(&format!("invalid number '{}'", number)))?;
+                let out_of_range = || error(&format!("'{} {}' is out of range", number, pair[1]));
+                let n32 = || i32::try_from(n).map_err(|_| out_of_range());
+                match unit {
+                    "year" => Interval::new(n32()?.checked_mul(12).ok_or_else(out_of_range)?, 0, 0),
+                    "month" | "mon" => Interval::new(n32()?, 0, 0),
+                    "week" => Interval::new(0, n32()?.checked_mul(7).ok_or_else(out_of_range)?, 0),
+                    "day" => Interval::new(0, n32()?, 0),
+                    "hour" => Interval::new(0, 0, n.checked_mul(MICROS_PER_HOUR).ok_or_else(out_of_range)?),
+                    "minute" | "min" => Interval::new(0, 0, n.checked_mul(MICROS_PER_MINUTE).ok_or_else(out_of_range)?),
+                    _ => return Err(error(&format!("unknown unit '{}'", pair[1]))),
+                }
+            };
+            interval = interval.checked_add(part).ok_or_else(|| error("out of range"))?;
+        }
+        Ok(interval)
+    }
+}
+
+/// Parse a possibly fractional, possibly negative number of seconds into microseconds
+fn parse_seconds(number: &str) -> Option<i64> {
+    let (negative, unsigned) = match number.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, number.strip_prefix('+').unwrap_or(number)),
+    };
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if whole.is_empty() || fraction.len() > 6 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let whole: i64 = whole.parse().ok()?;
+    let fraction: i64 = format!("{:0<6}", fraction).parse().ok()?;
+    let micros = whole.checked_mul(MICROS_PER_SECOND)?.checked_add(fraction)?;
+    Some(if negative { -micros } else { micros })
+}
+
+// ============================================================================
+// CALENDAR HELPERS
+// ============================================================================
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date (Howard Hinnant's algorithm)
+fn days_from_civil(year: i32, month: u32, day: u32) -> i32 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_from_march = (month + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year as i32;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Inverse of `days_from_civil`
+fn civil_from_days(days: i32) -> (i32, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Reads the fixed-width fields of date and timestamp text
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(s: &'a str) -> Self {
+        Cursor { rest: s }
+    }
+
+    fn is_done(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Exactly `width` ASCII digits
+    fn digits(&mut self, width: usize) -> Option<u32> {
+        let field = self.rest.get(..width)?;
+        if !field.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        self.rest = &self.rest[width..];
+        field.parse().ok()
+    }
+
+    /// `YYYY-MM-DD`
+    fn date(&mut self) -> Option<Date> {
+        let year = self.digits(4)?;
+        self.eat('-').then_some(())?;
+        let month = self.digits(2)?;
+        self.eat('-').then_some(())?;
+        let day = self.digits(2)?;
+        Date::from_ymd(year as i32, month, day)
+    }
+
+    /// `HH:MM[:SS[.ffffff]]` as microseconds since midnight
+    fn time(&mut self) -> Option<i64> {
+        let hour = self.digits(2).filter(|h| *h < 24)?;
+        self.eat(':').then_some(())?;
+        let minute = self.digits(2).filter(|m| *m < 60)?;
+        let mut micros = 0;
+        if self.eat(':') {
+            let second = self.digits(2).filter(|s| *s < 60)?;
+            micros = second as i64 * MICROS_PER_SECOND;
+            if self.eat('.') {
+                let width = self.rest.bytes().take_while(u8::is_ascii_digit).count();
+                if width == 0 || width > 6 {
+                    return None;
+                }
+                let fraction = self.digits(width)?;
+                micros += fraction as i64 * 10i64.pow(6 - width as u32);
+            }
+        }
+        Some(hour as i64 * MICROS_PER_HOUR + minute as i64 * MICROS_PER_MINUTE + micros)
+    }
+
+    /// Optional `Z`, `+HH`, `+HH:MM` or `+HHMM` (or `-`), in seconds
+    fn offset(&mut self) -> Option<i32> {
+        if self.eat('Z') || self.eat('z') || self.is_done() {
+            return Some(0);
+        }
+        let sign = if self.eat('+') { 1 } else if self.eat('-') { -1 } else { return None };
+        let hours = self.digits(2).filter(|h| *h < 24)?;
+        self.eat(':');
+        let minutes = if self.is_done() { 0 } else { self.digits(2).filter(|m| *m < 60)? };
+        Some(sign * (hours * 3600 + minutes * 60) as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil_round_trip() {
+        for (year, month, day) in [(1970, 1, 1), (2000, 2, 29), (1, 1, 1), (9999, 12, 31), (1969, 12, 31)] {
+            let date = Date::from_ymd(year, month, day).unwrap();
+            assert_eq!(date.year_month_day(), (year, month, day));
+        }
+        assert_eq!(Date::from_ymd(1970, 1, 2).unwrap().days_since_epoch(), 1);
+        assert!(Date::from_ymd(2023, 2, 29).is_none());
+    }
+
+    #[test]
+    fn test_month_arithmetic_clamps_day() {
+        let date: Date = "2024-01-31".parse().unwrap();
+        assert_eq!(date.add_months_and_days(1, 0).unwrap().to_string(), "2024-02-29");
+        assert_eq!(date.add_months_and_days(-2, 1).unwrap().to_string(), "2023-12-01");
+        assert_eq!(date.add_months_and_days(13, 0).unwrap().to_string(), "2025-02-28");
+        assert!(date.add_months_and_days(12 * 8000, 0).is_none());
+    }
+
+    #[test]
+    fn test_timestamp_parse_and_display() {
+        let cases = [
+            ("2024-01-31 10:00:00", "2024-01-31 10:00:00+00:00"),
+            ("2024-01-31T10:00Z", "2024-01-31 10:00:00+00:00"),
+            ("2024-01-31 10:00:00.250 -05:30", "2024-01-31 10:00:00.25-05:30"),
+            ("2024-01-31", "2024-01-31 00:00:00+00:00"),
+            ("2024-01-31 23:59:59+01", "2024-01-31 23:59:59+01:00"),
+        ];
+        for (input, display) in cases {
+            assert_eq!(input.parse::<Timestamp>().unwrap().to_string(), display, "{}", input);
+        }
+        for input in ["2024-01-31 24:00:00", "2024-1-31", "2024-01-31 10:00:00 PST", "2024-01-31 10:00:00.1234567"] {
+            assert!(input.parse::<Timestamp>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_interval_parse_and_display() {
+        let interval: Interval = "1 year 14 months -3 days 90 minutes 1.5 secs".parse().unwrap();
+        assert_eq!(interval.to_string(), "2 years 2 months -3 days 1 hour 30 minutes 1.5 seconds");
+        assert_eq!(interval.to_string().parse::<Interval>().unwrap().months(), 26);
+        assert_eq!("-0.5 seconds".parse::<Interval>().unwrap().to_string(), "-0.5 seconds");
+        assert_eq!("0 days".parse::<Interval>().unwrap().to_string(), "0 seconds");
+        assert!("7".parse::<Interval>().is_err());
+        assert!("7 fortnights".parse::<Interval>().is_err());
+        assert!("1.5 days".parse::<Interval>().is_err());
+    }
+
+    #[test]
+    fn test_timestamp_interval_arithmetic() {
+        let start: Timestamp = "2024-03-31 23:30:00+02:00".parse().unwrap();
+        let later = start.checked_add("1 month 1 hour".parse().unwrap()).unwrap();
+        assert_eq!(later.to_string(), "2024-05-01 00:30:00+02:00");
+        assert_eq!(later.since(start).micros(), (30 * 24 + 1) * MICROS_PER_HOUR);
+    }
+}
@@ -1,6 +1,16 @@
 // Comprehensive tests for SQL Expression Evaluator
-use std::collections::HashMap;
-use sqlexpr_rust::{evaluate, RuntimeValue, EvalError};
+#![allow(clippy::bool_assert_comparison)]
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicUsize, Ordering};
+use sqlexpr_rust::{
+    evaluate, parse, CompiledExpr, RuntimeValue, EvalError, ValueProvider,
+    EvalOptions, NullSemantics, Truth, PartialResult, FunctionRegistry, ValueType,
+    Parameter, Parameters, BooleanExpr, ParserOptions, Span, MAX_EVAL_DEPTH, OverflowPolicy,
+    Collation,
+};
 
 // ============================================================================
 // LITERAL TESTS
@@ -352,6 +362,48 @@ fn test_between_strings() {
     assert_eq!(evaluate("name BETWEEN 'Alice' AND 'Zoe'", &bindings).unwrap(), true);
 }
 
+#[test]
+fn test_between_expression_bounds() {
+    let mut bindings = HashMap::new();
+    bindings.insert("price".to_string(), RuntimeValue::Float(105.0));
+    bindings.insert("min_price".to_string(), RuntimeValue::Integer(50));
+    bindings.insert("max_price".to_string(), RuntimeValue::Integer(100));
+
+    assert_eq!(evaluate("price BETWEEN min_price AND max_price * 1.1", &bindings).unwrap(), true);
+    assert_eq!(evaluate("price BETWEEN min_price AND max_price", &bindings).unwrap(), false);
+    assert_eq!(evaluate("price NOT BETWEEN 0 AND max_price", &bindings).unwrap(), true);
+}
+
+#[test]
+fn test_between_reversed_runtime_bounds_are_empty() {
+    let mut bindings = HashMap::new();
+    bindings.insert("x".to_string(), RuntimeValue::Integer(5));
+    bindings.insert("lo".to_string(), RuntimeValue::Integer(10));
+    bindings.insert("hi".to_string(), RuntimeValue::Integer(1));
+
+    assert_eq!(evaluate("x BETWEEN lo AND hi", &bindings).unwrap(), false);
+    assert_eq!(evaluate("x NOT BETWEEN lo AND hi", &bindings).unwrap(), true);
+    assert_eq!(evaluate("x BETWEEN hi AND lo", &bindings).unwrap(), true);
+}
+
+#[test]
+fn test_between_runtime_bound_type_errors() {
+    let mut bindings = HashMap::new();
+    bindings.insert("x".to_string(), RuntimeValue::Integer(5));
+    bindings.insert("name".to_string(), RuntimeValue::String("m".to_string()));
+    bindings.insert("flag".to_string(), RuntimeValue::Boolean(true));
+
+    let err = evaluate("x BETWEEN name AND 10", &bindings).unwrap_err();
+    assert_eq!(err, EvalError::TypeError {
+        operation: "BETWEEN".to_string(),
+        expected: "integer bounds".to_string(),
+        actual: "string and integer".to_string(),
+        context: "BETWEEN bounds".to_string(),
+    });
+    assert!(matches!(evaluate("name BETWEEN 'a' AND x", &bindings), Err(EvalError::TypeError { .. })));
+    assert!(matches!(evaluate("x BETWEEN 1 AND flag", &bindings), Err(EvalError::TypeError { .. })));
+}
+
 // ============================================================================
 // IN TESTS
 // ============================================================================
@@ -412,6 +464,55 @@ fn test_error_in_operator_with_compatible_numeric_types_2() {
     assert_eq!(evaluate("x IN (6.1, .2, 8.0)", &map).unwrap(), true);
 }
 
+#[test]
+fn test_in_with_expression_members() {
+    let mut map = HashMap::new();
+    map.insert("region".to_string(), RuntimeValue::String("eu".to_string()));
+    map.insert("home_region".to_string(), RuntimeValue::String("eu".to_string()));
+    map.insert("x".to_string(), RuntimeValue::Integer(4));
+    map.insert("a".to_string(), RuntimeValue::Integer(3));
+    map.insert("b".to_string(), RuntimeValue::Float(9.5));
+
+    assert_eq!(evaluate("region IN (home_region, 'global')", &map).unwrap(), true);
+    assert_eq!(evaluate("x IN (a + 1, b)", &map).unwrap(), true);
+    assert_eq!(evaluate("x IN (a, b)", &map).unwrap(), false);
+    assert_eq!(evaluate("x NOT IN (a * 2, b, 7)", &map).unwrap(), true);
+    // Members after the first match are not evaluated
+    assert_eq!(evaluate("x IN (4, missing)", &map).unwrap(), true);
+}
+
+#[test]
+fn test_in_expression_member_type_errors() {
+    let mut map = HashMap::new();
+    map.insert("x".to_string(), RuntimeValue::Integer(4));
+    map.insert("name".to_string(), RuntimeValue::String("bob".to_string()));
+    map.insert("n".to_string(), RuntimeValue::Null);
+
+    let err = evaluate("x IN (1, name)", &map).unwrap_err();
+    assert_eq!(err, EvalError::TypeError {
+        operation: "IN".to_string(),
+        expected: "string".to_string(),
+        actual: "integer".to_string(),
+        context: "left operand type doesn't match list element types".to_string(),
+    });
+    assert!(matches!(evaluate("x IN (n, 4)", &map), Err(EvalError::NullInOperation { .. })));
+
+    assert_eq!(eval_3vl("x IN (n, 4)", &map), Truth::True);
+    assert_eq!(eval_3vl("x IN (n, 5)", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("x NOT IN (n, 5)", &map), Truth::Unknown);
+}
+
+#[test]
+fn test_in_expression_members_partial_eval() {
+    let expr = CompiledExpr::compile("x IN (a + 1, b, 10)").unwrap();
+    let mut known = HashMap::new();
+    known.insert("a".to_string(), RuntimeValue::Integer(3));
+    match expr.partial_eval(&known).unwrap() {
+        PartialResult::Residual(residual) => assert_eq!(residual.to_string(), "x IN (4, b, 10)"),
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+}
+
 // ============================================================================
 // IS NULL TESTS
 // ============================================================================
@@ -1315,3 +1416,2276 @@ fn test_boolean_literal_in_value_context() {
     assert_eq!(evaluate("'FALSE' != 'TRUE'", &map).unwrap(), true);
 }
 
+
+// ============================================================================
+// COMPILED EXPRESSION TESTS
+// ============================================================================
+
+#[test]
+fn test_compiled_expr_reused_across_bindings() {
+    let compiled = CompiledExpr::compile("price * quantity > 100 AND region = 'eu'").unwrap();
+
+    let mut map = HashMap::new();
+    map.insert("price".to_string(), RuntimeValue::Float(12.5));
+    map.insert("quantity".to_string(), RuntimeValue::Integer(10));
+    map.insert("region".to_string(), RuntimeValue::String("eu".to_string()));
+    assert_eq!(compiled.eval(&map).unwrap(), true);
+
+    map.insert("quantity".to_string(), RuntimeValue::Integer(2));
+    assert_eq!(compiled.eval(&map).unwrap(), false);
+
+    map.insert("quantity".to_string(), RuntimeValue::Integer(20));
+    map.insert("region".to_string(), RuntimeValue::String("us".to_string()));
+    assert_eq!(compiled.eval(&map).unwrap(), false);
+}
+
+#[test]
+fn test_compiled_expr_from_parsed_ast() {
+    let ast = parse("x BETWEEN 1 AND 10").unwrap();
+    let compiled = CompiledExpr::from(ast.clone());
+    assert_eq!(compiled.ast(), &ast);
+    assert_eq!(compiled.source(), ast.to_string());
+
+    let mut map = HashMap::new();
+    map.insert("x".to_string(), RuntimeValue::Integer(5));
+    assert_eq!(compiled.eval(&map).unwrap(), true);
+}
+
+#[test]
+fn test_compiled_expr_from_str() {
+    let compiled: CompiledExpr = "name LIKE 'A%'".parse().unwrap();
+    assert_eq!(compiled.source(), "name LIKE 'A%'");
+
+    let bad: Result<CompiledExpr, _> = "x >".parse();
+    assert!(bad.is_err());
+}
+
+#[test]
+fn test_compiled_expr_errors_match_evaluate() {
+    let compiled = CompiledExpr::compile("x / y > 1").unwrap();
+
+    let mut map = HashMap::new();
+    map.insert("x".to_string(), RuntimeValue::Integer(1));
+    assert!(matches!(compiled.eval(&map), Err(EvalError::UnboundVariable { .. })));
+
+    map.insert("y".to_string(), RuntimeValue::Integer(0));
+    assert_eq!(compiled.eval(&map), evaluate("x / y > 1", &map));
+    assert!(matches!(compiled.eval(&map), Err(EvalError::DivisionByZero { .. })));
+}
+
+#[test]
+fn test_compiled_expr_shared_across_threads() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<CompiledExpr>();
+
+    let compiled = std::sync::Arc::new(CompiledExpr::compile("n % 2 = 0").unwrap());
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let compiled = std::sync::Arc::clone(&compiled);
+            std::thread::spawn(move || {
+                let mut map = HashMap::new();
+                map.insert("n".to_string(), RuntimeValue::Integer(i));
+                compiled.eval(&map).unwrap()
+            })
+        })
+        .collect();
+
+    let results: Vec<bool> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    assert_eq!(results, vec![true, false, true, false]);
+}
+
+// ============================================================================
+// VALUE PROVIDER TESTS
+// ============================================================================
+
+struct Order {
+    id: i64,
+    customer: String,
+    express: bool,
+    coupon: Option<String>,
+}
+
+impl ValueProvider for Order {
+    fn resolve(&self, name: &str) -> Option<RuntimeValue> {
+        match name {
+            "id" => Some(RuntimeValue::Integer(self.id)),
+            "customer" => Some(RuntimeValue::String(self.customer.clone())),
+            "express" => Some(RuntimeValue::Boolean(self.express)),
+            "coupon" => Some(self.coupon.clone().map_or(RuntimeValue::Null, RuntimeValue::String)),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_provider_custom_struct() {
+    let order = Order {
+        id: 42,
+        customer: "ACME Corp".to_string(),
+        express: true,
+        coupon: None,
+    };
+
+    let compiled = CompiledExpr::compile("express AND id > 10 AND customer LIKE 'ACME%' AND coupon IS NULL").unwrap();
+    assert_eq!(compiled.eval(&order).unwrap(), true);
+
+    let compiled = CompiledExpr::compile("warehouse = 'north'").unwrap();
+    match compiled.eval(&order) {
+        Err(EvalError::UnboundVariable { name }) => assert_eq!(name, "warehouse"),
+        other => panic!("Expected UnboundVariable, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_provider_btreemap() {
+    let mut map = BTreeMap::new();
+    map.insert("x".to_string(), RuntimeValue::Integer(5));
+    map.insert("flag".to_string(), RuntimeValue::Boolean(false));
+
+    let compiled = CompiledExpr::compile("x BETWEEN 1 AND 10 AND NOT flag").unwrap();
+    assert_eq!(compiled.eval(&map).unwrap(), true);
+}
+
+#[test]
+fn test_provider_closure() {
+    let lookup = |name: &str| match name {
+        "a" => Some(RuntimeValue::Integer(3)),
+        "b" => Some(RuntimeValue::Float(1.5)),
+        _ => None,
+    };
+
+    let compiled = CompiledExpr::compile("a * b = 4.5").unwrap();
+    assert_eq!(compiled.eval(&lookup).unwrap(), true);
+
+    let compiled = CompiledExpr::compile("c > 0").unwrap();
+    assert!(matches!(compiled.eval(&lookup), Err(EvalError::UnboundVariable { .. })));
+}
+
+#[test]
+fn test_provider_resolves_lazily() {
+    let calls = Cell::new(0);
+    let lookup = |name: &str| {
+        calls.set(calls.get() + 1);
+        match name {
+            "x" => Some(RuntimeValue::Integer(1)),
+            "y" => Some(RuntimeValue::Integer(2)),
+            _ => None,
+        }
+    };
+
+    // Short-circuit OR never asks for y
+    let compiled = CompiledExpr::compile("x = 1 OR y = 2").unwrap();
+    assert_eq!(compiled.eval(&lookup).unwrap(), true);
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn test_provider_trait_object() {
+    let mut map = HashMap::new();
+    map.insert("x".to_string(), RuntimeValue::Integer(7));
+    let provider: &dyn ValueProvider = &map;
+
+    let compiled = CompiledExpr::compile("x % 2 = 1").unwrap();
+    assert_eq!(compiled.eval(provider).unwrap(), true);
+}
+
+// ============================================================================
+// THREE-VALUED LOGIC TESTS
+// ============================================================================
+
+fn eval_3vl(input: &str, map: &HashMap<String, RuntimeValue>) -> Truth {
+    let options = EvalOptions::new().null_semantics(NullSemantics::ThreeValued);
+    let result = CompiledExpr::compile(input).unwrap().eval_truth(map, &options);
+    if let Err(e) = &result {
+        eprintln!("Eval error: {}", e);
+    }
+    result.unwrap()
+}
+
+fn null_bindings() -> HashMap<String, RuntimeValue> {
+    let mut map = HashMap::new();
+    map.insert("n".to_string(), RuntimeValue::Null);
+    map.insert("x".to_string(), RuntimeValue::Integer(5));
+    map.insert("s".to_string(), RuntimeValue::String("abc".to_string()));
+    map
+}
+
+#[test]
+fn test_3vl_predicates_on_null_are_unknown() {
+    let map = null_bindings();
+    assert_eq!(eval_3vl("n = 1", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("n <> 1", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("n > 1", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("n LIKE 'a%'", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("n NOT LIKE 'a%'", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("n BETWEEN 1 AND 10", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("n IN (1, 2, 3)", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("n NOT IN (1, 2, 3)", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("n", &map), Truth::Unknown);
+}
+
+#[test]
+fn test_3vl_null_propagates_through_arithmetic() {
+    let map = null_bindings();
+    assert_eq!(eval_3vl("x + n = 5", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("-n < 0", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("x / n > 0", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("(x * n) IS NULL", &map), Truth::True);
+    assert_eq!(eval_3vl("(x % n) IS NOT NULL", &map), Truth::False);
+}
+
+#[test]
+fn test_3vl_kleene_truth_tables() {
+    let map = null_bindings();
+    // AND
+    assert_eq!(eval_3vl("n = 1 AND FALSE", &map), Truth::False);
+    assert_eq!(eval_3vl("FALSE AND n = 1", &map), Truth::False);
+    assert_eq!(eval_3vl("n = 1 AND TRUE", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("n = 1 AND n = 2", &map), Truth::Unknown);
+    // OR
+    assert_eq!(eval_3vl("n = 1 OR TRUE", &map), Truth::True);
+    assert_eq!(eval_3vl("TRUE OR n = 1", &map), Truth::True);
+    assert_eq!(eval_3vl("n = 1 OR FALSE", &map), Truth::Unknown);
+    // NOT
+    assert_eq!(eval_3vl("NOT n = 1", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("NOT (n = 1 OR x = 5)", &map), Truth::False);
+}
+
+#[test]
+fn test_3vl_unknown_is_false_at_top_level() {
+    let map = null_bindings();
+    let options = EvalOptions::new().null_semantics(NullSemantics::ThreeValued);
+
+    let compiled = CompiledExpr::compile("n > 1").unwrap();
+    assert_eq!(compiled.eval_with(&map, &options).unwrap(), false);
+
+    // NOT UNKNOWN is still UNKNOWN, so the negated filter also rejects the row
+    let compiled = CompiledExpr::compile("NOT (n > 1)").unwrap();
+    assert_eq!(compiled.eval_with(&map, &options).unwrap(), false);
+
+    let compiled = CompiledExpr::compile("n > 1 OR x = 5").unwrap();
+    assert_eq!(compiled.eval_with(&map, &options).unwrap(), true);
+}
+
+#[test]
+fn test_3vl_non_null_errors_still_raised() {
+    let map = null_bindings();
+    let options = EvalOptions::new().null_semantics(NullSemantics::ThreeValued);
+
+    let compiled = CompiledExpr::compile("s + 1 > 0").unwrap();
+    assert!(matches!(compiled.eval_with(&map, &options), Err(EvalError::TypeError { .. })));
+
+    let compiled = CompiledExpr::compile("missing = 1").unwrap();
+    assert!(matches!(compiled.eval_with(&map, &options), Err(EvalError::UnboundVariable { .. })));
+}
+
+#[test]
+fn test_strict_null_semantics_is_default() {
+    let map = null_bindings();
+    let compiled = CompiledExpr::compile("n = 1").unwrap();
+    assert!(matches!(compiled.eval(&map), Err(EvalError::NullInOperation { .. })));
+    assert!(matches!(
+        compiled.eval_truth(&map, &EvalOptions::new()),
+        Err(EvalError::NullInOperation { .. })
+    ));
+    assert_eq!(
+        CompiledExpr::compile("x = 5").unwrap().eval_truth(&map, &EvalOptions::new()).unwrap(),
+        Truth::True
+    );
+}
+
+// ============================================================================
+// PARTIAL EVALUATION TESTS
+// ============================================================================
+
+fn residual_of(result: PartialResult) -> String {
+    match result {
+        PartialResult::Residual(expr) => expr.to_string(),
+        PartialResult::Value(b) => panic!("Expected residual expression, got {}", b),
+    }
+}
+
+#[test]
+fn test_partial_eval_folds_known_variables() {
+    let compiled = CompiledExpr::compile("region = home AND amount > base * 2 + 1").unwrap();
+
+    let mut known = HashMap::new();
+    known.insert("home".to_string(), RuntimeValue::String("eu".to_string()));
+    known.insert("base".to_string(), RuntimeValue::Integer(10));
+
+    let residual = residual_of(compiled.partial_eval(&known).unwrap());
+    assert_eq!(residual, "(region = 'eu' AND amount > 21)");
+}
+
+#[test]
+fn test_partial_eval_short_circuits() {
+    let mut known = HashMap::new();
+    known.insert("tenant".to_string(), RuntimeValue::String("acme".to_string()));
+
+    let compiled = CompiledExpr::compile("tenant = 'other' AND size > 10").unwrap();
+    assert_eq!(compiled.partial_eval(&known).unwrap(), PartialResult::Value(false));
+
+    let compiled = CompiledExpr::compile("size > 10 OR tenant = 'acme'").unwrap();
+    assert_eq!(compiled.partial_eval(&known).unwrap(), PartialResult::Value(true));
+
+    // Decided sides drop out of the residual
+    let compiled = CompiledExpr::compile("tenant = 'acme' AND size > 10").unwrap();
+    assert_eq!(residual_of(compiled.partial_eval(&known).unwrap()), "size > 10");
+
+    let compiled = CompiledExpr::compile("size > 10 OR tenant <> 'acme'").unwrap();
+    assert_eq!(residual_of(compiled.partial_eval(&known).unwrap()), "size > 10");
+
+    let compiled = CompiledExpr::compile("NOT (tenant = 'acme' AND NOT flag)").unwrap();
+    assert_eq!(residual_of(compiled.partial_eval(&known).unwrap()), "NOT NOT flag");
+}
+
+#[test]
+fn test_partial_eval_residual_evaluates_later() {
+    let compiled = CompiledExpr::compile(
+        "tier IN ('gold', 'silver') AND (score * weight >= 50 OR name LIKE 'VIP%')",
+    ).unwrap();
+
+    let mut known = HashMap::new();
+    known.insert("tier".to_string(), RuntimeValue::String("gold".to_string()));
+    known.insert("weight".to_string(), RuntimeValue::Float(2.5));
+
+    let residual = match compiled.partial_eval(&known).unwrap() {
+        PartialResult::Residual(expr) => CompiledExpr::from(expr),
+        PartialResult::Value(b) => panic!("Expected residual expression, got {}", b),
+    };
+    assert_eq!(residual.source(), "((score * 2.5) >= 50 OR name LIKE 'VIP%')");
+
+    let mut rest = HashMap::new();
+    rest.insert("score".to_string(), RuntimeValue::Integer(10));
+    rest.insert("name".to_string(), RuntimeValue::String("VIP Bob".to_string()));
+    assert_eq!(residual.eval(&rest).unwrap(), true);
+
+    // The residual text can also be reparsed
+    assert_eq!(CompiledExpr::compile(residual.source()).unwrap().eval(&rest).unwrap(), true);
+}
+
+#[test]
+fn test_partial_eval_all_bound_matches_eval() {
+    let compiled = CompiledExpr::compile("a + b > 10 AND c").unwrap();
+
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), RuntimeValue::Integer(4));
+    map.insert("b".to_string(), RuntimeValue::Integer(7));
+    map.insert("c".to_string(), RuntimeValue::Boolean(true));
+    assert_eq!(compiled.partial_eval(&map).unwrap(), PartialResult::Value(true));
+
+    let empty: HashMap<String, RuntimeValue> = HashMap::new();
+    assert_eq!(
+        compiled.partial_eval(&empty).unwrap(),
+        PartialResult::Residual(compiled.ast().clone())
+    );
+}
+
+#[test]
+fn test_partial_eval_defers_errors_behind_unknowns() {
+    let mut known = HashMap::new();
+    known.insert("x".to_string(), RuntimeValue::Integer(10));
+    known.insert("y".to_string(), RuntimeValue::Integer(0));
+
+    // The division is only reached if flag is true, so the error is deferred
+    let compiled = CompiledExpr::compile("flag AND x / y > 1").unwrap();
+    let residual = residual_of(compiled.partial_eval(&known).unwrap());
+    assert_eq!(residual, "(flag AND (10 / 0) > 1)");
+
+    // Without unbound variables in the way the error is reported immediately
+    let compiled = CompiledExpr::compile("x / y > 1 AND flag").unwrap();
+    assert!(matches!(compiled.partial_eval(&known), Err(EvalError::DivisionByZero { .. })));
+}
+
+#[test]
+fn test_partial_eval_three_valued() {
+    let options = EvalOptions::new().null_semantics(NullSemantics::ThreeValued);
+    let mut known = HashMap::new();
+    known.insert("n".to_string(), RuntimeValue::Null);
+
+    let compiled = CompiledExpr::compile("n = 1 OR z > 3").unwrap();
+    let residual = residual_of(compiled.partial_eval_with(&known, &options).unwrap());
+    assert_eq!(residual, "(NULL = 1 OR z > 3)");
+
+    let compiled = CompiledExpr::compile("n = 1 AND z > 3").unwrap();
+    let residual = residual_of(compiled.partial_eval_with(&known, &options).unwrap());
+    assert_eq!(residual, "(NULL = 1 AND z > 3)");
+
+    let compiled = CompiledExpr::compile("n = 1").unwrap();
+    assert_eq!(compiled.partial_eval_with(&known, &options).unwrap(), PartialResult::Value(false));
+}
+
+#[test]
+fn test_partial_eval_folds_each_node_once() {
+    let options = EvalOptions::new().max_steps(400);
+    let mut known = HashMap::new();
+    known.insert("x".to_string(), RuntimeValue::Integer(0));
+
+    // Folding x + 1 + 1 + ... computes every addition once
+    let compiled = CompiledExpr::compile(&format!("x{} > y", " + 1".repeat(50))).unwrap();
+    assert_eq!(residual_of(compiled.partial_eval_with(&known, &options).unwrap()), "50 > y");
+
+    // With the unbound variable at the bottom, no level is evaluated again from scratch
+    let compiled = CompiledExpr::compile(&format!("y{} > x", " + 1".repeat(50))).unwrap();
+    assert_eq!(
+        compiled.partial_eval_with(&known, &options).unwrap(),
+        PartialResult::Residual(CompiledExpr::compile(&format!("y{} > 0", " + 1".repeat(50))).unwrap().ast().clone())
+    );
+
+    // A predicate is evaluated from its folded operands, so a function in it is
+    // called once whether the predicate folds to a value or to a residual
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut functions = FunctionRegistry::new();
+    let counter = Arc::clone(&calls);
+    functions.register("score", &[ValueType::Integer], ValueType::Integer, move |args| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Ok(args[0].clone())
+    });
+    let options = EvalOptions::new().functions(functions.clone());
+    for (input, expected) in [("score(x) > 0", "false"), ("score(x) > y", "0 > y")] {
+        calls.store(0, Ordering::SeqCst);
+        let compiled = CompiledExpr::compile_with_functions(input, &functions).unwrap();
+        let folded = match compiled.partial_eval_with(&known, &options).unwrap() {
+            PartialResult::Value(b) => b.to_string(),
+            PartialResult::Residual(expr) => expr.to_string(),
+        };
+        assert_eq!(folded, expected, "{}", input);
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "{}", input);
+    }
+}
+
+// ============================================================================
+// BUILT-IN FUNCTION TESTS
+// ============================================================================
+
+#[test]
+fn test_string_functions() {
+    let mut map = HashMap::new();
+    map.insert("name".to_string(), RuntimeValue::String("  Bob Smith ".to_string()));
+
+    assert_eq!(evaluate("UPPER(TRIM(name)) = 'BOB SMITH'", &map).unwrap(), true);
+    assert_eq!(evaluate("LOWER(TRIM(name)) = 'bob smith'", &map).unwrap(), true);
+    assert_eq!(evaluate("LENGTH(name) = 12", &map).unwrap(), true);
+    assert_eq!(evaluate("LTRIM(name) = 'Bob Smith '", &map).unwrap(), true);
+    assert_eq!(evaluate("RTRIM(name) = '  Bob Smith'", &map).unwrap(), true);
+    assert_eq!(evaluate("SUBSTRING(TRIM(name), 5) = 'Smith'", &map).unwrap(), true);
+    assert_eq!(evaluate("SUBSTRING(TRIM(name), 1, 3) = 'Bob'", &map).unwrap(), true);
+    assert_eq!(evaluate("SUBSTRING('abc', 0, 2) = 'a'", &map).unwrap(), true);
+    assert_eq!(evaluate("SUBSTRING('abc', 5) = ''", &map).unwrap(), true);
+    assert_eq!(evaluate("REPLACE(TRIM(name), 'Bob', 'Rob') = 'Rob Smith'", &map).unwrap(), true);
+    assert_eq!(evaluate("CONCAT('a', 'b', 'c') = 'abc'", &map).unwrap(), true);
+    assert_eq!(evaluate("LENGTH('héllo') = 5", &map).unwrap(), true);
+}
+
+#[test]
+fn test_math_functions() {
+    let mut map = HashMap::new();
+    map.insert("delta".to_string(), RuntimeValue::Float(-0.25));
+    map.insert("n".to_string(), RuntimeValue::Integer(-7));
+
+    assert_eq!(evaluate("ABS(delta) < 0.5", &map).unwrap(), true);
+    assert_eq!(evaluate("ABS(n) = 7", &map).unwrap(), true);
+    assert_eq!(evaluate("ROUND(2.5) = 3.0", &map).unwrap(), true);
+    assert_eq!(evaluate("ROUND(3.14159, 2) = 3.14", &map).unwrap(), true);
+    assert_eq!(evaluate("ROUND(1234, -2) = 1200", &map).unwrap(), true);
+    assert_eq!(evaluate("FLOOR(delta) = -1", &map).unwrap(), true);
+    assert_eq!(evaluate("CEIL(delta) = 0", &map).unwrap(), true);
+    assert_eq!(evaluate("CEILING(1.2) = 2", &map).unwrap(), true);
+    assert_eq!(evaluate("SIGN(n) = -1 AND SIGN(0.0) = 0", &map).unwrap(), true);
+    map.insert("nan".to_string(), RuntimeValue::Float(f64::NAN));
+    assert_eq!(evaluate("SIGN(nan) = nan AND SIGN(nan) > 1", &map).unwrap(), true);
+    assert_eq!(evaluate("SQRT(16) = 4", &map).unwrap(), true);
+    assert_eq!(evaluate("POWER(2, 10) = 1024", &map).unwrap(), true);
+    assert_eq!(evaluate("MOD(n, 3) = -1", &map).unwrap(), true);
+}
+
+#[test]
+fn test_round_precision() {
+    let mut map = HashMap::new();
+    // 2^53 + 1 is not representable as a float
+    map.insert("big".to_string(), RuntimeValue::Integer(9007199254740993));
+    map.insert("min".to_string(), RuntimeValue::Integer(i64::MIN));
+
+    assert_eq!(evaluate("ROUND(big, -1) = 9007199254740990", &map).unwrap(), true);
+    assert_eq!(evaluate("ROUND(big, -2) = 9007199254741000", &map).unwrap(), true);
+    assert_eq!(evaluate("ROUND(-15, -1) = -20 AND ROUND(-14, -1) = -10", &map).unwrap(), true);
+    assert_eq!(evaluate("ROUND(min, -18) = -9000000000000000000", &map).unwrap(), true);
+    assert_eq!(evaluate("ROUND(5, -400) = 0 AND ROUND(min, -20) = 0", &map).unwrap(), true);
+    assert_eq!(evaluate("ROUND(1.5, 400) = 1.5", &map).unwrap(), true);
+    assert_eq!(evaluate("ROUND(0.1, 20) = 0.1", &map).unwrap(), true);
+    assert_eq!(evaluate("ROUND(1.5, -400) = 0.0", &map).unwrap(), true);
+    assert_eq!(evaluate("ROUND(-250.0, -2) = -300.0", &map).unwrap(), true);
+    assert!(matches!(evaluate("ROUND(min, -19) = 0", &map), Err(EvalError::Overflow { .. })));
+}
+
+#[test]
+fn test_null_handling_functions() {
+    let mut map = HashMap::new();
+    map.insert("region".to_string(), RuntimeValue::Null);
+    map.insert("fallback".to_string(), RuntimeValue::String("eu".to_string()));
+
+    assert_eq!(evaluate("COALESCE(region, 'us') = 'us'", &map).unwrap(), true);
+    assert_eq!(evaluate("COALESCE(region, fallback, 'us') = 'eu'", &map).unwrap(), true);
+    assert_eq!(evaluate("COALESCE(region, NULL) IS NULL", &map).unwrap(), true);
+    assert_eq!(evaluate("NULLIF(fallback, 'eu') IS NULL", &map).unwrap(), true);
+    assert_eq!(evaluate("NULLIF(fallback, 'us') = 'eu'", &map).unwrap(), true);
+    assert_eq!(evaluate("NULLIF(1, 1.0) IS NULL", &map).unwrap(), true);
+}
+
+#[test]
+fn test_error_function_argument_type() {
+    let mut map = HashMap::new();
+    map.insert("n".to_string(), RuntimeValue::Integer(5));
+
+    match evaluate("UPPER(n) = 'X'", &map).unwrap_err() {
+        EvalError::TypeError { operation, expected, actual, context } => {
+            assert_eq!(operation, "UPPER");
+            assert_eq!(expected, "string");
+            assert_eq!(actual, "integer");
+            assert_eq!(context, "argument 1");
+        }
+        other => panic!("Expected TypeError, got {:?}", other),
+    }
+    assert!(matches!(evaluate("ABS('x') > 1", &map), Err(EvalError::TypeError { .. })));
+    assert!(matches!(evaluate("SUBSTRING('abc', 1.5) = 'a'", &map), Err(EvalError::TypeError { .. })));
+    assert!(matches!(evaluate("SQRT(-1) > 0", &map), Err(EvalError::TypeError { .. })));
+    assert!(matches!(evaluate("MOD(n, 0) = 1", &map), Err(EvalError::DivisionByZero { .. })));
+}
+
+#[test]
+fn test_function_null_argument() {
+    let mut map = HashMap::new();
+    map.insert("name".to_string(), RuntimeValue::Null);
+
+    assert!(matches!(evaluate("UPPER(name) = 'X'", &map), Err(EvalError::NullInOperation { .. })));
+
+    let options = EvalOptions::new().null_semantics(NullSemantics::ThreeValued);
+    let compiled = CompiledExpr::compile("UPPER(name) = 'X'").unwrap();
+    assert_eq!(compiled.eval_truth(&map, &options).unwrap(), Truth::Unknown);
+    let compiled = CompiledExpr::compile("LENGTH(name) IS NULL").unwrap();
+    assert_eq!(compiled.eval_truth(&map, &options).unwrap(), Truth::True);
+}
+
+#[test]
+fn test_partial_eval_folds_function_calls() {
+    let compiled = CompiledExpr::compile("UPPER(name) = UPPER(target) AND LENGTH(code) > 3").unwrap();
+
+    let mut known = HashMap::new();
+    known.insert("target".to_string(), RuntimeValue::String("bob".to_string()));
+
+    match compiled.partial_eval(&known).unwrap() {
+        PartialResult::Residual(expr) => {
+            assert_eq!(expr.to_string(), "(UPPER(name) = 'BOB' AND LENGTH(code) > 3)");
+        }
+        PartialResult::Value(b) => panic!("Expected residual expression, got {}", b),
+    }
+}
+
+#[test]
+fn test_partial_eval_keeps_non_finite_floats() {
+    // NaN and inf would reparse as variables, so nodes evaluating to them stay
+    let mut known = HashMap::new();
+    known.insert("nan".to_string(), RuntimeValue::Float(f64::NAN));
+    known.insert("big".to_string(), RuntimeValue::Float(1e300));
+    let compiled = CompiledExpr::compile("x > big * big AND y <> nan + 1 AND z < big + 1").unwrap();
+    let residual = residual_of(compiled.partial_eval(&known).unwrap());
+    assert_eq!(residual, "((x > (1e300 * 1e300) AND y <> (nan + 1)) AND z < 1e300)");
+
+    let reparsed = CompiledExpr::compile(&residual).unwrap();
+    let mut map = known.clone();
+    map.insert("x".to_string(), RuntimeValue::Float(f64::INFINITY));
+    map.insert("y".to_string(), RuntimeValue::Float(0.0));
+    map.insert("z".to_string(), RuntimeValue::Float(0.0));
+    assert_eq!(reparsed.eval(&map).unwrap(), compiled.eval(&map).unwrap());
+}
+
+#[test]
+fn test_partial_eval_keeps_volatile_calls() {
+    // NOW() is read when the residual is evaluated, not when it is folded
+    let compiled = CompiledExpr::compile("tenant = 'a' AND created_at > NOW() - INTERVAL '7' DAY").unwrap();
+    let mut known = HashMap::new();
+    known.insert("tenant".to_string(), RuntimeValue::String("a".to_string()));
+    assert_eq!(
+        residual_of(compiled.partial_eval(&known).unwrap()),
+        "created_at > (NOW() - INTERVAL '7 days')"
+    );
+
+    let mut functions = FunctionRegistry::new();
+    functions
+        .register("plain", &[ValueType::String], ValueType::Boolean, |_| Ok(RuntimeValue::Boolean(true)))
+        .register_volatile("live", &[ValueType::String], ValueType::Boolean, |_| Ok(RuntimeValue::Boolean(true)));
+    let compiled = CompiledExpr::compile_with_functions("plain('x') = live(name)", &functions).unwrap();
+    let options = EvalOptions::new().functions(functions.clone());
+    assert_eq!(residual_of(compiled.partial_eval_with(&known, &options).unwrap()), "TRUE = live(name)");
+    let compiled = CompiledExpr::compile_with_functions("size > 1 AND plain('x') = live('x')", &functions).unwrap();
+    assert_eq!(
+        residual_of(compiled.partial_eval_with(&known, &options).unwrap()),
+        "(size > 1 AND TRUE = live('x'))"
+    );
+}
+
+// ============================================================================
+// USER-DEFINED FUNCTION TESTS
+// ============================================================================
+
+/// Registry with a few domain functions used by the tests below
+fn domain_functions() -> FunctionRegistry {
+    let mut functions = FunctionRegistry::new();
+    functions
+        .register("distance", &[ValueType::Float, ValueType::Float], ValueType::Float, |args| {
+            match (&args[0], &args[1]) {
+                (RuntimeValue::Float(x), RuntimeValue::Float(y)) => Ok(RuntimeValue::Float((x * x + y * y).sqrt())),
+                _ => Err("expected floats".to_string()),
+            }
+        })
+        .register("flag", &[ValueType::String], ValueType::Boolean, |args| {
+            match &args[0] {
+                RuntimeValue::String(name) if name == "beta" => Ok(RuntimeValue::Boolean(true)),
+                RuntimeValue::String(name) if name == "legacy" => Ok(RuntimeValue::Boolean(false)),
+                RuntimeValue::String(name) => Err(format!("no such flag '{}'", name)),
+                _ => Err("expected string".to_string()),
+            }
+        })
+        .register("broken", &[ValueType::Any], ValueType::Integer, |_| {
+            Ok(RuntimeValue::String("oops".to_string()))
+        });
+    functions
+}
+
+#[test]
+fn test_user_function_evaluation() {
+    let functions = domain_functions();
+    let options = EvalOptions::new().functions(functions.clone());
+
+    let mut map = HashMap::new();
+    map.insert("x".to_string(), RuntimeValue::Integer(3));
+    map.insert("y".to_string(), RuntimeValue::Float(4.0));
+
+    let compiled = CompiledExpr::compile_with_functions("distance(x, y) = 5.0 AND FLAG('beta') = TRUE", &functions).unwrap();
+    assert_eq!(compiled.eval_with(&map, &options).unwrap(), true);
+
+    let compiled = CompiledExpr::compile_with_functions("distance(x, y) < 5 OR flag('legacy') = TRUE", &functions).unwrap();
+    assert_eq!(compiled.eval_with(&map, &options).unwrap(), false);
+}
+
+#[test]
+fn test_user_function_arity_checked_at_compile_time() {
+    let functions = domain_functions();
+
+    let result = CompiledExpr::compile_with_functions("distance(x) > 1", &functions);
+    assert!(result.is_err());
+    if let Err(e) = &result {
+        assert!(e.message.contains("expects 2 argument(s), got 1"), "unexpected message: {}", e);
+    }
+
+    // Without the registry the function is unknown to the parser
+    assert!(CompiledExpr::compile("distance(x, y) > 1").is_err());
+}
+
+#[test]
+fn test_user_function_missing_from_options() {
+    let functions = domain_functions();
+    let compiled = CompiledExpr::compile_with_functions("flag('beta') = TRUE", &functions).unwrap();
+
+    let result = compiled.eval(&HashMap::new());
+    assert!(matches!(result, Err(EvalError::UnknownFunction { ref name }) if name == "flag"));
+}
+
+#[test]
+fn test_user_function_failure() {
+    let functions = domain_functions();
+    let options = EvalOptions::new().functions(functions.clone());
+
+    let compiled = CompiledExpr::compile_with_functions("flag('gamma') = TRUE", &functions).unwrap();
+    match compiled.eval_with(&HashMap::new(), &options) {
+        Err(EvalError::FunctionError { name, message }) => {
+            assert_eq!(name, "flag");
+            assert_eq!(message, "no such flag 'gamma'");
+        }
+        other => panic!("Expected FunctionError, got {:?}", other),
+    }
+
+    // Return values are checked against the declared return type
+    let compiled = CompiledExpr::compile_with_functions("broken(1) = 1", &functions).unwrap();
+    let result = compiled.eval_with(&HashMap::new(), &options);
+    assert!(matches!(result, Err(EvalError::FunctionError { .. })));
+}
+
+#[test]
+fn test_user_function_argument_types() {
+    let functions = domain_functions();
+    let options = EvalOptions::new().functions(functions.clone());
+
+    let compiled = CompiledExpr::compile_with_functions("flag(42) = TRUE", &functions).unwrap();
+    let result = compiled.eval_with(&HashMap::new(), &options);
+    assert!(matches!(result, Err(EvalError::TypeError { ref operation, .. }) if operation == "flag"));
+}
+
+#[test]
+fn test_user_function_null_argument() {
+    let functions = domain_functions();
+    let compiled = CompiledExpr::compile_with_functions("flag(name) = TRUE", &functions).unwrap();
+
+    let mut map = HashMap::new();
+    map.insert("name".to_string(), RuntimeValue::Null);
+
+    let options = EvalOptions::new().functions(functions.clone());
+    assert!(matches!(compiled.eval_with(&map, &options), Err(EvalError::NullInOperation { .. })));
+
+    let options = options.null_semantics(NullSemantics::ThreeValued);
+    assert_eq!(compiled.eval_truth(&map, &options).unwrap(), Truth::Unknown);
+}
+
+#[test]
+fn test_user_function_overrides_builtin() {
+    let mut functions = FunctionRegistry::new();
+    functions.register("upper", &[ValueType::String], ValueType::String, |_| {
+        Ok(RuntimeValue::String("overridden".to_string()))
+    });
+    let options = EvalOptions::new().functions(functions.clone());
+
+    let compiled = CompiledExpr::compile_with_functions("UPPER('a') = 'overridden'", &functions).unwrap();
+    assert_eq!(compiled.eval_with(&HashMap::new(), &options).unwrap(), true);
+    assert_eq!(compiled.eval(&HashMap::new()).unwrap(), false);
+}
+
+// ============================================================================
+// COMPILED LIKE PATTERN TESTS
+// ============================================================================
+
+#[test]
+fn test_like_fast_path_patterns() {
+    let mut map = HashMap::new();
+    map.insert("topic".to_string(), RuntimeValue::String("orders.eu.created".to_string()));
+
+    assert_eq!(evaluate("topic LIKE 'orders.eu.created'", &map).unwrap(), true);
+    assert_eq!(evaluate("topic LIKE 'orders.%'", &map).unwrap(), true);
+    assert_eq!(evaluate("topic LIKE '%.created'", &map).unwrap(), true);
+    assert_eq!(evaluate("topic LIKE '%.eu.%'", &map).unwrap(), true);
+    assert_eq!(evaluate("topic LIKE '%%'", &map).unwrap(), true);
+    assert_eq!(evaluate("topic LIKE 'orders'", &map).unwrap(), false);
+    assert_eq!(evaluate("topic LIKE '%.us.%'", &map).unwrap(), false);
+    assert_eq!(evaluate("topic NOT LIKE 'payments.%'", &map).unwrap(), true);
+}
+
+#[test]
+fn test_like_escaped_wildcards_in_fast_path() {
+    let mut map = HashMap::new();
+    map.insert("label".to_string(), RuntimeValue::String("100%_off".to_string()));
+
+    assert_eq!(evaluate("label LIKE '%!%!_off' ESCAPE '!'", &map).unwrap(), true);
+    assert_eq!(evaluate("label LIKE '100!%%' ESCAPE '!'", &map).unwrap(), true);
+    assert_eq!(evaluate("label LIKE '10!%%' ESCAPE '!'", &map).unwrap(), false);
+}
+
+#[test]
+fn test_like_wildcards_match_newlines() {
+    let mut map = HashMap::new();
+    map.insert("body".to_string(), RuntimeValue::String("line one\nline two".to_string()));
+
+    assert_eq!(evaluate("body LIKE 'line%two'", &map).unwrap(), true);
+    assert_eq!(evaluate("body LIKE '%one_line%'", &map).unwrap(), true);
+}
+
+#[test]
+fn test_compiled_like_reused_across_evaluations() {
+    let compiled = CompiledExpr::compile("sku LIKE 'AB_-%'").unwrap();
+
+    for (sku, expected) in [("ABC-1", true), ("ABX-22", true), ("AB-1", false), ("XBC-1", false)] {
+        let mut map = HashMap::new();
+        map.insert("sku".to_string(), RuntimeValue::String(sku.to_string()));
+        assert_eq!(compiled.eval(&map).unwrap(), expected, "sku {}", sku);
+    }
+}
+
+// ============================================================================
+// ERROR SPAN TESTS
+// ============================================================================
+
+#[test]
+fn test_eval_spanned_unbound_variable() {
+    let input = "a = 1 OR\n  missing > 2";
+    let compiled = CompiledExpr::compile(input).unwrap();
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), RuntimeValue::Integer(0));
+
+    let err = compiled.eval_spanned(&map, &EvalOptions::default()).unwrap_err();
+    assert!(matches!(err.error, EvalError::UnboundVariable { ref name } if name == "missing"));
+    assert_eq!(&input[err.span.start..err.span.end], "missing");
+    assert_eq!((err.span.line, err.span.column), (2, 3));
+    assert!(err.to_string().ends_with("at line 2, column 3"));
+}
+
+#[test]
+fn test_eval_spanned_points_at_failing_subexpression() {
+    let input = "x > 0 AND (x + 1) / (y - y) > 2";
+    let compiled = CompiledExpr::compile(input).unwrap();
+    let mut map = HashMap::new();
+    map.insert("x".to_string(), RuntimeValue::Integer(4));
+    map.insert("y".to_string(), RuntimeValue::Integer(3));
+
+    let err = compiled.eval_spanned(&map, &EvalOptions::default()).unwrap_err();
+    assert!(matches!(err.error, EvalError::DivisionByZero { .. }));
+    assert_eq!(&input[err.span.start..err.span.end], "(x + 1) / (y - y)");
+}
+
+#[test]
+fn test_eval_spanned_type_error() {
+    let input = "UPPER(n) = 'A'";
+    let compiled = CompiledExpr::compile(input).unwrap();
+    let mut map = HashMap::new();
+    map.insert("n".to_string(), RuntimeValue::Integer(1));
+
+    let err = compiled.eval_spanned(&map, &EvalOptions::default()).unwrap_err();
+    assert!(matches!(err.error, EvalError::TypeError { .. }));
+    assert_eq!(&input[err.span.start..err.span.end], "UPPER(n)");
+}
+
+#[test]
+fn test_eval_spanned_success() {
+    let compiled = CompiledExpr::compile("x BETWEEN 1 AND 5").unwrap();
+    let mut map = HashMap::new();
+    map.insert("x".to_string(), RuntimeValue::Integer(3));
+
+    assert_eq!(compiled.eval_spanned(&map, &EvalOptions::default()).unwrap(), Truth::True);
+}
+
+// ============================================================================
+// PARAMETER BINDING TESTS
+// ============================================================================
+
+#[test]
+fn test_bind_positional_parameters() {
+    let template = CompiledExpr::compile("price >= ? AND price < ? * 2").unwrap();
+    let filter = template
+        .bind(&Parameters::new().push(RuntimeValue::Integer(10)).push(RuntimeValue::Integer(15)))
+        .unwrap();
+    assert_eq!(filter.ast().to_string(), "(price >= 10 AND price < (15 * 2))");
+
+    let mut map = HashMap::new();
+    map.insert("price".to_string(), RuntimeValue::Integer(29));
+    assert_eq!(filter.eval(&map).unwrap(), true);
+    map.insert("price".to_string(), RuntimeValue::Integer(30));
+    assert_eq!(filter.eval(&map).unwrap(), false);
+}
+
+#[test]
+fn test_bind_named_parameters_without_quoting() {
+    let template = CompiledExpr::compile("owner = :owner OR UPPER(owner) = UPPER(:owner)").unwrap();
+    let hostile = "x' OR 1 = 1 --";
+    let filter = template
+        .bind(&Parameters::new().set("owner", RuntimeValue::String(hostile.to_string())))
+        .unwrap();
+
+    let mut map = HashMap::new();
+    map.insert("owner".to_string(), RuntimeValue::String("bob".to_string()));
+    assert_eq!(filter.eval(&map).unwrap(), false);
+    map.insert("owner".to_string(), RuntimeValue::String(hostile.to_string()));
+    assert_eq!(filter.eval(&map).unwrap(), true);
+}
+
+#[test]
+fn test_parameters_separate_from_variables() {
+    // A parameter and a variable with the same name do not interfere
+    let template = CompiledExpr::compile("limit > :limit").unwrap();
+    let filter = template.bind(&Parameters::new().set("limit", RuntimeValue::Integer(5))).unwrap();
+
+    let mut map = HashMap::new();
+    map.insert("limit".to_string(), RuntimeValue::Integer(7));
+    assert_eq!(filter.eval(&map).unwrap(), true);
+}
+
+#[test]
+fn test_missing_parameter_errors() {
+    let template = CompiledExpr::compile("a = ? AND b = :b").unwrap();
+
+    let result = template.bind(&Parameters::new().set("b", RuntimeValue::Integer(1)));
+    assert_eq!(result.unwrap_err(), EvalError::UnboundParameter { parameter: Parameter::Positional(1) });
+
+    let result = template.bind(&Parameters::new().push(RuntimeValue::Integer(1)));
+    assert_eq!(result.unwrap_err(), EvalError::UnboundParameter { parameter: Parameter::Named("b".to_string()) });
+
+    // Evaluating without binding reports the placeholder
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), RuntimeValue::Integer(1));
+    let err = template.eval(&map).unwrap_err();
+    assert!(matches!(err, EvalError::UnboundParameter { .. }));
+    assert!(err.to_string().contains("Unbound parameter 1"));
+}
+
+#[test]
+fn test_bind_null_parameter() {
+    let template = CompiledExpr::compile("COALESCE(?, 0) = 0").unwrap();
+    let filter = template.bind(&Parameters::new().push(RuntimeValue::Null)).unwrap();
+    assert_eq!(filter.eval(&HashMap::new()).unwrap(), true);
+}
+
+#[test]
+fn test_partial_eval_keeps_unbound_parameters() {
+    let template = CompiledExpr::compile("region = 'eu' AND size > ?").unwrap();
+    let mut known = HashMap::new();
+    known.insert("region".to_string(), RuntimeValue::String("eu".to_string()));
+
+    match template.partial_eval(&known).unwrap() {
+        PartialResult::Residual(residual) => assert_eq!(residual.to_string(), "size > ?"),
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+}
+
+// ============================================================================
+// NESTING LIMIT TESTS
+// ============================================================================
+
+#[test]
+fn test_eval_deepest_accepted_expressions() {
+    let n = ParserOptions::DEFAULT_MAX_DEPTH;
+    let inputs = [
+        format!("{}flag", "NOT ".repeat(n - 1)),
+        format!("{}x{} > 0", "x + (".repeat(n - 2), ")".repeat(n - 2)),
+        format!("{}a{} = 'A'", "UPPER(".repeat(n - 2), ")".repeat(n - 2)),
+        format!("{}x > 0{}", "(".repeat(n), ")".repeat(n)),
+        format!("{}flag{}", "flag AND (".repeat(n - 1), ")".repeat(n - 1)),
+    ];
+    let mut map = HashMap::new();
+    map.insert("flag".to_string(), RuntimeValue::Boolean(true));
+    map.insert("a".to_string(), RuntimeValue::String("a".to_string()));
+    map.insert("x".to_string(), RuntimeValue::Integer(1));
+
+    for input in &inputs {
+        let expr = CompiledExpr::compile(input).unwrap();
+        assert!(expr.eval(&map).is_ok(), "{}", input);
+        assert!(expr.partial_eval(&HashMap::new()).is_ok(), "{}", input);
+        assert!(expr.bind(&Parameters::new()).is_ok(), "{}", input);
+    }
+}
+
+#[test]
+fn test_eval_rejects_too_deep_ast() {
+    let span = Span::default();
+    let mut ast = BooleanExpr::Variable("flag".to_string(), span);
+    for _ in 0..MAX_EVAL_DEPTH {
+        ast = BooleanExpr::Not(Box::new(ast), span);
+    }
+    let expr = CompiledExpr::from(ast);
+    let mut map = HashMap::new();
+    map.insert("flag".to_string(), RuntimeValue::Boolean(true));
+
+    let expected = EvalError::NestingTooDeep { depth: MAX_EVAL_DEPTH + 1, limit: MAX_EVAL_DEPTH };
+    assert_eq!(expr.eval(&map).unwrap_err(), expected);
+    assert_eq!(expr.partial_eval(&map).unwrap_err(), expected);
+    assert_eq!(expr.bind(&Parameters::new()).unwrap_err(), expected);
+    assert_eq!(expr.eval_spanned(&map, &EvalOptions::default()).unwrap_err().error, expected);
+}
+
+#[test]
+fn test_eval_rejects_parse_beyond_eval_limit() {
+    let options = ParserOptions::new().max_depth(MAX_EVAL_DEPTH + 10);
+    let input = format!("{}a > 0", "- ".repeat(MAX_EVAL_DEPTH));
+    let expr = CompiledExpr::compile_with_options(&input, &options).unwrap();
+    assert_eq!(expr.ast().depth(), MAX_EVAL_DEPTH + 2);
+
+    let err = expr.eval(&HashMap::new()).unwrap_err();
+    assert!(matches!(err, EvalError::NestingTooDeep { .. }));
+    assert!(err.to_string().contains("more than the 100 that can be evaluated"));
+}
+
+/// Run `f` on a thread with the 2 MiB stack Rust gives spawned threads by default
+fn on_default_stack<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    std::thread::Builder::new().stack_size(2 << 20).spawn(f).unwrap().join().unwrap()
+}
+
+#[test]
+fn test_eval_deepest_evaluable_expressions_fit_in_thread_stack() {
+    let n = MAX_EVAL_DEPTH;
+    let nested = |levels: usize, shape: fn(&str) -> String| {
+        format!("{} > 0", (0..levels).fold("x".to_string(), |inner, _| shape(&inner)))
+    };
+    let inputs = [
+        format!("{}x{} > 0", "ABS(".repeat(n - 2), ")".repeat(n - 2)),
+        format!("{}flag", "NOT ".repeat(n - 1)),
+        format!("{}x{} > 0", "1 + (".repeat(n - 2), ")".repeat(n - 2)),
+        format!("{}a{} = 'A'", "'b' || (".repeat(n - 2), ")".repeat(n - 2)),
+        format!("{}x > 0", "- ".repeat(n - 2)),
+        format!("{}flag{}", "flag AND (".repeat(n - 1), ")".repeat(n - 1)),
+        nested(n / 2 - 1, |inner| format!("CASE WHEN {} > 0 THEN x END", inner)),
+        nested(n - 2, |inner| format!("CASE WHEN flag THEN {} END", inner)),
+        nested(n - 2, |inner| format!("CASE {} WHEN 1 THEN x END", inner)),
+    ];
+    for input in inputs {
+        // Parsing this deep takes more stack than evaluating, and CASE conditions
+        // count as nested deeper than they are
+        let options = ParserOptions::new().max_depth(3 * n);
+        let expr = std::thread::Builder::new().stack_size(16 << 20)
+            .spawn(move || CompiledExpr::compile_with_options(&input, &options).unwrap())
+            .unwrap().join().unwrap();
+        assert_eq!(expr.ast().depth(), n, "{}", expr.source());
+
+        on_default_stack(move || {
+            let mut map = HashMap::new();
+            map.insert("flag".to_string(), RuntimeValue::Boolean(true));
+            map.insert("a".to_string(), RuntimeValue::String("a".to_string()));
+            map.insert("x".to_string(), RuntimeValue::Integer(1));
+            assert!(expr.eval(&map).is_ok(), "{}", expr.source());
+            let options = EvalOptions::new().null_semantics(NullSemantics::ThreeValued);
+            assert!(expr.eval_spanned(&map, &options).is_ok(), "{}", expr.source());
+            assert!(expr.partial_eval(&map).is_ok(), "{}", expr.source());
+            assert!(expr.partial_eval(&HashMap::new()).is_ok(), "{}", expr.source());
+            assert!(expr.bind(&Parameters::new()).is_ok(), "{}", expr.source());
+            let ast = expr.ast();
+            assert_eq!(ast.clone(), *ast);
+            assert!(!ast.to_string().is_empty());
+            assert!(!format!("{:?}", ast).is_empty());
+            assert!(!sqlexpr_rust::pretty::tree_string(ast).is_empty());
+        });
+    }
+}
+
+#[test]
+fn test_eval_longest_accepted_chains_fit_in_thread_stack() {
+    // Each link takes an operator and an operand, after the first operand and
+    // before the comparison
+    let links = (ParserOptions::DEFAULT_MAX_TOKENS - 3) / 2;
+    let inputs = [
+        format!("flag{}", " AND flag".repeat(links)),
+        format!("NOT flag{}", " OR NOT flag".repeat(links / 2)),
+        format!("x{} > 0", " + 1".repeat(links)),
+        format!("x{} > 0", " * 1".repeat(links)),
+        format!("a{} = 'A'", " || 'b'".repeat(links)),
+    ];
+    for input in inputs {
+        let expr = CompiledExpr::compile(&input).unwrap();
+        assert!(expr.ast().depth() <= 3, "{}", expr.source());
+
+        on_default_stack(move || {
+            let mut map = HashMap::new();
+            map.insert("flag".to_string(), RuntimeValue::Boolean(true));
+            map.insert("a".to_string(), RuntimeValue::String("a".to_string()));
+            map.insert("x".to_string(), RuntimeValue::Integer(1));
+            assert!(expr.eval(&map).is_ok(), "{}", expr.source());
+            let options = EvalOptions::new().null_semantics(NullSemantics::ThreeValued);
+            assert!(expr.eval_spanned(&map, &options).is_ok(), "{}", expr.source());
+            assert!(expr.partial_eval(&map).is_ok(), "{}", expr.source());
+            assert!(expr.partial_eval(&HashMap::new()).is_ok(), "{}", expr.source());
+            assert!(expr.bind(&Parameters::new()).is_ok(), "{}", expr.source());
+            let ast = expr.ast();
+            assert_eq!(ast.clone(), *ast);
+            assert!(!ast.to_string().is_empty());
+            assert!(!format!("{:?}", ast).is_empty());
+            assert!(!sqlexpr_rust::pretty::tree_string(ast).is_empty());
+        });
+    }
+}
+
+#[test]
+fn test_eval_many_ored_terms() {
+    let input = (0..300).map(|i| format!("x = {}", i)).collect::<Vec<_>>().join(" OR ");
+    let expr = CompiledExpr::compile(&input).unwrap();
+
+    let mut map = HashMap::new();
+    map.insert("x".to_string(), RuntimeValue::Integer(299));
+    assert_eq!(expr.eval(&map).unwrap(), true);
+    map.insert("x".to_string(), RuntimeValue::Integer(300));
+    assert_eq!(expr.eval(&map).unwrap(), false);
+    assert!(matches!(expr.partial_eval(&HashMap::new()).unwrap(), PartialResult::Residual(_)));
+    assert_eq!(expr.partial_eval(&map).unwrap(), PartialResult::Value(false));
+}
+
+// ============================================================================
+// EVALUATION BUDGET TESTS
+// ============================================================================
+
+#[test]
+fn test_budget_counts_nodes() {
+    // AND, then a boolean, relational and two value nodes for each comparison
+    let expr = CompiledExpr::compile("a > 1 AND b < 2").unwrap();
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), RuntimeValue::Integer(5));
+    map.insert("b".to_string(), RuntimeValue::Integer(0));
+
+    assert_eq!(expr.eval_with(&map, &EvalOptions::new().max_steps(9)).unwrap(), true);
+    let err = expr.eval_with(&map, &EvalOptions::new().max_steps(8)).unwrap_err();
+    assert_eq!(err, EvalError::BudgetExceeded { limit: 8 });
+    assert_eq!(err.to_string(), "Evaluation exceeded its budget of 8 steps");
+}
+
+#[test]
+fn test_budget_short_circuit_takes_no_steps() {
+    let expr = CompiledExpr::compile("a > 1 OR b + c * d < 2").unwrap();
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), RuntimeValue::Integer(5));
+    assert_eq!(expr.eval_with(&map, &EvalOptions::new().max_steps(5)).unwrap(), true);
+}
+
+#[test]
+fn test_budget_counts_like_matches() {
+    let expr = CompiledExpr::compile("name LIKE '%x%'").unwrap();
+    let mut map = HashMap::new();
+    map.insert("name".to_string(), RuntimeValue::String("xyz".to_string()));
+
+    // Boolean, relational and value nodes, then the match itself
+    assert_eq!(expr.eval_with(&map, &EvalOptions::new().max_steps(4)).unwrap(), true);
+    assert!(matches!(
+        expr.eval_with(&map, &EvalOptions::new().max_steps(3)),
+        Err(EvalError::BudgetExceeded { limit: 3 })
+    ));
+}
+
+#[test]
+fn test_budget_error_span() {
+    let input = "a > 1 AND b < 2";
+    let expr = CompiledExpr::compile(input).unwrap();
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), RuntimeValue::Integer(5));
+    map.insert("b".to_string(), RuntimeValue::Integer(0));
+
+    let err = expr.eval_spanned(&map, &EvalOptions::new().max_steps(6)).unwrap_err();
+    assert_eq!(err.error, EvalError::BudgetExceeded { limit: 6 });
+    assert_eq!(&input[err.span.start..err.span.end], "b < 2");
+}
+
+#[test]
+fn test_budget_partial_eval() {
+    let expr = CompiledExpr::compile("region = 'eu' AND size > 10").unwrap();
+    let mut known = HashMap::new();
+    known.insert("region".to_string(), RuntimeValue::String("eu".to_string()));
+
+    assert!(matches!(
+        expr.partial_eval_with(&known, &EvalOptions::new().max_steps(100)).unwrap(),
+        PartialResult::Residual(_)
+    ));
+    // Running out of budget is reported rather than left in the residual
+    for steps in 0..4 {
+        assert_eq!(
+            expr.partial_eval_with(&known, &EvalOptions::new().max_steps(steps)).unwrap_err(),
+            EvalError::BudgetExceeded { limit: steps }
+        );
+    }
+}
+
+#[test]
+fn test_budget_partial_eval_while_folding() {
+    // The left side of the comparison is folded, then the comparison is kept
+    // without being evaluated since y is unbound
+    let expr = CompiledExpr::compile(&format!("x{} > y", " + 1".repeat(20))).unwrap();
+    let mut known = HashMap::new();
+    known.insert("x".to_string(), RuntimeValue::Integer(0));
+
+    assert_eq!(residual_of(expr.partial_eval_with(&known, &EvalOptions::new().max_steps(200)).unwrap()), "20 > y");
+    assert!(expr.partial_eval_with(&known, &EvalOptions::new().max_steps(69)).is_ok());
+    for steps in [50, 68] {
+        assert_eq!(
+            expr.partial_eval_with(&known, &EvalOptions::new().max_steps(steps)).unwrap_err(),
+            EvalError::BudgetExceeded { limit: steps }
+        );
+    }
+}
+
+#[test]
+fn test_cancel_flag() {
+    let expr = CompiledExpr::compile("a > 1").unwrap();
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), RuntimeValue::Integer(5));
+
+    let flag = Arc::new(AtomicBool::new(false));
+    let options = EvalOptions::new().cancel_flag(flag.clone());
+    assert_eq!(expr.eval_with(&map, &options).unwrap(), true);
+
+    flag.store(true, Ordering::Relaxed);
+    let err = expr.eval_with(&map, &options).unwrap_err();
+    assert_eq!(err, EvalError::Cancelled);
+    assert_eq!(err.to_string(), "Evaluation cancelled");
+}
+
+#[test]
+fn test_cancel_flag_checked_periodically() {
+    let flag = Arc::new(AtomicBool::new(false));
+    let cancel = flag.clone();
+    let mut functions = FunctionRegistry::new();
+    functions.register("cancel", &[], ValueType::Integer, move |_| {
+        cancel.store(true, Ordering::Relaxed);
+        Ok(RuntimeValue::Integer(0))
+    });
+    let options = EvalOptions::new().functions(functions.clone()).cancel_flag(flag);
+
+    // Cancelling part-way through stops a long evaluation soon after
+    let input = format!("cancel() = 0{}", " AND 1 = 1".repeat(60));
+    let expr = CompiledExpr::compile_with_functions(&input, &functions).unwrap();
+    assert_eq!(expr.eval_with(&HashMap::new(), &options).unwrap_err(), EvalError::Cancelled);
+}
+
+#[test]
+fn test_partial_eval_cancelled() {
+    let flag = Arc::new(AtomicBool::new(false));
+    let cancel = flag.clone();
+    let mut functions = FunctionRegistry::new();
+    functions.register("cancel", &[], ValueType::Integer, move |_| {
+        cancel.store(true, Ordering::Relaxed);
+        Ok(RuntimeValue::Integer(0))
+    });
+    let options = EvalOptions::new().functions(functions.clone()).cancel_flag(flag.clone());
+
+    // Cancelled while folding the left side, after the comparison failed at y
+    let input = format!("cancel(){} > y", " + 1".repeat(20));
+    let expr = CompiledExpr::compile_with_functions(&input, &functions).unwrap();
+    assert_eq!(expr.partial_eval_with(&HashMap::new(), &options).unwrap_err(), EvalError::Cancelled);
+
+    // Cancelled while folding a CASE condition
+    flag.store(false, Ordering::Relaxed);
+    let input = format!("CASE WHEN cancel(){} > 0 THEN y END > 1", " + 1".repeat(40));
+    let expr = CompiledExpr::compile_with_functions(&input, &functions).unwrap();
+    assert_eq!(expr.partial_eval_with(&HashMap::new(), &options).unwrap_err(), EvalError::Cancelled);
+}
+
+// ============================================================================
+// INTEGER OVERFLOW TESTS
+// ============================================================================
+
+fn overflow_map() -> HashMap<String, RuntimeValue> {
+    let mut map = HashMap::new();
+    map.insert("max".to_string(), RuntimeValue::Integer(i64::MAX));
+    map.insert("min".to_string(), RuntimeValue::Integer(i64::MIN));
+    map
+}
+
+#[test]
+fn test_overflow_is_error_by_default() {
+    let cases = [
+        ("max + 1 > 0", "addition", "9223372036854775807 + 1"),
+        ("min - 1 < 0", "subtraction", "-9223372036854775808 - 1"),
+        ("max * 2 > 0", "multiplication", "9223372036854775807 * 2"),
+        ("-min > 0", "unary minus", "-(-9223372036854775808)"),
+        ("ABS(min) > 0", "ABS", "ABS(-9223372036854775808)"),
+        ("ROUND(max, -1) > 0", "ROUND", "ROUND(9223372036854775807, -1)"),
+    ];
+    for (input, operation, expression) in cases {
+        let err = evaluate(input, &overflow_map()).unwrap_err();
+        assert_eq!(err, EvalError::Overflow {
+            operation: operation.to_string(),
+            expression: expression.to_string(),
+        }, "{}", input);
+    }
+    let err = evaluate("max + 1 > 0", &overflow_map()).unwrap_err();
+    assert_eq!(err.to_string(), "Overflow in addition: 9223372036854775807 + 1");
+}
+
+#[test]
+fn test_overflow_promote_to_float() {
+    let options = EvalOptions::new().overflow_policy(OverflowPolicy::PromoteToFloat);
+    let cases = [
+        "max + 1 = 9223372036854775808.0",
+        "min - 1 = -9223372036854775809.0",
+        "max * 2 = 18446744073709551614.0",
+        "-min = 9223372036854775808.0",
+        "ABS(min) = 9223372036854775808.0",
+        "ROUND(max, -1) = 9223372036854775810.0",
+        // Results that fit stay integers
+        "max - 1 = 9223372036854775806",
+    ];
+    for input in cases {
+        let expr = CompiledExpr::compile(input).unwrap();
+        assert_eq!(expr.eval_with(&overflow_map(), &options).unwrap(), true, "{}", input);
+    }
+}
+
+#[test]
+fn test_overflow_saturate() {
+    let options = EvalOptions::new().overflow_policy(OverflowPolicy::Saturate);
+    let cases = [
+        "max + 1 = max",
+        "min - 1 = min",
+        "max * 2 = max",
+        "min * 2 = min",
+        "-min = max",
+        "ABS(min) = max",
+        "ROUND(max, -1) = max",
+        "max + 1 - 1 = max - 1",
+    ];
+    for input in cases {
+        let expr = CompiledExpr::compile(input).unwrap();
+        assert_eq!(expr.eval_with(&overflow_map(), &options).unwrap(), true, "{}", input);
+    }
+}
+
+#[test]
+fn test_overflow_remainder_never_overflows() {
+    let map = overflow_map();
+    assert_eq!(evaluate("min % -1 = 0", &map).unwrap(), true);
+    assert_eq!(evaluate("MOD(min, -1) = 0", &map).unwrap(), true);
+}
+
+#[test]
+fn test_overflow_partial_eval() {
+    let expr = CompiledExpr::compile("max + 1 > x").unwrap();
+    let err = expr.partial_eval(&overflow_map()).unwrap_err();
+    assert!(matches!(err, EvalError::Overflow { .. }));
+
+    let options = EvalOptions::new().overflow_policy(OverflowPolicy::Saturate);
+    match expr.partial_eval_with(&overflow_map(), &options).unwrap() {
+        PartialResult::Residual(residual) => assert_eq!(residual.to_string(), "9223372036854775807 > x"),
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+}
+
+// ============================================================================
+// DECIMAL TESTS
+// ============================================================================
+
+fn decimal(s: &str) -> RuntimeValue {
+    RuntimeValue::Decimal(s.parse().unwrap())
+}
+
+fn decimal_map() -> HashMap<String, RuntimeValue> {
+    let mut map = HashMap::new();
+    map.insert("price".to_string(), decimal("19.99"));
+    map.insert("qty".to_string(), RuntimeValue::Integer(3));
+    map.insert("ratio".to_string(), RuntimeValue::Float(0.5));
+    map.insert("x".to_string(), RuntimeValue::Integer(10));
+    map
+}
+
+fn eval_decimal(input: &str) -> Result<bool, EvalError> {
+    let options = ParserOptions::new().decimal_literals(true);
+    CompiledExpr::compile_with_options(input, &options).unwrap().eval(&decimal_map())
+}
+
+#[test]
+fn test_decimal_literals_are_exact() {
+    assert_eq!(eval_decimal("0.1 + 0.2 = 0.3").unwrap(), true);
+    assert_eq!(eval_decimal("0.3 - 0.1 = 0.2").unwrap(), true);
+    assert_eq!(eval_decimal("1.10 * 3 = 3.3").unwrap(), true);
+    // Products that only need fractional digits dropped stay in range
+    assert_eq!(eval_decimal("1.0000000000000000000000 * 1.00000000000000000000 = 1").unwrap(), true);
+    // Float literals keep their floating point behavior
+    assert_eq!(evaluate("0.1 + 0.2 = 0.3", &HashMap::new()).unwrap(), false);
+}
+
+#[test]
+fn test_decimal_arithmetic_with_integers() {
+    let cases = [
+        "price * qty = 59.97",
+        "price + 1 = 20.99",
+        "1 - price = -18.99",
+        "price / 4 = 4.9975",
+        "100 / price > 5",
+        "price % 1 = 0.99",
+        "-price < 0",
+        "+price = 19.990",
+        "price / 3 = 6.66333333333333333333",
+    ];
+    for input in cases {
+        assert_eq!(eval_decimal(input).unwrap(), true, "{}", input);
+    }
+}
+
+#[test]
+fn test_decimal_comparisons() {
+    let cases = [
+        "price = 19.99",
+        "price > 19",
+        "20 > price",
+        "price <> 19.9",
+        "price BETWEEN 19.99 AND 20",
+        "price BETWEEN 19 AND 20",
+        "price IN (9.99, 19.99)",
+        "qty IN (1.0, 3.00)",
+        "NOT price IN (19.9, 20.0)",
+    ];
+    for input in cases {
+        assert_eq!(eval_decimal(input).unwrap(), true, "{}", input);
+    }
+}
+
+#[test]
+fn test_decimal_mixed_with_float_gives_float() {
+    let map = decimal_map();
+    assert_eq!(evaluate("price + ratio = 20.49", &map).unwrap(), true);
+    assert_eq!(evaluate("price > 19.5", &map).unwrap(), true);
+    assert_eq!(evaluate("price * ratio < 10.0", &map).unwrap(), true);
+}
+
+#[test]
+fn test_decimal_division_by_zero() {
+    let err = eval_decimal("price / 0.00 > 1").unwrap_err();
+    assert!(matches!(err, EvalError::DivisionByZero { .. }));
+    let err = eval_decimal("price % 0 > 1").unwrap_err();
+    assert!(matches!(err, EvalError::DivisionByZero { .. }));
+}
+
+#[test]
+fn test_decimal_overflow_ignores_policy() {
+    let mut map = HashMap::new();
+    map.insert("big".to_string(), decimal("99999999999999999999999999999999999999"));
+    for policy in [OverflowPolicy::Error, OverflowPolicy::PromoteToFloat, OverflowPolicy::Saturate] {
+        let options = EvalOptions::new().overflow_policy(policy);
+        let err = CompiledExpr::compile("big + 1 > 0").unwrap().eval_with(&map, &options).unwrap_err();
+        assert_eq!(err.to_string(), "Overflow in decimal addition: 99999999999999999999999999999999999999 + 1");
+    }
+}
+
+#[test]
+fn test_decimal_functions() {
+    let cases = [
+        "ROUND(price, 1) = 20.0",
+        "ROUND(price) = 20",
+        "ROUND(price, -1) = 20",
+        "FLOOR(price) = 19",
+        "CEIL(-price) = -19",
+        "ABS(-price) = price",
+        "SIGN(-price) = -1",
+        "MOD(price, 2) = 1.99",
+        "SQRT(price * 0) = 0",
+        "NULLIF(price, 19.990) IS NULL",
+        "COALESCE(NULL, price) = 19.99",
+    ];
+    for input in cases {
+        assert_eq!(eval_decimal(input).unwrap(), true, "{}", input);
+    }
+}
+
+#[test]
+fn test_decimal_type_errors() {
+    let err = eval_decimal("price LIKE '19%'").unwrap_err();
+    assert_eq!(err, EvalError::TypeError {
+        operation: "LIKE".to_string(),
+        expected: "string".to_string(),
+        actual: "decimal".to_string(),
+        context: "left operand".to_string(),
+    });
+    let err = eval_decimal("price = 'cheap'").unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { actual, .. } if actual == "decimal vs string"));
+}
+
+#[test]
+fn test_decimal_user_function() {
+    let mut functions = FunctionRegistry::new();
+    functions.register("NET", &[ValueType::Decimal], ValueType::Decimal, |args| {
+        match &args[0] {
+            RuntimeValue::Decimal(d) => Ok(RuntimeValue::Decimal(d.checked_mul("0.8".parse().unwrap()).unwrap())),
+            other => Err(format!("unexpected {:?}", other)),
+        }
+    });
+    let parse_options = ParserOptions::new().decimal_literals(true).functions(functions.clone());
+    let options = EvalOptions::new().functions(functions);
+    let map = decimal_map();
+    for input in ["NET(price) = 15.992", "NET(qty) = 2.4"] {
+        let expr = CompiledExpr::compile_with_options(input, &parse_options).unwrap();
+        assert_eq!(expr.eval_with(&map, &options).unwrap(), true, "{}", input);
+    }
+}
+
+#[test]
+fn test_decimal_partial_eval() {
+    let expr = CompiledExpr::compile("price * 2 > y").unwrap();
+    match expr.partial_eval(&decimal_map()).unwrap() {
+        PartialResult::Residual(residual) => {
+            assert_eq!(residual.to_string(), "DECIMAL '39.98' > y");
+            // The residual's text keeps decimal semantics when parsed again: the
+            // float nearest 39.98 is just below the exact decimal
+            let mut map = HashMap::new();
+            map.insert("y".to_string(), RuntimeValue::Float(39.98));
+            let reparsed = CompiledExpr::compile(&residual.to_string()).unwrap();
+            assert_eq!(reparsed.eval(&map).unwrap(), true);
+        }
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+}
+
+// ============================================================================
+// TEMPORAL TESTS
+// ============================================================================
+
+fn temporal_map() -> HashMap<String, RuntimeValue> {
+    let mut map = HashMap::new();
+    map.insert("due".to_string(), RuntimeValue::Date("2024-01-31".parse().unwrap()));
+    map.insert("created_at".to_string(), RuntimeValue::Timestamp("2024-01-31 10:00:00+02:00".parse().unwrap()));
+    map.insert("grace".to_string(), RuntimeValue::Interval("3 days".parse().unwrap()));
+    map.insert("n".to_string(), RuntimeValue::Integer(2));
+    map
+}
+
+#[test]
+fn test_temporal_comparisons() {
+    let cases = [
+        "due = DATE '2024-01-31'",
+        "due < DATE '2024-02-01'",
+        "created_at = TIMESTAMP '2024-01-31 08:00:00Z'",
+        "created_at > due",
+        "due <= created_at",
+        "DATE '2024-01-31' = TIMESTAMP '2024-01-31 00:00:00'",
+        "grace > INTERVAL '2 days'",
+        "INTERVAL '1 month' = INTERVAL '30 days'",
+        "due BETWEEN DATE '2024-01-01' AND DATE '2024-12-31'",
+        "created_at BETWEEN DATE '2024-01-31' AND TIMESTAMP '2024-01-31 12:00:00'",
+        "grace BETWEEN INTERVAL '1' DAY AND INTERVAL '1' WEEK",
+        "due IN (DATE '2024-01-30', DATE '2024-01-31')",
+        "created_at NOT IN (TIMESTAMP '2024-01-31 10:00:00')",
+    ];
+    let map = temporal_map();
+    for input in cases {
+        assert_eq!(evaluate(input, &map).unwrap(), true, "{}", input);
+    }
+}
+
+#[test]
+fn test_temporal_arithmetic() {
+    let cases = [
+        "due + INTERVAL '1' MONTH = DATE '2024-02-29'",
+        "due - INTERVAL '31 days' = DATE '2023-12-31'",
+        "due + grace * n = DATE '2024-02-06'",
+        "due + INTERVAL '12 hours' = TIMESTAMP '2024-01-31 12:00:00'",
+        "DATE '2024-03-01' - due = INTERVAL '30 days'",
+        "created_at - due = INTERVAL '8 hours'",
+        "created_at + INTERVAL '1 day 30 minutes' = TIMESTAMP '2024-02-01 08:30:00Z'",
+        "created_at - INTERVAL '1' YEAR < DATE '2023-02-01'",
+        "-grace + INTERVAL '4 days' = INTERVAL '1 day'",
+        "n * INTERVAL '1 week' = INTERVAL '14 days'",
+    ];
+    let map = temporal_map();
+    for input in cases {
+        assert_eq!(evaluate(input, &map).unwrap(), true, "{}", input);
+    }
+}
+
+#[test]
+fn test_temporal_now() {
+    let mut map = temporal_map();
+    assert_eq!(evaluate("created_at > NOW() - INTERVAL '7' DAY", &map).unwrap(), false);
+    map.insert("created_at".to_string(), RuntimeValue::Timestamp(sqlexpr_rust::Timestamp::now()));
+    assert_eq!(evaluate("created_at > NOW() - INTERVAL '7' DAY", &map).unwrap(), true);
+    assert_eq!(evaluate("NOW() > TIMESTAMP '2024-01-01 00:00:00'", &map).unwrap(), true);
+}
+
+#[test]
+fn test_temporal_overflow() {
+    let err = evaluate("DATE '9999-12-31' + INTERVAL '1 day' > due", &temporal_map()).unwrap_err();
+    assert_eq!(err.to_string(), "Overflow in date and time addition: DATE '9999-12-31' + INTERVAL '1 day'");
+}
+
+#[test]
+fn test_temporal_type_errors() {
+    let map = temporal_map();
+    let err = evaluate("due = '2024-01-31'", &map).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { actual, .. } if actual == "date vs string"));
+    let err = evaluate("due + 1 > due", &map).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { actual, .. } if actual == "date and integer"));
+    let err = evaluate("due + due > due", &map).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { actual, .. } if actual == "date and date"));
+    let err = evaluate("grace > due", &map).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { actual, .. } if actual == "interval vs date"));
+    let err = evaluate("due IN (INTERVAL '1 day')", &map).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { .. }));
+}
+
+#[test]
+fn test_temporal_user_function() {
+    let mut functions = FunctionRegistry::new();
+    functions.register("YEAR", &[ValueType::Timestamp], ValueType::Integer, |args| {
+        match &args[0] {
+            RuntimeValue::Timestamp(t) => Ok(RuntimeValue::Integer(t.to_string()[..4].parse().unwrap())),
+            other => Err(format!("unexpected {:?}", other)),
+        }
+    });
+    let parse_options = ParserOptions::new().functions(functions.clone());
+    let options = EvalOptions::new().functions(functions);
+    for input in ["YEAR(due) = 2024", "YEAR(created_at + INTERVAL '1 year') = 2025"] {
+        let expr = CompiledExpr::compile_with_options(input, &parse_options).unwrap();
+        assert_eq!(expr.eval_with(&temporal_map(), &options).unwrap(), true, "{}", input);
+    }
+}
+
+#[test]
+fn test_temporal_partial_eval() {
+    let expr = CompiledExpr::compile("due + grace < deadline").unwrap();
+    match expr.partial_eval(&temporal_map()).unwrap() {
+        PartialResult::Residual(residual) => assert_eq!(residual.to_string(), "DATE '2024-02-03' < deadline"),
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+}
+
+// ============================================================================
+// VARIABLE PATH TESTS
+// ============================================================================
+
+fn string(s: &str) -> RuntimeValue {
+    RuntimeValue::String(s.to_string())
+}
+
+fn map_of(entries: &[(&str, RuntimeValue)]) -> RuntimeValue {
+    RuntimeValue::Map(entries.iter().map(|(k, v)| (k.to_string(), v.clone())).collect())
+}
+
+fn nested_map() -> HashMap<String, RuntimeValue> {
+    let mut map = HashMap::new();
+    map.insert("order".to_string(), map_of(&[
+        ("customer", map_of(&[("tier", string("gold")), ("vip", RuntimeValue::Boolean(true))])),
+        ("coupon", RuntimeValue::Null),
+        ("total", RuntimeValue::Integer(250)),
+    ]));
+    map.insert("items".to_string(), RuntimeValue::List(vec![
+        map_of(&[("sku", string("A1")), ("qty", RuntimeValue::Integer(2))]),
+        map_of(&[("sku", string("B7")), ("qty", RuntimeValue::Integer(1))]),
+    ]));
+    map.insert("headers".to_string(), map_of(&[("x-id", string("abc"))]));
+    map
+}
+
+#[test]
+fn test_path_resolution() {
+    let cases = [
+        "order.customer.tier = 'gold'",
+        "order.customer.vip",
+        "order.total * 2 > 400",
+        "items[0].sku = 'A1'",
+        "items[1].qty + items[0].qty = 3",
+        "headers['x-id'] = 'abc'",
+        "UPPER(items[1].sku) LIKE 'B%'",
+        "order.coupon IS NULL",
+        "order.coupon.code IS NULL",
+    ];
+    let map = nested_map();
+    for input in cases {
+        assert_eq!(evaluate(input, &map).unwrap(), true, "{}", input);
+    }
+}
+
+#[test]
+fn test_path_missing_segment_is_unbound() {
+    let map = nested_map();
+    let cases = [
+        ("order.customer.name = 'x'", "order.customer.name"),
+        ("items[2].sku = 'x'", "items[2]"),
+        ("headers['x-trace'] = 'x'", "headers['x-trace']"),
+        ("cart.total > 0", "cart"),
+    ];
+    for (input, name) in cases {
+        let err = evaluate(input, &map).unwrap_err();
+        assert_eq!(err, EvalError::UnboundVariable { name: name.to_string() }, "{}", input);
+    }
+}
+
+#[test]
+fn test_path_type_errors() {
+    let map = nested_map();
+    let err = evaluate("order.total.cents > 0", &map).unwrap_err();
+    assert_eq!(err, EvalError::TypeError {
+        operation: "path".to_string(),
+        expected: "map".to_string(),
+        actual: "integer".to_string(),
+        context: "path 'order.total.cents'".to_string(),
+    });
+    let err = evaluate("order[0] = 1", &map).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { expected, actual, .. } if expected == "list" && actual == "map"));
+    let err = evaluate("order.customer = 'gold'", &map).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { actual, .. } if actual == "map vs string"));
+    let err = evaluate("order.customer.tier", &map).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { context, .. } if context == "variable 'order.customer.tier'"));
+}
+
+#[test]
+fn test_path_error_span() {
+    let input = "items[0].qty > 1 AND items[5].qty > 1";
+    let expr = CompiledExpr::compile(input).unwrap();
+    let err = expr.eval_spanned(&nested_map(), &EvalOptions::default()).unwrap_err();
+    assert_eq!(&input[err.span.start..err.span.end], "items[5].qty");
+}
+
+#[test]
+fn test_path_partial_eval() {
+    let expr = CompiledExpr::compile("order.total > limit AND order.customer.vip AND items[0].sku = sku").unwrap();
+    let mut known = nested_map();
+    known.insert("limit".to_string(), RuntimeValue::Integer(100));
+    match expr.partial_eval(&known).unwrap() {
+        PartialResult::Residual(residual) => assert_eq!(residual.to_string(), "'A1' = sku"),
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+
+    let expr = CompiledExpr::compile("flags.beta OR order.total > 1000").unwrap();
+    match expr.partial_eval(&nested_map()).unwrap() {
+        PartialResult::Residual(residual) => assert_eq!(residual.to_string(), "flags.beta"),
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+}
+
+#[test]
+fn test_collection_parameter_cannot_be_bound() {
+    let expr = CompiledExpr::compile("tier = ?").unwrap();
+    let err = expr.bind(&Parameters::new().push(RuntimeValue::List(vec![]))).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { actual, .. } if actual == "list"));
+}
+
+// ============================================================================
+// COLLECTION PREDICATE TESTS
+// ============================================================================
+
+fn collection_map() -> HashMap<String, RuntimeValue> {
+    let mut map = HashMap::new();
+    map.insert("tags".to_string(), RuntimeValue::List(vec![string("new"), string("vip")]));
+    map.insert("scores".to_string(), RuntimeValue::List(vec![
+        RuntimeValue::Integer(91),
+        RuntimeValue::Float(97.5),
+        RuntimeValue::Integer(95),
+    ]));
+    map.insert("attrs".to_string(), map_of(&[("color", string("red")), ("size", string("XL"))]));
+    map.insert("none".to_string(), RuntimeValue::List(vec![]));
+    map.insert("gaps".to_string(), RuntimeValue::List(vec![RuntimeValue::Integer(1), RuntimeValue::Null]));
+    map.insert("missing".to_string(), RuntimeValue::Null);
+    map.insert("x".to_string(), string("vip"));
+    map
+}
+
+#[test]
+fn test_collection_predicates() {
+    let cases = [
+        ("tags CONTAINS 'vip'", true),
+        ("tags CONTAINS 'old'", false),
+        ("tags NOT CONTAINS 'old'", true),
+        ("x IN tags", true),
+        ("'old' NOT IN tags", true),
+        ("scores CONTAINS 95.0", true),
+        ("attrs CONTAINS 'color'", true),
+        ("'red' IN attrs", false),
+        ("ANY(scores) > 96", true),
+        ("ALL(scores) > 90", true),
+        ("ALL(scores) > 91", false),
+        ("90 < ALL(scores)", true),
+        ("ANY(attrs) = 'XL'", true),
+        ("ANY(none) = 1", false),
+        ("ALL(none) = 1", true),
+        ("CARDINALITY(tags) = 2", true),
+        ("CARDINALITY(attrs) + CARDINALITY(none) = 2", true),
+    ];
+    let map = collection_map();
+    for (input, expected) in cases {
+        assert_eq!(evaluate(input, &map).unwrap(), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_collection_predicate_type_errors() {
+    let map = collection_map();
+    let err = evaluate("x CONTAINS 'v'", &map).unwrap_err();
+    assert_eq!(err, EvalError::TypeError {
+        operation: "CONTAINS".to_string(),
+        expected: "list or map".to_string(),
+        actual: "string".to_string(),
+        context: "collection operand".to_string(),
+    });
+    let err = evaluate("ANY(x) = 'v'", &map).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { operation, actual, .. } if operation == "ANY" && actual == "string"));
+    let err = evaluate("1 IN tags", &map).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { actual, .. } if actual == "integer vs string"));
+    let err = evaluate("CARDINALITY(x) > 0", &map).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { operation, .. } if operation == "CARDINALITY"));
+}
+
+#[test]
+fn test_collection_predicates_with_null() {
+    let map = collection_map();
+    assert!(matches!(evaluate("missing CONTAINS 1", &map), Err(EvalError::NullInOperation { .. })));
+    assert!(matches!(evaluate("gaps CONTAINS 2", &map), Err(EvalError::NullInOperation { .. })));
+
+    assert_eq!(eval_3vl("missing CONTAINS 1", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("gaps CONTAINS 1", &map), Truth::True);
+    assert_eq!(eval_3vl("gaps CONTAINS 2", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("2 NOT IN gaps", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("ANY(gaps) = 1", &map), Truth::True);
+    assert_eq!(eval_3vl("ALL(gaps) = 1", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("ALL(gaps) = 2", &map), Truth::False);
+    assert_eq!(eval_3vl("ALL(missing) > 0", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("ALL(none) > missing", &map), Truth::True);
+}
+
+#[test]
+fn test_quantified_error_span() {
+    let input = "ANY(scores) > 90 AND ALL(x) > 1";
+    let expr = CompiledExpr::compile(input).unwrap();
+    let err = expr.eval_spanned(&collection_map(), &EvalOptions::default()).unwrap_err();
+    assert_eq!(&input[err.span.start..err.span.end], "ALL(x)");
+}
+
+#[test]
+fn test_collection_predicates_partial_eval() {
+    let expr = CompiledExpr::compile("tags CONTAINS tag AND ANY(scores) > 90").unwrap();
+    match expr.partial_eval(&collection_map()).unwrap() {
+        PartialResult::Residual(residual) => assert_eq!(residual.to_string(), "tags CONTAINS tag"),
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+}
+
+// ============================================================================
+// EXACT NUMERIC COMPARISON TESTS
+// ============================================================================
+
+fn large_number_map() -> HashMap<String, RuntimeValue> {
+    let mut map = HashMap::new();
+    // 2^53 + 1 has no exact float form and rounds to 2^53
+    map.insert("id".to_string(), RuntimeValue::Integer(9_007_199_254_740_993));
+    map.insert("f".to_string(), RuntimeValue::Float(9_007_199_254_740_992.0));
+    map.insert("nan".to_string(), RuntimeValue::Float(f64::NAN));
+    map.insert("inf".to_string(), RuntimeValue::Float(f64::INFINITY));
+    map.insert("neg_zero".to_string(), RuntimeValue::Float(-0.0));
+    map.insert("tenth".to_string(), RuntimeValue::Decimal("0.1".parse().unwrap()));
+    map
+}
+
+#[test]
+fn test_large_integer_float_comparison_is_exact() {
+    let map = large_number_map();
+    assert_eq!(evaluate("id = f", &map), Ok(false));
+    assert_eq!(evaluate("f <> id", &map), Ok(true));
+    assert_eq!(evaluate("id > f", &map), Ok(true));
+    assert_eq!(evaluate("f >= id", &map), Ok(false));
+    assert_eq!(evaluate("id - 1 = f", &map), Ok(true));
+    assert_eq!(evaluate("id IN (f, 1.5)", &map), Ok(false));
+    assert_eq!(evaluate("id - 1 IN (f, 1.5)", &map), Ok(true));
+    assert_eq!(evaluate("id BETWEEN f AND f", &map), Ok(false));
+    assert_eq!(evaluate("id BETWEEN f AND f + 2.0", &map), Ok(true));
+    assert_eq!(evaluate("NULLIF(id, f) IS NULL", &map), Ok(false));
+    assert_eq!(evaluate("9223372036854775807 < 9223372036854775807.0", &map), Ok(true));
+}
+
+#[test]
+fn test_nan_and_infinity_ordering() {
+    let map = large_number_map();
+    assert_eq!(evaluate("nan = nan", &map), Ok(true));
+    assert_eq!(evaluate("nan > inf", &map), Ok(true));
+    assert_eq!(evaluate("9223372036854775807 < nan", &map), Ok(true));
+    assert_eq!(evaluate("inf > 9223372036854775807", &map), Ok(true));
+    assert_eq!(evaluate("-inf < -9223372036854775807 - 1", &map), Ok(true));
+    assert_eq!(evaluate("neg_zero = 0", &map), Ok(true));
+    assert_eq!(evaluate("neg_zero IN (0.0)", &map), Ok(true));
+    assert_eq!(evaluate("nan IN (1, nan)", &map), Ok(true));
+    assert_eq!(evaluate("nan BETWEEN 0 AND inf", &map), Ok(false));
+    assert_eq!(evaluate("NULLIF(nan, nan) IS NULL", &map), Ok(true));
+}
+
+#[test]
+fn test_decimal_float_comparison_is_exact() {
+    let map = large_number_map();
+    // The float nearest to 0.1 is slightly above it
+    assert_eq!(evaluate("tenth = 0.1", &map), Ok(false));
+    assert_eq!(evaluate("tenth < 0.1", &map), Ok(true));
+    assert_eq!(evaluate("tenth * 5 = 0.5", &map), Ok(true));
+    assert_eq!(evaluate("tenth BETWEEN 0 AND 0.1", &map), Ok(true));
+}
+
+// ============================================================================
+// COLLATION TESTS
+// ============================================================================
+
+fn collation_map() -> HashMap<String, RuntimeValue> {
+    let mut map = HashMap::new();
+    map.insert("country".to_string(), string("Germany"));
+    // "Café" with a precomposed é, and "CAFE" followed by a combining acute accent
+    map.insert("composed".to_string(), string("Caf\u{e9}"));
+    map.insert("decomposed".to_string(), string("CAFE\u{301}"));
+    map.insert("tags".to_string(), RuntimeValue::List(vec![string("VIP"), string("New")]));
+    map
+}
+
+fn eval_collated(input: &str, collation: Collation) -> Result<bool, EvalError> {
+    let options = EvalOptions::new().collation(collation);
+    CompiledExpr::compile(input).unwrap().eval_with(&collation_map(), &options)
+}
+
+#[test]
+fn test_collation_option() {
+    let cases = [
+        ("country = 'germany'", false, true),
+        ("country <> 'GERMANY'", true, false),
+        ("country > 'france'", false, true),
+        ("country BETWEEN 'france' AND 'italy'", false, true),
+        ("country IN ('GERMANY', 'spain')", false, true),
+        ("country LIKE 'ger%'", false, true),
+        ("country LIKE 'g_rm%y'", false, true),
+        ("country NOT LIKE '%MANY'", true, false),
+        ("tags CONTAINS 'vip'", false, true),
+        ("'new' IN tags", false, true),
+        ("ANY(tags) = 'vip'", false, true),
+    ];
+    for (input, binary, nocase) in cases {
+        assert_eq!(eval_collated(input, Collation::Binary), Ok(binary), "{}", input);
+        assert_eq!(eval_collated(input, Collation::NoCase), Ok(nocase), "{}", input);
+    }
+}
+
+#[test]
+fn test_unicode_normalized_comparison() {
+    assert_eq!(eval_collated("composed = 'Caf\u{e9}'", Collation::Binary), Ok(true));
+    assert_eq!(eval_collated("composed = 'Cafe\u{301}'", Collation::Binary), Ok(false));
+    assert_eq!(eval_collated("composed = 'Cafe\u{301}'", Collation::Unicode), Ok(true));
+    assert_eq!(eval_collated("composed LIKE '%e\u{301}'", Collation::Unicode), Ok(true));
+    assert_eq!(eval_collated("composed = decomposed", Collation::Unicode), Ok(false));
+    assert_eq!(eval_collated("composed = decomposed", Collation::NoCase), Ok(true));
+    assert_eq!(eval_collated("decomposed LIKE 'caf_'", Collation::NoCase), Ok(true));
+}
+
+#[test]
+fn test_collate_clause_overrides_option() {
+    let map = collation_map();
+    assert_eq!(evaluate("country = 'germany' COLLATE NOCASE", &map), Ok(true));
+    assert_eq!(evaluate("country = 'germany' COLLATE NOCASE AND country = 'germany'", &map), Ok(false));
+    assert_eq!(evaluate("country LIKE 'GER%' COLLATE NOCASE", &map), Ok(true));
+    assert_eq!(evaluate("composed = 'Cafe\u{301}' COLLATE UNICODE", &map), Ok(true));
+    assert_eq!(eval_collated("country = 'germany' COLLATE BINARY", Collation::NoCase), Ok(false));
+    // Collations do not affect values other than strings
+    assert_eq!(evaluate("1 = 1.0 COLLATE NOCASE", &map), Ok(true));
+    assert_eq!(evaluate("country IS NOT NULL COLLATE NOCASE", &map), Ok(true));
+}
+
+#[test]
+fn test_collate_clause_partial_eval_and_parameters() {
+    let expr = CompiledExpr::compile("country = :c COLLATE NOCASE AND size > 1").unwrap();
+    let bound = expr.bind(&Parameters::new().set("c", string("GERMANY"))).unwrap();
+    match bound.partial_eval(&collation_map()).unwrap() {
+        PartialResult::Residual(residual) => assert_eq!(residual.to_string(), "size > 1"),
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+    let expr = CompiledExpr::compile("name = 'x' COLLATE NOCASE OR size > 1").unwrap();
+    match expr.partial_eval(&collation_map()).unwrap() {
+        PartialResult::Residual(residual) => {
+            assert_eq!(residual.to_string(), "(name = 'x' COLLATE NOCASE OR size > 1)");
+        }
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+}
+
+// ============================================================================
+// PATTERN OPERATOR TESTS
+// ============================================================================
+
+#[test]
+fn test_ilike() {
+    let map = collation_map();
+    assert_eq!(evaluate("country ILIKE 'GER%'", &map), Ok(true));
+    assert_eq!(evaluate("country ILIKE 'g_rmany'", &map), Ok(true));
+    assert_eq!(evaluate("country NOT ILIKE '%MANY'", &map), Ok(false));
+    assert_eq!(evaluate("decomposed ILIKE 'caf\u{e9}'", &map), Ok(true));
+    // LIKE stays case-sensitive beside it
+    assert_eq!(evaluate("country LIKE 'GER%' OR country ILIKE 'x%'", &map), Ok(false));
+    // ILIKE ignores case under any collation
+    assert_eq!(eval_collated("country ILIKE 'germany' COLLATE BINARY", Collation::Binary), Ok(true));
+}
+
+#[test]
+fn test_regexp() {
+    let mut map = HashMap::new();
+    map.insert("code".to_string(), string("DE-1234"));
+    map.insert("note".to_string(), string("line one\nline two"));
+    assert_eq!(evaluate("code REGEXP '^[A-Z]{2}-[0-9]+$'", &map), Ok(true));
+    assert_eq!(evaluate("code REGEXP '[0-9]{5}'", &map), Ok(false));
+    // REGEXP matches anywhere in the string unless anchored
+    assert_eq!(evaluate("code REGEXP '12'", &map), Ok(true));
+    assert_eq!(evaluate("code NOT REGEXP '^de'", &map), Ok(true));
+    assert_eq!(evaluate("code REGEXP '(?i)^de'", &map), Ok(true));
+    assert_eq!(evaluate("note REGEXP '(?m)^line two$'", &map), Ok(true));
+}
+
+#[test]
+fn test_similar_to() {
+    let mut map = HashMap::new();
+    map.insert("code".to_string(), string("FR-99"));
+    map.insert("file".to_string(), string("report.csv"));
+    assert_eq!(evaluate("code SIMILAR TO '(DE|FR)-%'", &map), Ok(true));
+    assert_eq!(evaluate("code SIMILAR TO '(DE|FR)-[0-9]{3}'", &map), Ok(false));
+    assert_eq!(evaluate("code NOT SIMILAR TO 'ES-%'", &map), Ok(true));
+    // SIMILAR TO matches the whole string, and . is an ordinary character
+    assert_eq!(evaluate("code SIMILAR TO 'FR'", &map), Ok(false));
+    assert_eq!(evaluate("file SIMILAR TO '%.csv'", &map), Ok(true));
+    assert_eq!(evaluate("file SIMILAR TO 'report_csv'", &map), Ok(true));
+    assert_eq!(evaluate("file SIMILAR TO 'report.(csv|tsv)'", &map), Ok(true));
+    assert_eq!(evaluate("file SIMILAR TO 'report!_csv' ESCAPE '!'", &map), Ok(false));
+}
+
+#[test]
+fn test_pattern_operators_with_null_and_wrong_types() {
+    let mut map = HashMap::new();
+    map.insert("n".to_string(), RuntimeValue::Null);
+    map.insert("x".to_string(), RuntimeValue::Integer(5));
+    for (input, operation) in [("n ILIKE 'a'", "ILIKE"), ("n REGEXP 'a'", "REGEXP"), ("n SIMILAR TO 'a'", "SIMILAR TO")] {
+        let err = evaluate(input, &map).unwrap_err();
+        assert!(matches!(&err, EvalError::NullInOperation { operation: op, .. } if op == operation), "{}: {:?}", input, err);
+        assert_eq!(eval_3vl(input, &map), Truth::Unknown, "{}", input);
+    }
+    let err = evaluate("x REGEXP '5'", &map).unwrap_err();
+    assert_eq!(err, EvalError::TypeError {
+        operation: "REGEXP".to_string(),
+        expected: "string".to_string(),
+        actual: "integer".to_string(),
+        context: "left operand".to_string(),
+    });
+}
+
+#[test]
+fn test_pattern_operators_partial_eval() {
+    let expr = CompiledExpr::compile("code SIMILAR TO 'a%' AND name ILIKE 'b%' AND tag REGEXP 'c'").unwrap();
+    let mut known = HashMap::new();
+    known.insert("name".to_string(), string("Bob"));
+    match expr.partial_eval(&known).unwrap() {
+        PartialResult::Residual(residual) => {
+            assert_eq!(residual.to_string(), "(code SIMILAR TO 'a%' AND tag REGEXP 'c')");
+        }
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+}
+
+// ============================================================================
+// STRING CONCATENATION TESTS
+// ============================================================================
+
+fn person_map() -> HashMap<String, RuntimeValue> {
+    let mut map = HashMap::new();
+    map.insert("first".to_string(), string("Ada"));
+    map.insert("last".to_string(), string("Lovelace"));
+    map.insert("born".to_string(), RuntimeValue::Integer(1815));
+    map.insert("height".to_string(), RuntimeValue::Float(1.5));
+    map.insert("middle".to_string(), RuntimeValue::Null);
+    map.insert("tags".to_string(), RuntimeValue::List(vec![string("math")]));
+    map
+}
+
+#[test]
+fn test_concat_strings() {
+    let map = person_map();
+    assert_eq!(evaluate("first || ' ' || last = 'Ada Lovelace'", &map), Ok(true));
+    assert_eq!(evaluate("first || last LIKE 'Ada%ace'", &map), Ok(true));
+    assert_eq!(evaluate("UPPER(first || '!') = 'ADA!'", &map), Ok(true));
+    assert_eq!(evaluate("'' || '' = ''", &map), Ok(true));
+}
+
+#[test]
+fn test_concat_scalars_as_text() {
+    let map = person_map();
+    assert_eq!(evaluate("first || born = 'Ada1815'", &map), Ok(true));
+    // || binds more loosely than arithmetic
+    assert_eq!(evaluate("'v' || born + 1 = 'v1816'", &map), Ok(true));
+    assert_eq!(evaluate("height || 'm' = '1.5m'", &map), Ok(true));
+    assert_eq!(evaluate("2.0 || '' = '2.0'", &map), Ok(true));
+    assert_eq!(evaluate("1.0 || 'x' = '1.0x'", &map), Ok(true));
+    assert_eq!(evaluate("'#' || TRUE = '#true'", &map), Ok(true));
+    assert_eq!(evaluate("'on ' || DATE '1815-12-10' = 'on 1815-12-10'", &map), Ok(true));
+    assert_eq!(evaluate("'' || INTERVAL '36 years' = '36 years'", &map), Ok(true));
+    let mut floats = HashMap::new();
+    floats.insert("inf".to_string(), RuntimeValue::Float(f64::NEG_INFINITY));
+    floats.insert("nan".to_string(), RuntimeValue::Float(f64::NAN));
+    assert_eq!(evaluate("inf || ' ' || nan = '-Infinity NaN'", &floats), Ok(true));
+    // Decimals keep their scale
+    let options = ParserOptions::new().decimal_literals(true);
+    let expr = CompiledExpr::compile_with_options("'$' || 2.50 = '$2.50'", &options).unwrap();
+    assert_eq!(expr.eval(&map), Ok(true));
+}
+
+#[test]
+fn test_concat_type_errors() {
+    let map = person_map();
+    for (input, actual) in [("born || 1 = '18151'", "integer and integer"), ("first || tags = 'x'", "string and list")] {
+        let err = evaluate(input, &map).unwrap_err();
+        assert_eq!(err, EvalError::TypeError {
+            operation: "||".to_string(),
+            expected: "a string and a string or scalar value".to_string(),
+            actual: actual.to_string(),
+            context: "string concatenation".to_string(),
+        }, "{}", input);
+    }
+    // + does not join strings, and says what does
+    let err = evaluate("first + last = 'AdaLovelace'", &map).unwrap_err();
+    assert!(matches!(err, EvalError::TypeError { context, .. } if context.contains("||")));
+}
+
+#[test]
+fn test_concat_with_null() {
+    let map = person_map();
+    let err = evaluate("first || middle = 'Ada'", &map).unwrap_err();
+    assert!(matches!(err, EvalError::NullInOperation { operation, .. } if operation == "||"));
+    assert_eq!(eval_3vl("first || middle = 'Ada'", &map), Truth::Unknown);
+    assert_eq!(eval_3vl("first || middle IS NULL", &map), Truth::True);
+    // COALESCE supplies a default for a missing part
+    assert_eq!(evaluate("first || COALESCE(middle, '') || last = 'AdaLovelace'", &map), Ok(true));
+}
+
+#[test]
+fn test_concat_partial_eval() {
+    let expr = CompiledExpr::compile("first || ' ' || last = name AND title || last = name").unwrap();
+    match expr.partial_eval(&person_map()).unwrap() {
+        PartialResult::Residual(residual) => {
+            assert_eq!(residual.to_string(), "('Ada Lovelace' = name AND (title || 'Lovelace') = name)");
+        }
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+}
+
+// ============================================================================
+// CASE EXPRESSION TESTS
+// ============================================================================
+
+fn order_map(tier: &str, price: f64) -> HashMap<String, RuntimeValue> {
+    let mut map = HashMap::new();
+    map.insert("tier".to_string(), string(tier));
+    map.insert("price".to_string(), RuntimeValue::Float(price));
+    map.insert("qty".to_string(), RuntimeValue::Integer(0));
+    map.insert("total".to_string(), RuntimeValue::Integer(50));
+    map.insert("note".to_string(), RuntimeValue::Null);
+    map
+}
+
+#[test]
+fn test_searched_case() {
+    let input = "CASE WHEN tier = 'gold' THEN price * 0.9 ELSE price END < 100";
+    assert_eq!(evaluate(input, &order_map("gold", 105.0)), Ok(true));
+    assert_eq!(evaluate(input, &order_map("silver", 105.0)), Ok(false));
+    // The first true condition wins
+    let input = "CASE WHEN price > 100 THEN 'high' WHEN price > 10 THEN 'mid' ELSE 'low' END = 'mid'";
+    assert_eq!(evaluate(input, &order_map("gold", 50.0)), Ok(true));
+    assert_eq!(evaluate(input, &order_map("gold", 500.0)), Ok(false));
+}
+
+#[test]
+fn test_simple_case() {
+    let input = "CASE tier WHEN 'gold' THEN 3 WHEN 'silver' THEN 2 ELSE 1 END * 10 = 20";
+    assert_eq!(evaluate(input, &order_map("silver", 1.0)), Ok(true));
+    assert_eq!(evaluate(input, &order_map("bronze", 1.0)), Ok(false));
+    assert_eq!(evaluate("CASE (qty + 1) WHEN 1 THEN 'one' END = 'one'", &order_map("gold", 1.0)), Ok(true));
+}
+
+#[test]
+fn test_simple_case_evaluates_operand_once() {
+    let calls = Arc::new(AtomicI64::new(0));
+    let counter = calls.clone();
+    let mut functions = FunctionRegistry::new();
+    functions.register("next", &[], ValueType::Integer, move |_| {
+        Ok(RuntimeValue::Integer(counter.fetch_add(1, Ordering::Relaxed) + 1))
+    });
+    let input = "CASE next() WHEN 0 THEN 'a' WHEN 1 THEN 'b' WHEN 2 THEN 'c' END = 'b'";
+    let expr = CompiledExpr::compile_with_functions(input, &functions).unwrap();
+    assert_eq!(expr.eval_with(&HashMap::new(), &EvalOptions::new().functions(functions)), Ok(true));
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    // Nested operands take steps in proportion to their size
+    let mut input = "qty".to_string();
+    for _ in 0..30 {
+        input = format!("CASE {} WHEN 1 THEN 1 WHEN 0 THEN 0 END", input);
+    }
+    let expr = CompiledExpr::compile(&format!("{} = 0", input)).unwrap();
+    assert_eq!(expr.eval_with(&order_map("gold", 1.0), &EvalOptions::new().max_steps(400)), Ok(true));
+
+    // The operand or a value may be ANY(...) or ALL(...)
+    let mut map = order_map("gold", 1.0);
+    map.insert("xs".to_string(), RuntimeValue::List(vec![RuntimeValue::Integer(1), RuntimeValue::Integer(2)]));
+    assert_eq!(evaluate("CASE ANY(xs) WHEN 3 THEN 'x' WHEN 2 THEN 'y' END = 'y'", &map), Ok(true));
+    assert_eq!(evaluate("CASE ALL(xs) WHEN 2 THEN 'x' ELSE 'z' END = 'z'", &map), Ok(true));
+    assert_eq!(evaluate("CASE total / 25 WHEN ANY(xs) THEN 'x' END = 'x'", &map), Ok(true));
+}
+
+#[test]
+fn test_simple_case_partial_eval() {
+    let expr = CompiledExpr::compile(
+        "CASE tier WHEN 'x' THEN 1 WHEN region THEN rate WHEN 'gold' THEN total ELSE 0 END > 10",
+    ).unwrap();
+    match expr.partial_eval(&order_map("gold", 1.0)).unwrap() {
+        PartialResult::Residual(residual) => {
+            assert_eq!(residual.to_string(), "CASE 'gold' WHEN region THEN rate ELSE 50 END > 10");
+        }
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+    let expr = CompiledExpr::compile("CASE tier WHEN 'gold' THEN total END > limit").unwrap();
+    match expr.partial_eval(&order_map("gold", 1.0)).unwrap() {
+        PartialResult::Residual(residual) => assert_eq!(residual.to_string(), "50 > limit"),
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+    // An unbound operand keeps every branch
+    let expr = CompiledExpr::compile("CASE region WHEN 'EU' THEN 1 WHEN tier THEN 2 END > 1").unwrap();
+    match expr.partial_eval(&order_map("gold", 1.0)).unwrap() {
+        PartialResult::Residual(residual) => {
+            assert_eq!(residual.to_string(), "CASE region WHEN 'EU' THEN 1 WHEN 'gold' THEN 2 END > 1");
+        }
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+}
+
+#[test]
+fn test_case_only_evaluates_chosen_branch() {
+    let map = order_map("gold", 1.0);
+    assert_eq!(evaluate("CASE WHEN qty = 0 THEN 0 ELSE total / qty END = 0", &map), Ok(true));
+    assert!(matches!(
+        evaluate("CASE WHEN qty <> 0 THEN 0 ELSE total / qty END = 0", &map),
+        Err(EvalError::DivisionByZero { .. })
+    ));
+}
+
+#[test]
+fn test_case_with_null() {
+    let map = order_map("gold", 1.0);
+    // Without ELSE, no matching branch gives NULL
+    assert_eq!(evaluate("CASE WHEN tier = 'x' THEN 1 END IS NULL", &map), Ok(true));
+    assert!(matches!(
+        evaluate("CASE WHEN tier = 'x' THEN 1 END = 1", &map),
+        Err(EvalError::NullInOperation { .. })
+    ));
+    assert!(matches!(
+        evaluate("CASE WHEN note = 'x' THEN 1 ELSE 2 END = 2", &map),
+        Err(EvalError::NullInOperation { .. })
+    ));
+    // An UNKNOWN condition is not true, so its branch is skipped
+    assert_eq!(eval_3vl("CASE WHEN note = 'x' THEN 1 ELSE 2 END = 2", &map), Truth::True);
+    assert_eq!(eval_3vl("CASE WHEN tier = 'x' THEN 1 END = 1", &map), Truth::Unknown);
+}
+
+#[test]
+fn test_case_result_type_checked_when_chosen() {
+    let map = order_map("gold", 1.0);
+    // Numbers of any type mix
+    assert_eq!(evaluate("CASE WHEN tier = 'gold' THEN price ELSE 0 END = 1", &map), Ok(true));
+    assert_eq!(evaluate("CASE WHEN tier = 'gold' THEN note ELSE 0 END IS NULL", &map), Ok(true));
+
+    let input = "CASE WHEN tier = 'gold' THEN tier ELSE -1 END = 'x'";
+    let expr = CompiledExpr::compile(input).unwrap();
+    let err = expr.eval_spanned(&map, &EvalOptions::default()).unwrap_err();
+    assert_eq!(err.error, EvalError::TypeError {
+        operation: "CASE".to_string(),
+        expected: "numeric".to_string(),
+        actual: "string".to_string(),
+        context: "result of a branch".to_string(),
+    });
+    assert_eq!(&input[err.span.start..err.span.end], "tier");
+    // Without literal results there is nothing to check against
+    assert_eq!(evaluate("CASE WHEN qty = 1 THEN tier ELSE total END = 50", &map), Ok(true));
+
+    // Only the result chosen is checked, whichever branch it comes from
+    let mut map = HashMap::new();
+    map.insert("s".to_string(), string("a"));
+    let branch_error = |input: &str, map: &HashMap<String, RuntimeValue>| matches!(
+        evaluate(input, map),
+        Err(EvalError::TypeError { context, .. }) if context == "result of a branch"
+    );
+    map.insert("x".to_string(), RuntimeValue::Integer(10));
+    assert!(branch_error("CASE WHEN x > 9 THEN s ELSE 1 END = 'a'", &map));
+    assert!(branch_error("CASE WHEN x > 9 THEN s ELSE 1 END = 1", &map));
+    map.insert("x".to_string(), RuntimeValue::Integer(1));
+    assert_eq!(evaluate("CASE WHEN x > 9 THEN s ELSE 1 END = 1", &map), Ok(true));
+    assert!(!branch_error("CASE WHEN x > 9 THEN s ELSE 1 END = 'a'", &map));
+}
+
+#[test]
+fn test_case_parameters_and_partial_eval() {
+    let template = CompiledExpr::compile("CASE WHEN tier = :tier THEN price * ? ELSE price END < 100").unwrap();
+    let filter = template
+        .bind(&Parameters::new().set("tier", string("gold")).push(RuntimeValue::Float(0.5)))
+        .unwrap();
+    assert_eq!(filter.ast().to_string(), "CASE WHEN tier = 'gold' THEN (price * 0.5) ELSE price END < 100");
+    assert_eq!(filter.eval(&order_map("gold", 150.0)), Ok(true));
+
+    let expr = CompiledExpr::compile(
+        "CASE WHEN tier = 'x' THEN 1 WHEN region = 'EU' THEN rate WHEN tier = 'gold' THEN total ELSE 0 END > 10",
+    ).unwrap();
+    match expr.partial_eval(&order_map("gold", 1.0)).unwrap() {
+        PartialResult::Residual(residual) => {
+            assert_eq!(residual.to_string(), "CASE WHEN region = 'EU' THEN rate ELSE 50 END > 10");
+        }
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+    let expr = CompiledExpr::compile("CASE WHEN tier = 'gold' THEN total END > limit").unwrap();
+    match expr.partial_eval(&order_map("gold", 1.0)).unwrap() {
+        PartialResult::Residual(residual) => assert_eq!(residual.to_string(), "50 > limit"),
+        PartialResult::Value(v) => panic!("Expected residual, got {}", v),
+    }
+}
@@ -12,8 +12,25 @@
 // - All literal types
 // - Comments (line and block)
 // - Variables/identifiers
-
-use sqlexpr_rust::parse;
+// - Scalar function calls
+// - Source spans
+// - Error kinds and diagnostics
+// - Parameter placeholders
+// - Parser options
+// - Size and nesting limits
+// - Decimal literals
+// - Date, timestamp and interval literals
+// - Variable paths
+// - Collection predicates
+// - String concatenation
+// - CASE expressions
+
+use sqlexpr_rust::{
+    parse, parse_with_options, pretty, BooleanExpr, Collation, Decimal, RegexSyntax, FunctionRegistry, Parameter, ParseError, ParseErrorKind, ParserOptions,
+    CaseBranch, CaseWhen, PathSegment, Quantifier,
+    RelationalExpr, RuntimeValue, Span, ValueExpr, ValueLiteral, ValueType,
+};
+use sqlexpr_rust::lexer::Token;
 
 // ============================================================================
 // BOOLEAN OPERATORS
@@ -445,6 +462,19 @@ fn test_like_multichar_with_escape() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_like_quotes_display_round_trips() {
+    for (input, expected) in [
+        ("s LIKE 'O''B%'", "s LIKE 'O''B%'"),
+        ("s NOT ILIKE '''%'''", "s NOT ILIKE '''%'''"),
+        ("s LIKE 'it''''s%' ESCAPE ''''", "s LIKE 'it''''s%' ESCAPE ''''"),
+    ] {
+        let ast = parse(input).unwrap();
+        assert_eq!(ast.to_string(), expected, "{}", input);
+        assert_eq!(parse(&ast.to_string()).unwrap(), ast, "{}", input);
+    }
+}
+
 #[test]
 fn test_not_like_multichar_with_escape() {
     let result = parse("code NOT LIKE 'TEST\\%USER%' ESCAPE '\\'");
@@ -623,13 +653,28 @@ fn test_not_between_operator() {
 
 #[test]
 fn test_between_with_expressions() {
-    // NOTE: BETWEEN now requires literal bounds, not expressions
-    // This is a breaking change - expressions in BETWEEN are no longer allowed
+    // Bounds may be any value expressions; only literal bounds are checked at parse time
     let result = parse("(x + y) BETWEEN (a - 5) AND (b * 2)");
-    if let Ok(r) = &result {
-        eprintln!("Expected error but found success: {}", r);
+    if let Err(e) = &result {
+        eprintln!("Parse error: {}", e);
+    }
+    assert!(result.is_ok(), "Expected BETWEEN with expression bounds");
+}
+
+#[test]
+fn test_between_literal_bounds_checked_beside_expressions() {
+    for input in ["price BETWEEN min_price AND max_price * 1.1", "x BETWEEN 10 AND y", "a BETWEEN b.low AND 10", "a BETWEEN ? AND 10"] {
+        assert!(parse(input).is_ok(), "{}", input);
+    }
+    let cases = [
+        ("x BETWEEN y AND TRUE", ParseErrorKind::InvalidBetweenBound),
+        ("x NOT BETWEEN NULL AND y", ParseErrorKind::InvalidBetweenBound),
+        ("x BETWEEN -'a' AND y", ParseErrorKind::InvalidUnaryMinus),
+        ("x BETWEEN ANY(a) AND 10", ParseErrorKind::MisplacedQuantifier),
+    ];
+    for (input, kind) in cases {
+        assert_eq!(parse(input).unwrap_err().kind, kind, "{}", input);
     }
-    assert!(result.is_err(), "Expected error for expressions in BETWEEN bounds");
 }
 
 #[test]
@@ -854,6 +899,45 @@ fn test_in_zero_values() {
     assert!(result.is_err(), "Expected error for mixed Integer/Float in IN list");
 }
 
+#[test]
+fn test_in_with_expression_members() {
+    let cases = [
+        ("region IN (home_region, 'global')", "region IN (home_region, 'global')"),
+        ("x IN (a + 1, b)", "x IN ((a + 1), b)"),
+        ("x NOT IN (-1, -a, ABS(b))", "x NOT IN (-1, -a, ABS(b))"),
+        ("x IN (1 + 1, 3)", "x IN ((1 + 1), 3)"),
+        ("x IN (order.limit, ?)", "x IN (order.limit, ?)"),
+    ];
+    for (input, expected) in cases {
+        let ast = parse(input).unwrap();
+        assert_eq!(ast.to_string(), expected, "{}", input);
+    }
+
+    let ast = parse("x IN (-5, y)").unwrap();
+    let BooleanExpr::Relational(rel) = &ast else { panic!("Expected relational, got {:?}", ast) };
+    let RelationalExpr::In { values, .. } = rel.as_ref() else { panic!("Expected IN, got {:?}", rel) };
+    assert_eq!(values[0], ValueExpr::Literal(ValueLiteral::Integer(-5), Span::new(6, 8, 1, 7)));
+    assert!(matches!(&values[1], ValueExpr::Variable(name, _) if name == "y"));
+}
+
+#[test]
+fn test_in_literal_members_checked_among_expressions() {
+    let cases = [
+        ("x IN (a, 1, 'b')", ParseErrorKind::InListTypeMismatch),
+        ("x IN (a, NULL)", ParseErrorKind::InvalidInListValue),
+        ("x IN (TRUE, a)", ParseErrorKind::InvalidInListValue),
+        ("x IN (a, -'b')", ParseErrorKind::InvalidUnaryMinus),
+        ("x IN (a, ANY(b))", ParseErrorKind::MisplacedQuantifier),
+        ("x IN (a,)", ParseErrorKind::UnexpectedToken),
+    ];
+    for (input, kind) in cases {
+        assert_eq!(parse(input).unwrap_err().kind, kind, "{}", input);
+    }
+    let input = "x IN (1, a, 2.5)";
+    let err = parse(input).unwrap_err();
+    assert_eq!(&input[err.span.start..err.span.end], "2.5");
+}
+
 // ============================================================================
 // IS NULL OPERATOR
 // ============================================================================
@@ -1511,3 +1595,1192 @@ fn test_true_false_case_insensitive() {
     }
     assert!(result.is_ok());
 }
+
+// ============================================================================
+// FUNCTION CALLS
+// ============================================================================
+
+#[test]
+fn test_function_call_in_comparison() {
+    let result = parse("UPPER(name) = 'BOB'");
+    if let Err(e) = &result {
+        eprintln!("Parse error: {}", e);
+    }
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_function_call_nested_and_in_arithmetic() {
+    let result = parse("ABS(ROUND(delta, 2)) * 2 < LENGTH(TRIM(code)) + 0.5");
+    if let Err(e) = &result {
+        eprintln!("Parse error: {}", e);
+    }
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_function_call_in_like_between_in() {
+    for input in [
+        "LOWER(email) LIKE '%@example.com'",
+        "LENGTH(code) BETWEEN 3 AND 8",
+        "UPPER(region) IN ('EU', 'US')",
+        "COALESCE(region, 'us') IS NOT NULL",
+        "NOT SUBSTRING(code, 1, 2) = 'XX'",
+    ] {
+        let result = parse(input);
+        if let Err(e) = &result {
+            eprintln!("Parse error: {}", e);
+        }
+        assert!(result.is_ok(), "Expected function call to parse: {}", input);
+    }
+}
+
+#[test]
+fn test_function_name_case_insensitive() {
+    let result = parse("upper(name) = 'BOB' AND Coalesce(a, b) > 1");
+    if let Err(e) = &result {
+        eprintln!("Parse error: {}", e);
+    }
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_function_call_display() {
+    let ast = parse("COALESCE(a, b + 1, 0) > 2").unwrap();
+    assert_eq!(ast.to_string(), "COALESCE(a, (b + 1), 0) > 2");
+}
+
+#[test]
+fn test_error_unknown_function() {
+    let result = parse("FROBNICATE(x) = 1");
+    assert!(result.is_err(), "Expected unknown function to be rejected");
+    assert!(result.unwrap_err().message.contains("Unknown function 'FROBNICATE'"));
+}
+
+#[test]
+fn test_error_function_wrong_arity() {
+    let result = parse("UPPER(a, b) = 'X'");
+    assert!(result.is_err(), "Expected wrong argument count to be rejected");
+    assert!(result.unwrap_err().message.contains("expects 1 argument(s), got 2"));
+
+    assert!(parse("SUBSTRING(s) = 'x'").is_err());
+    assert!(parse("COALESCE() IS NULL").is_err());
+}
+
+#[test]
+fn test_error_function_call_unclosed() {
+    let result = parse("UPPER(name = 'BOB'");
+    assert!(result.is_err(), "Expected missing ')' to be rejected");
+}
+
+// ============================================================================
+// SOURCE SPANS
+// ============================================================================
+
+fn spanned(input: &str, span: Span) -> &str {
+    &input[span.start..span.end]
+}
+
+#[test]
+fn test_span_covers_whole_expression() {
+    let input = "  (a > 1 OR b < 2)  ";
+    let ast = parse(input).unwrap();
+    assert_eq!(spanned(input, ast.span()), "a > 1 OR b < 2");
+}
+
+#[test]
+fn test_span_of_nested_nodes() {
+    let input = "active AND\n  price * 2 BETWEEN 10 AND 20";
+    let ast = parse(input).unwrap();
+    let BooleanExpr::And(left, right, _) = &ast else { panic!("Expected AND, got {:?}", ast) };
+    assert_eq!(spanned(input, left.span()), "active");
+
+    let BooleanExpr::Relational(rel) = right.as_ref() else { panic!("Expected relational, got {:?}", right) };
+    let RelationalExpr::Between { expr, upper, .. } = rel.as_ref() else { panic!("Expected BETWEEN, got {:?}", rel) };
+    assert_eq!(spanned(input, rel.span()), "price * 2 BETWEEN 10 AND 20");
+    assert_eq!(spanned(input, expr.span()), "price * 2");
+    assert_eq!(spanned(input, upper.span()), "20");
+    assert_eq!((expr.span().line, expr.span().column), (2, 3));
+}
+
+#[test]
+fn test_span_of_function_call() {
+    let input = "UPPER( name ) = 'BOB'";
+    let ast = parse(input).unwrap();
+    let BooleanExpr::Relational(rel) = &ast else { panic!("Expected relational, got {:?}", ast) };
+    let RelationalExpr::Equality { left, .. } = rel.as_ref() else { panic!("Expected equality, got {:?}", rel) };
+    assert_eq!(spanned(input, left.span()), "UPPER( name )");
+}
+
+#[test]
+fn test_error_span_points_at_offending_token() {
+    let input = "a > 1 AND AND b";
+    let err = parse(input).unwrap_err();
+    assert_eq!(spanned(input, err.span), "AND");
+    assert_eq!(err.span.start, 10);
+    assert_eq!(err.to_string(), "Parse error: Expected value expression, got AND at line 1, column 11");
+}
+
+#[test]
+fn test_error_span_on_later_line() {
+    let input = "a = 1\nAND b IN ('x', NULL)";
+    let err = parse(input).unwrap_err();
+    assert_eq!(spanned(input, err.span), "NULL");
+    assert_eq!((err.span.line, err.span.column), (2, 16));
+}
+
+#[test]
+fn test_error_span_from_lexer() {
+    let input = "name = 'unterminated";
+    let err = parse(input).unwrap_err();
+    assert_eq!((err.span.line, err.span.column), (1, 8));
+}
+
+// ============================================================================
+// ERROR KINDS AND DIAGNOSTICS
+// ============================================================================
+
+fn error_kind(input: &str) -> ParseErrorKind {
+    parse(input).unwrap_err().kind
+}
+
+#[test]
+fn test_error_kinds() {
+    assert_eq!(error_kind("a > 1 AND"), ParseErrorKind::UnexpectedToken);
+    assert_eq!(error_kind("a > 1 @ 2"), ParseErrorKind::UnexpectedCharacter);
+    assert_eq!(error_kind("name = 'open"), ParseErrorKind::UnterminatedString);
+    assert_eq!(error_kind("a = 1 /* open"), ParseErrorKind::UnterminatedComment);
+    assert_eq!(error_kind("a = 0x"), ParseErrorKind::InvalidNumber);
+    assert_eq!(error_kind("a = 99999999999999999999"), ParseErrorKind::InvalidNumber);
+    assert_eq!(error_kind("a IN (-'x')"), ParseErrorKind::InvalidUnaryMinus);
+    assert_eq!(error_kind("a IN (1, NULL)"), ParseErrorKind::InvalidInListValue);
+    assert_eq!(error_kind("a IN (1, 'x')"), ParseErrorKind::InListTypeMismatch);
+    assert_eq!(error_kind("a BETWEEN NULL AND 10"), ParseErrorKind::InvalidBetweenBound);
+    assert_eq!(error_kind("a BETWEEN 1 AND 'z'"), ParseErrorKind::BetweenTypeMismatch);
+    assert_eq!(error_kind("a NOT BETWEEN 10 AND 1"), ParseErrorKind::ReversedBetweenBounds);
+    assert_eq!(error_kind("FROBNICATE(a) = 1"), ParseErrorKind::UnknownFunction);
+    assert_eq!(error_kind("UPPER(a, b) = 'X'"), ParseErrorKind::WrongArgumentCount);
+}
+
+#[test]
+fn test_error_expected_and_found_tokens() {
+    let err = parse("a IS NOT 5").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(err.expected, vec!["NULL".to_string()]);
+    assert_eq!(err.found, Some(Token::IntegerLiteral(5)));
+
+    let err = parse("UPPER(a = 'X'").unwrap_err();
+    assert_eq!(err.found, Some(Token::Equal));
+
+    let err = parse("a NOT 5").unwrap_err();
+    assert_eq!(err.expected, vec!["LIKE", "ILIKE", "REGEXP", "SIMILAR", "BETWEEN", "IN", "CONTAINS"]);
+    assert_eq!(err.found, Some(Token::IntegerLiteral(5)));
+
+    // Only unexpected token errors carry expected and found tokens
+    let err = parse("a IN (1, 'x')").unwrap_err();
+    assert!(err.expected.is_empty());
+    assert_eq!(err.found, None);
+}
+
+#[test]
+fn test_error_message_excludes_input() {
+    let err = parse("a BETWEEN 10 AND 1").unwrap_err();
+    assert_eq!(err.message, "BETWEEN lower bound (10) must be less than or equal to upper bound (1)");
+}
+
+#[test]
+fn test_render_underlines_span() {
+    let input = "a BETWEEN 10 AND 1";
+    let err = parse(input).unwrap_err();
+    assert_eq!(
+        err.render(input),
+        "error: BETWEEN lower bound (10) must be less than or equal to upper bound (1)\n \
+         --> line 1, column 11\n  \
+         |\n\
+         1 | a BETWEEN 10 AND 1\n  \
+         |           ^^^^^^^^\n"
+    );
+}
+
+#[test]
+fn test_render_end_of_input_and_multiline_span() {
+    let input = "x = 1 AND";
+    let rendered = parse(input).unwrap_err().render(input);
+    assert!(rendered.ends_with("1 | x = 1 AND\n  |          ^\n"), "{}", rendered);
+
+    // A span over several lines is underlined to the end of its first line
+    let input = "a = 'one\n\ttwo";
+    let rendered = parse(input).unwrap_err().render(input);
+    assert!(rendered.ends_with("1 | a = 'one\n  |     ^^^^\n"), "{}", rendered);
+
+    // Tabs are kept so the caret lines up
+    let input = "a = 1 AND\n\tb @ 2";
+    let rendered = parse(input).unwrap_err().render(input);
+    assert!(rendered.ends_with("2 | \tb @ 2\n  | \t  ^\n"), "{}", rendered);
+}
+
+#[test]
+fn test_render_other_input_does_not_panic() {
+    let err = parse("a = 1 AND\n\tb @ 2").unwrap_err();
+    for other in ["", "a", "é", "a = 1"] {
+        let rendered = err.render(other);
+        assert!(rendered.starts_with("error: "), "{}", rendered);
+        assert!(rendered.ends_with("^\n"), "{}", rendered);
+    }
+    assert!(err.render("").ends_with("2 | \n  | ^\n"));
+
+    // A span no parse produces: line and column 0, and end before start
+    let err = ParseError { span: Span::new(5, 2, 0, 0), ..err };
+    assert!(err.render("abc").ends_with("0 | \n  | ^\n"), "{}", err.render("abc"));
+}
+
+// ============================================================================
+// PARAMETER PLACEHOLDERS
+// ============================================================================
+
+#[test]
+fn test_parameters_parse_as_distinct_nodes() {
+    let ast = parse("a = ? AND b > :limit AND c < ?").unwrap();
+    assert_eq!(ast.to_string(), "((a = ? AND b > :limit) AND c < ?)");
+
+    let BooleanExpr::And(_, last, _) = &ast else { panic!("Expected AND, got {:?}", ast) };
+    let BooleanExpr::Relational(rel) = last.as_ref() else { panic!("Expected relational, got {:?}", last) };
+    let RelationalExpr::Comparison { right, .. } = rel.as_ref() else { panic!("Expected comparison, got {:?}", rel) };
+    assert!(matches!(right, ValueExpr::Parameter(Parameter::Positional(2), _)));
+}
+
+#[test]
+fn test_parameters_in_value_expressions() {
+    assert!(parse("total * ? > :min + 1").is_ok());
+    assert!(parse("UPPER(:name) = name").is_ok());
+    assert!(parse("(? + a) IS NOT NULL").is_ok());
+    assert!(parse(":flag = TRUE").is_ok());
+}
+
+#[test]
+fn test_positional_parameters_numbered_in_order() {
+    // Backtracking over a parenthesized expression must not renumber parameters
+    let ast = parse("(? + a) > ? AND b = ?").unwrap();
+    let BooleanExpr::And(_, last, _) = &ast else { panic!("Expected AND, got {:?}", ast) };
+    let BooleanExpr::Relational(rel) = last.as_ref() else { panic!("Expected relational, got {:?}", last) };
+    let RelationalExpr::Equality { right, .. } = rel.as_ref() else { panic!("Expected equality, got {:?}", rel) };
+    assert!(matches!(right, ValueExpr::Parameter(Parameter::Positional(3), _)));
+}
+
+#[test]
+fn test_error_parameters_where_literal_required() {
+    assert_eq!(error_kind("a LIKE :pattern"), ParseErrorKind::UnexpectedToken);
+    assert_eq!(error_kind("a = : b"), ParseErrorKind::UnexpectedCharacter);
+}
+
+// ============================================================================
+// PARSER OPTIONS
+// ============================================================================
+
+#[test]
+fn test_parser_options_default_matches_parse() {
+    let input = "a = ? AND UPPER(b) = :name";
+    assert_eq!(parse_with_options(input, &ParserOptions::default()).unwrap(), parse(input).unwrap());
+}
+
+#[test]
+fn test_parser_options_disable_parameters() {
+    let options = ParserOptions::new().parameters(false);
+    for input in ["a = ?", "b > :limit", "UPPER(:name) = 'X'"] {
+        let err = parse_with_options(input, &options).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::FeatureDisabled, "{}", input);
+    }
+    assert!(parse_with_options("a = 1", &options).is_ok());
+}
+
+#[test]
+fn test_parser_options_functions() {
+    let mut functions = FunctionRegistry::new();
+    functions.register("tier", &[ValueType::Integer], ValueType::String, |_| Ok(RuntimeValue::Null));
+    let options = ParserOptions::new().functions(functions);
+
+    assert!(parse_with_options("tier(points) = 'gold'", &options).is_ok());
+    assert_eq!(
+        parse_with_options("tier(points, 1) = 'gold'", &options).unwrap_err().kind,
+        ParseErrorKind::WrongArgumentCount
+    );
+    assert_eq!(error_kind("tier(points) = 'gold'"), ParseErrorKind::UnknownFunction);
+}
+
+// ============================================================================
+// SIZE AND NESTING LIMITS
+// ============================================================================
+
+/// Options with no limit other than the nesting depth
+fn depth_only() -> ParserOptions {
+    ParserOptions::new().max_input_length(usize::MAX).max_tokens(usize::MAX)
+}
+
+#[test]
+fn test_limit_input_length() {
+    let options = ParserOptions::new().max_input_length(5);
+    assert!(parse_with_options("a > 1", &options).is_ok());
+
+    let err = parse_with_options("a > 10", &options).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::InputTooLong);
+    assert_eq!(err.span, Span::new(0, 6, 1, 1));
+
+    let long = format!("name = '{}'", "x".repeat(ParserOptions::DEFAULT_MAX_INPUT_LENGTH));
+    assert_eq!(parse(&long).unwrap_err().kind, ParseErrorKind::InputTooLong);
+}
+
+#[test]
+fn test_limit_token_count() {
+    let options = ParserOptions::new().max_tokens(3);
+    assert!(parse_with_options("a > 1 -- comments are not tokens", &options).is_ok());
+
+    let input = "a > 1 AND b";
+    let err = parse_with_options(input, &options).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::TooManyTokens);
+    assert_eq!(&input[err.span.start..err.span.end], "AND");
+}
+
+#[test]
+fn test_limit_in_list_length() {
+    let options = ParserOptions::new().max_in_list(3);
+    assert!(parse_with_options("a IN (1, 2, 3)", &options).is_ok());
+
+    let input = "a NOT IN (1, 2, 3, 4, 5)";
+    let err = parse_with_options(input, &options).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::InListTooLong);
+    assert_eq!(&input[err.span.start..err.span.end], "4");
+
+    let values: Vec<String> = (0..=ParserOptions::DEFAULT_MAX_IN_LIST).map(|i| i.to_string()).collect();
+    let input = format!("a IN ({})", values.join(", "));
+    assert_eq!(parse(&input).unwrap_err().kind, ParseErrorKind::InListTooLong);
+}
+
+#[test]
+fn test_limit_depth_matches_ast_depth() {
+    let inputs = [
+        "flag",
+        "NOT NOT NOT flag",
+        "a > 1 AND b < 2 OR c = 3",
+        "(a > 1 OR b < 2) AND NOT (c = 3)",
+        "a + b * -c > 0",
+        "((a + b) * c) / d BETWEEN 1 AND 10",
+        "UPPER(LOWER(TRIM(name))) = 'X'",
+        "ABS(a - b) + 1 IN (1, 2)",
+        "-(-(-a)) IS NOT NULL",
+        "a OR b OR c OR d",
+        "a + b - c + d > 0",
+        "a * b + c > 0",
+        "a + (b + c) > 0",
+        "a || b || c = 'abc'",
+    ];
+    for input in inputs {
+        let depth = parse(input).unwrap().depth();
+        assert!(parse_with_options(input, &ParserOptions::new().max_depth(depth)).is_ok(), "{}", input);
+        let err = parse_with_options(input, &ParserOptions::new().max_depth(depth - 1)).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::NestingTooDeep, "{}", input);
+    }
+}
+
+#[test]
+fn test_limit_depth_default() {
+    let max = ParserOptions::DEFAULT_MAX_DEPTH;
+    let nots = |n: usize| format!("{}flag", "NOT ".repeat(n));
+    assert_eq!(parse(&nots(max - 1)).unwrap().depth(), max);
+    assert_eq!(parse(&nots(max)).unwrap_err().kind, ParseErrorKind::NestingTooDeep);
+
+    let parens = |n: usize| format!("{}a > 0{}", "(".repeat(n), ")".repeat(n));
+    assert!(parse(&parens(max)).is_ok());
+    assert_eq!(parse(&parens(max + 1)).unwrap_err().kind, ParseErrorKind::NestingTooDeep);
+
+    let value_parens = |n: usize| format!("{}a + 1{} > 0", "(".repeat(n), ")".repeat(n));
+    assert!(parse(&value_parens(max)).is_ok());
+    assert_eq!(parse(&value_parens(max + 1)).unwrap_err().kind, ParseErrorKind::NestingTooDeep);
+}
+
+#[test]
+fn test_limit_depth_default_fits_in_thread_stack() {
+    let shapes: [fn(usize) -> String; 7] = [
+        |n| format!("{}a > 0{}", "(".repeat(n), ")".repeat(n)),
+        |n| format!("{}a + 1{} > 0", "(".repeat(n), ")".repeat(n)),
+        |n| format!("{}flag", "NOT ".repeat(n)),
+        |n| format!("{}a > 0", "- ".repeat(n)),
+        |n| format!("{}a{} = 'A'", "UPPER(".repeat(n), ")".repeat(n)),
+        |n| format!("{}a{} > 0", "ABS((".repeat(n), "))".repeat(n)),
+        |n| format!("{}a > 0{}", "(NOT ".repeat(n), ")".repeat(n)),
+    ];
+    // The deepest expression of each shape the default limits accept parses on
+    // the 2 MiB stack Rust gives spawned threads, even in a debug build
+    std::thread::Builder::new().stack_size(2 << 20).spawn(move || {
+        for shape in shapes {
+            let deepest = (0..=ParserOptions::DEFAULT_MAX_DEPTH).rev().find(|&n| parse(&shape(n)).is_ok()).unwrap();
+            assert!(deepest >= ParserOptions::DEFAULT_MAX_DEPTH / 2 - 2, "{}", shape(1));
+            let err = parse(&shape(deepest + 1)).unwrap_err();
+            assert_eq!(err.kind, ParseErrorKind::NestingTooDeep, "{}", shape(1));
+        }
+    }).unwrap().join().unwrap();
+}
+
+#[test]
+fn test_limit_depth_error_span() {
+    let input = "a * (b + c) + d > 0";
+    let err = parse_with_options(input, &ParserOptions::new().max_depth(3)).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::NestingTooDeep);
+    assert_eq!(&input[err.span.start..err.span.end], "a * (b + c) + d");
+    assert_eq!(err.message, "Expression is nested more than 3 levels deep");
+}
+
+#[test]
+fn test_limit_depth_untrusted_input_does_not_overflow() {
+    let n = 100_000;
+    let inputs = [
+        format!("{}a > 0{}", "(".repeat(n), ")".repeat(n)),
+        format!("{}a + 1{} > 0", "(".repeat(n), ")".repeat(n)),
+        format!("{}flag", "NOT ".repeat(n)),
+        format!("{}a > 0", "- ".repeat(n)),
+        format!("{}a > 0", "UPPER(".repeat(n)),
+    ];
+    for input in &inputs {
+        let err = parse_with_options(input, &depth_only()).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::NestingTooDeep, "{}", &input[..20]);
+    }
+
+    // Chains of one operator are not nested, however long
+    let chains = [
+        format!("a{} > 0", " + a".repeat(n)),
+        format!("flag{}", " AND flag".repeat(n)),
+        format!("a > 0{}", " OR a > 0".repeat(n)),
+    ];
+    for input in &chains {
+        let ast = parse_with_options(input, &depth_only()).unwrap();
+        assert!(ast.depth() <= 3, "{}", &input[..20]);
+    }
+}
+
+#[test]
+fn test_limit_depth_counts_chain_as_one_level() {
+    let input = (0..300).map(|i| format!("x = {}", i)).collect::<Vec<_>>().join(" OR ");
+    assert_eq!(parse(&input).unwrap().depth(), 3);
+
+    let input = format!("a0{} > 0", (1..300).map(|i| format!(" + a{}", i)).collect::<String>());
+    assert_eq!(parse(&input).unwrap().depth(), 3);
+
+    // Each change of precedence, and each parenthesized operand on the right, nests
+    assert_eq!(parse("a + b > 0").unwrap().depth(), 3);
+    assert_eq!(parse("a * b + c > 0").unwrap().depth(), 4);
+    assert_eq!(parse("a + (b + c) > 0").unwrap().depth(), 4);
+    assert_eq!(parse("(a + b) + c > 0").unwrap().depth(), 3);
+    assert_eq!(parse("a > 0 AND b > 0 OR c > 0").unwrap().depth(), 4);
+}
+
+// ============================================================================
+// DECIMAL LITERALS
+// ============================================================================
+
+fn decimals() -> ParserOptions {
+    ParserOptions::new().decimal_literals(true)
+}
+
+fn dec(s: &str) -> Decimal {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_decimal_literal_mode() {
+    let ast = parse_with_options("price = 0.10", &decimals()).unwrap();
+    let BooleanExpr::Relational(rel) = &ast else { panic!("Expected relational, got {:?}", ast) };
+    let RelationalExpr::Equality { right, .. } = rel.as_ref() else { panic!("Expected equality, got {:?}", rel) };
+    assert_eq!(*right, ValueExpr::Literal(ValueLiteral::Decimal(dec("0.10")), Span::new(8, 12, 1, 9)));
+    assert_eq!(ast.to_string(), "price = DECIMAL '0.10'");
+    assert_eq!(pretty::tree_string(&ast), "\
+Relational
+   Equality: Equal
+      Variable: price
+      Literal: Decimal(0.10)
+");
+}
+
+#[test]
+fn test_decimal_literal_mode_off_by_default() {
+    let ast = parse("price = 0.10").unwrap();
+    assert_eq!(ast.to_string(), "price = 0.1");
+}
+
+#[test]
+fn test_decimal_literal_mode_keeps_exponents_as_floats() {
+    let ast = parse_with_options("a = 1.5e3 AND b = 42", &decimals()).unwrap();
+    assert_eq!(ast.to_string(), "(a = 1500.0 AND b = 42)");
+}
+
+#[test]
+fn test_decimal_literal_too_many_digits() {
+    let err = parse_with_options("a = 1.000000000000000000000000000000000000001", &decimals()).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+}
+
+#[test]
+fn test_decimal_between_bounds() {
+    let options = decimals();
+    assert!(parse_with_options("a BETWEEN -1.50 AND 2", &options).is_ok());
+    assert!(parse_with_options("a BETWEEN 1.50 AND 1.5", &options).is_ok());
+    for input in ["a BETWEEN 2.01 AND 2", "a BETWEEN 3 AND 2.99", "a BETWEEN 1.5 AND -1.5"] {
+        let err = parse_with_options(input, &options).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ReversedBetweenBounds, "{}", input);
+    }
+}
+
+#[test]
+fn test_mixed_numeric_between_bounds_compared_exactly() {
+    // 9007199254740993 has no float of its own and rounds to 9007199254740992.0
+    for input in [
+        "x BETWEEN 9007199254740993 AND 9007199254740992.0",
+        "x BETWEEN 9007199254740992.0 AND 9007199254740991",
+        // The float 0.1 is slightly above one tenth
+        "x BETWEEN 0.1 AND DECIMAL '0.1'",
+    ] {
+        let err = parse(input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ReversedBetweenBounds, "{}", input);
+    }
+    assert!(parse("x BETWEEN 9007199254740992 AND 9007199254740992.0").is_ok());
+    assert!(parse("x BETWEEN 9007199254740991 AND 9007199254740992.0").is_ok());
+    assert!(parse("x BETWEEN DECIMAL '0.1' AND 0.1").is_ok());
+    let err = parse("x BETWEEN 9007199254740993 AND 9007199254740992.0").unwrap_err();
+    assert_eq!(err.message, "BETWEEN lower bound (9007199254740993) must be less than or equal to upper bound (9007199254740992.0)");
+}
+
+#[test]
+fn test_decimal_in_list() {
+    let options = decimals();
+    let ast = parse_with_options("a IN (-1.50, 2.25)", &options).unwrap();
+    assert_eq!(ast.to_string(), "a IN (DECIMAL '-1.50', DECIMAL '2.25')");
+    assert!(parse_with_options("a IN (1.50, 2)", &options).is_err());
+}
+
+#[test]
+fn test_typed_decimal_literal() {
+    // DECIMAL '...' is exact whether or not decimal literals are enabled
+    let ast = parse("price = decimal '0.10'").unwrap();
+    assert_eq!(pretty::tree_string(&ast), pretty::tree_string(&parse_with_options("price = 0.10", &decimals()).unwrap()));
+    let ast = parse("a IN (-DECIMAL '1.50', DECIMAL '2')").unwrap();
+    assert_eq!(ast.to_string(), "a IN (DECIMAL '-1.50', DECIMAL '2')");
+    let err = parse("a = DECIMAL '1e3'").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::InvalidTypedLiteral);
+    // Without a string after it, DECIMAL is a variable
+    assert_eq!(parse("decimal > 0").unwrap().to_string(), "decimal > 0");
+}
+
+#[test]
+fn test_decimal_literal_display_round_trips() {
+    for input in ["price = 0.10", "a BETWEEN -1.50 AND 2", "a IN (0.5, 1.25)", "price * 1.100 > -0.001"] {
+        let ast = parse_with_options(input, &decimals()).unwrap();
+        assert_eq!(parse(&ast.to_string()).unwrap().to_string(), ast.to_string(), "{}", input);
+    }
+}
+
+// ============================================================================
+// DATE, TIMESTAMP AND INTERVAL LITERALS
+// ============================================================================
+
+#[test]
+fn test_typed_literals() {
+    let ast = parse("created_at >= TIMESTAMP '2024-01-31T10:00:00+02:00' AND due < date '2024-02-01'").unwrap();
+    assert_eq!(
+        ast.to_string(),
+        "(created_at >= TIMESTAMP '2024-01-31 10:00:00+02:00' AND due < DATE '2024-02-01')"
+    );
+    let ast = parse("DATE '2024-01-31' = due").unwrap();
+    assert_eq!(pretty::tree_string(&ast), "\
+Relational
+   Equality: Equal
+      Literal: Date(2024-01-31)
+      Variable: due
+");
+}
+
+#[test]
+fn test_typed_literal_span() {
+    let ast = parse("d = DATE '2024-01-31'").unwrap();
+    let BooleanExpr::Relational(rel) = &ast else { panic!("Expected relational, got {:?}", ast) };
+    let RelationalExpr::Equality { right, .. } = rel.as_ref() else { panic!("Expected equality, got {:?}", rel) };
+    assert_eq!(right.span(), Span::new(4, 21, 1, 5));
+}
+
+#[test]
+fn test_interval_literal_forms() {
+    let cases = [
+        ("created_at > NOW() - INTERVAL '7' DAY", "created_at > (NOW() - INTERVAL '7 days')"),
+        ("x < INTERVAL '1 year 6 months'", "x < INTERVAL '1 year 6 months'"),
+        ("x < INTERVAL '90' minutes", "x < INTERVAL '1 hour 30 minutes'"),
+        ("x IN (-INTERVAL '1 day', INTERVAL '2 days')", "x IN (INTERVAL '-1 day', INTERVAL '2 days')"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(parse(input).unwrap().to_string(), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_typed_literal_keywords_remain_variable_names() {
+    assert!(parse("date = DATE '2024-01-31'").is_ok());
+    assert!(parse("timestamp > interval").is_ok());
+    assert!(matches!(parse("date").unwrap(), BooleanExpr::Variable(..)));
+}
+
+#[test]
+fn test_invalid_typed_literals() {
+    for input in [
+        "d = DATE '2024-02-30'",
+        "d = DATE '31/01/2024'",
+        "t = TIMESTAMP '2024-01-31 25:00:00'",
+        "i = INTERVAL '7'",
+        "i = INTERVAL '7' fortnights",
+    ] {
+        let err = parse(input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidTypedLiteral, "{}", input);
+    }
+    let err = parse("d IN (-DATE '2024-01-31')").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::InvalidUnaryMinus);
+}
+
+#[test]
+fn test_typed_literal_between_and_in_checks() {
+    assert!(parse("d BETWEEN DATE '2024-01-01' AND TIMESTAMP '2024-01-01 12:00:00'").is_ok());
+    let err = parse("d BETWEEN DATE '2024-12-31' AND DATE '2024-01-01'").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::ReversedBetweenBounds);
+    let err = parse("d BETWEEN INTERVAL '2 days' AND INTERVAL '1' DAY").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::ReversedBetweenBounds);
+    let err = parse("d BETWEEN DATE '2024-01-01' AND INTERVAL '1' DAY").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::BetweenTypeMismatch);
+    let err = parse("d IN (DATE '2024-01-01', TIMESTAMP '2024-01-01 00:00:00')").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::InListTypeMismatch);
+}
+
+// ============================================================================
+// VARIABLE PATHS
+// ============================================================================
+
+#[test]
+fn test_path_in_comparison() {
+    let ast = parse("order.customer.tier = 'gold'").unwrap();
+    let BooleanExpr::Relational(rel) = &ast else { panic!("Expected relational, got {:?}", ast) };
+    let RelationalExpr::Equality { left, .. } = rel.as_ref() else { panic!("Expected equality, got {:?}", rel) };
+    assert_eq!(*left, ValueExpr::Path(
+        "order".to_string(),
+        vec![PathSegment::Field("customer".to_string()), PathSegment::Field("tier".to_string())],
+        Span::new(0, 19, 1, 1),
+    ));
+}
+
+#[test]
+fn test_path_segments_display() {
+    let cases = [
+        ("items[0].sku = 'A1'", "items[0].sku = 'A1'"),
+        ("headers['x-id'] IS NOT NULL", "headers['x-id'] IS NOT NULL"),
+        ("headers['plain'] = 'x'", "headers.plain = 'x'"),
+        ("m['it''s'] = 1", "m['it''s'] = 1"),
+        ("m['in'] = 1", "m['in'] = 1"),
+        ("a.b[2][0] + 1 > LENGTH(c.d)", "(a.b[2][0] + 1) > LENGTH(c.d)"),
+    ];
+    for (input, expected) in cases {
+        let ast = parse(input).unwrap();
+        assert_eq!(ast.to_string(), expected, "{}", input);
+        assert_eq!(parse(&ast.to_string()).unwrap(), parse(expected).unwrap(), "{}", input);
+    }
+}
+
+#[test]
+fn test_path_as_boolean() {
+    let ast = parse("flags.enabled AND NOT flags['beta']").unwrap();
+    assert_eq!(pretty::tree_string(&ast), "\
+And
+   Path: flags.enabled
+   Not
+      Path: flags.beta
+");
+    assert!(matches!(parse("items[0].active").unwrap(), BooleanExpr::Path(..)));
+    assert!(matches!(parse("a.b - 1 > 0").unwrap(), BooleanExpr::Relational(..)));
+}
+
+#[test]
+fn test_invalid_paths() {
+    for input in ["a. = 1", "a[] = 1", "a[x] = 1", "a[0 = 1", "a.'b' = 1"] {
+        let err = parse(input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedToken, "{}", input);
+    }
+}
+
+// ============================================================================
+// COLLECTION PREDICATES
+// ============================================================================
+
+#[test]
+fn test_collection_predicates_display() {
+    let cases = [
+        ("tags CONTAINS 'vip'", "tags CONTAINS 'vip'"),
+        ("tags not contains 'vip'", "tags NOT CONTAINS 'vip'"),
+        ("'vip' IN tags", "'vip' IN tags"),
+        ("x NOT IN order.tags", "x NOT IN order.tags"),
+        ("ANY(scores) > 90", "ANY(scores) > 90"),
+        ("90 <= all(scores)", "90 <= ALL(scores)"),
+        ("ANY(a.b) <> 1 OR CARDINALITY(tags) > 2", "(ANY(a.b) <> 1 OR CARDINALITY(tags) > 2)"),
+    ];
+    for (input, expected) in cases {
+        let ast = parse(input).unwrap();
+        assert_eq!(ast.to_string(), expected, "{}", input);
+        assert_eq!(parse(&ast.to_string()).unwrap().to_string(), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_collection_predicates_ast() {
+    let ast = parse("ANY(scores) > 90 AND tags CONTAINS 'vip' AND x IN tags").unwrap();
+    assert_eq!(pretty::tree_string(&ast), "\
+And
+   And
+      Relational
+         Comparison: GreaterThan
+            Quantified: Any
+               Variable: scores
+            Literal: Integer(90)
+      Relational
+         Contains: negated=false
+            Variable: tags
+            Literal: String(\"vip\")
+   Relational
+      InCollection: negated=false
+         Variable: x
+         Variable: tags
+");
+    let ast = parse("1 = ALL(a)").unwrap();
+    let BooleanExpr::Relational(rel) = &ast else { panic!("Expected relational, got {:?}", ast) };
+    let RelationalExpr::Equality { right, .. } = rel.as_ref() else { panic!("Expected equality, got {:?}", rel) };
+    assert!(matches!(right, ValueExpr::Quantified(Quantifier::All, _, span) if *span == Span::new(4, 10, 1, 5)));
+}
+
+#[test]
+fn test_collection_keywords_remain_identifiers() {
+    for input in ["contains", "contains = 1", "any > 0", "all AND any", "m.contains CONTAINS contains"] {
+        assert!(parse(input).is_ok(), "{}", input);
+    }
+}
+
+#[test]
+fn test_misplaced_quantifier() {
+    for input in [
+        "ANY(a) = ALL(b)",
+        "ANY(a) + 1 > 2",
+        "ABS(ANY(a)) > 2",
+        "ANY(a) LIKE 'x%'",
+        "ANY(a) IS NULL",
+        "ANY(a) BETWEEN 1 AND 2",
+        "tags CONTAINS ANY(b)",
+        "ANY(ANY(a)) = 1",
+    ] {
+        let err = parse(input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MisplacedQuantifier, "{}", input);
+    }
+}
+
+// ============================================================================
+// COLLATIONS
+// ============================================================================
+
+#[test]
+fn test_collate_display() {
+    let cases = [
+        ("country = 'germany' COLLATE NOCASE", "country = 'germany' COLLATE NOCASE"),
+        ("name like 'jo%' collate nocase", "name LIKE 'jo%' COLLATE NOCASE"),
+        ("x NOT BETWEEN 'a' AND 'm' COLLATE Unicode", "x NOT BETWEEN 'a' AND 'm' COLLATE UNICODE"),
+        ("x IN ('a', y) COLLATE binary AND z > 1", "(x IN ('a', y) COLLATE BINARY AND z > 1)"),
+        ("tags CONTAINS 'vip' COLLATE NOCASE", "tags CONTAINS 'vip' COLLATE NOCASE"),
+    ];
+    for (input, expected) in cases {
+        let ast = parse(input).unwrap();
+        assert_eq!(ast.to_string(), expected, "{}", input);
+        assert_eq!(parse(&ast.to_string()).unwrap().to_string(), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_collate_ast() {
+    let ast = parse("name = 'x' COLLATE NOCASE").unwrap();
+    assert_eq!(pretty::tree_string(&ast), "\
+Relational
+   Collated: NOCASE
+      Equality: Equal
+         Variable: name
+         Literal: String(\"x\")
+");
+    let BooleanExpr::Relational(rel) = &ast else { panic!("Expected relational, got {:?}", ast) };
+    assert!(matches!(rel.as_ref(), RelationalExpr::Collated { collation: Collation::NoCase, .. }));
+    assert_eq!(rel.span(), Span::new(0, 25, 1, 1));
+    assert_eq!(ast.depth(), parse("name = 'x'").unwrap().depth());
+}
+
+#[test]
+fn test_collate_errors() {
+    let err = parse("name = 'x' COLLATE FRENCH").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnknownCollation);
+    assert_eq!(err.span, Span::new(19, 25, 1, 20));
+    assert!(err.message.contains("'FRENCH'"), "{}", err.message);
+
+    let err = parse("name = 'x' COLLATE 'nocase'").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(err.expected, vec!["collation name".to_string()]);
+
+    // COLLATE ends a predicate; it cannot follow an operand or another COLLATE
+    for input in ["name COLLATE NOCASE = 'x'", "a = b COLLATE NOCASE COLLATE BINARY"] {
+        assert!(parse(input).is_err(), "{}", input);
+    }
+}
+
+#[test]
+fn test_collate_remains_identifier() {
+    for input in ["collate", "collate = 1", "a.collate > 1", "x = collate COLLATE nocase"] {
+        assert!(parse(input).is_ok(), "{}", input);
+    }
+}
+
+// ============================================================================
+// PATTERN OPERATORS
+// ============================================================================
+
+#[test]
+fn test_pattern_operators_display() {
+    let cases = [
+        ("name ilike 'jo%'", "name ILIKE 'jo%'"),
+        ("name NOT ILIKE '50!%' ESCAPE '!'", "name NOT ILIKE '50!%' ESCAPE '!'"),
+        ("code regexp '^[A-Z]{2}-[0-9]+$'", "code REGEXP '^[A-Z]{2}-[0-9]+$'"),
+        ("code NOT REGEXP 'x'", "code NOT REGEXP 'x'"),
+        ("code similar to '(DE|FR)-%'", "code SIMILAR TO '(DE|FR)-%'"),
+        ("code NOT SIMILAR TO 'a!%' ESCAPE '!'", "code NOT SIMILAR TO 'a!%' ESCAPE '!'"),
+    ];
+    for (input, expected) in cases {
+        let ast = parse(input).unwrap();
+        assert_eq!(ast.to_string(), expected, "{}", input);
+        assert_eq!(parse(&ast.to_string()).unwrap().to_string(), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_pattern_operators_quotes_display_round_trips() {
+    for input in [
+        "s REGEXP '^it''s$'",
+        "s NOT SIMILAR TO '''(a|b)%'",
+        "s SIMILAR TO 'it''''s%' ESCAPE ''''",
+    ] {
+        let ast = parse(input).unwrap();
+        assert_eq!(ast.to_string(), input);
+        assert_eq!(parse(&ast.to_string()).unwrap(), ast, "{}", input);
+    }
+    assert_eq!(
+        pretty::tree_string(&parse("s REGEXP 'it''s' AND t LIKE 'O''B%'").unwrap()),
+        "\
+And
+   Relational
+      Regexp: negated=false, pattern='it''s', escape=None
+         Variable: s
+   Relational
+      Like: negated=false, pattern='O''B%', escape=None
+         Variable: t
+"
+    );
+}
+
+#[test]
+fn test_pattern_operators_ast() {
+    let ast = parse("a ILIKE 'x%' AND b REGEXP '^y' AND c SIMILAR TO 'z|w'").unwrap();
+    assert_eq!(pretty::tree_string(&ast), "\
+And
+   And
+      Relational
+         ILike: negated=false, pattern='x%', escape=None
+            Variable: a
+      Relational
+         Regexp: negated=false, pattern='^y', escape=None
+            Variable: b
+   Relational
+      SimilarTo: negated=false, pattern='z|w', escape=None
+         Variable: c
+");
+    let ast = parse("a NOT SIMILAR TO 'z'").unwrap();
+    let BooleanExpr::Relational(rel) = &ast else { panic!("Expected relational, got {:?}", ast) };
+    let RelationalExpr::Regex { pattern, negated, span, .. } = rel.as_ref() else { panic!("Expected regex, got {:?}", rel) };
+    assert_eq!(pattern.syntax(), RegexSyntax::SimilarTo);
+    assert!(*negated);
+    assert_eq!(*span, Span::new(0, 20, 1, 1));
+}
+
+#[test]
+fn test_invalid_patterns_are_parse_errors() {
+    for (input, start, end) in [
+        ("code REGEXP '[a-'", 12, 17),
+        ("code REGEXP '(x'", 12, 16),
+        ("code SIMILAR TO '[abc'", 16, 22),
+        ("code NOT SIMILAR TO 'ab!' ESCAPE '!'", 20, 25),
+    ] {
+        let err = parse(input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidPattern, "{}", input);
+        assert_eq!((err.span.start, err.span.end), (start, end), "{}", input);
+    }
+
+    let err = parse("code SIMILAR 'x'").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(err.expected, vec!["TO".to_string()]);
+
+    // REGEXP takes no ESCAPE clause, and patterns must be string literals
+    assert!(parse("code REGEXP 'x' ESCAPE '!'").is_err());
+    assert!(parse("code REGEXP pattern").is_err());
+    assert!(parse("code ILIKE 5").is_err());
+}
+
+#[test]
+fn test_pattern_keywords_remain_identifiers() {
+    for input in ["ilike", "regexp = 'x'", "similar > 1 AND to < 2", "ilike ILIKE 'x'", "similar SIMILAR TO 'to'"] {
+        assert!(parse(input).is_ok(), "{}", input);
+    }
+}
+
+// ============================================================================
+// STRING CONCATENATION
+// ============================================================================
+
+#[test]
+fn test_concat_precedence() {
+    let cases = [
+        ("first || ' ' || last = 'Ada Lovelace'", "((first || ' ') || last) = 'Ada Lovelace'"),
+        ("'v' || n + 1 = 'v2'", "('v' || (n + 1)) = 'v2'"),
+        ("a || b * 2 LIKE 'x%'", "(a || (b * 2)) LIKE 'x%'"),
+        ("a || (b || c) IS NULL", "(a || (b || c)) IS NULL"),
+        ("UPPER(a || b) IN ('X', 'Y')", "UPPER((a || b)) IN ('X', 'Y')"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(parse(input).unwrap().to_string(), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_concat_tree_and_span() {
+    let ast = parse("name || '!' = x").unwrap();
+    assert_eq!(pretty::tree_string(&ast), "\
+Relational
+   Equality: Equal
+      Concat
+         Variable: name
+         Literal: String(\"!\")
+      Variable: x
+");
+    let BooleanExpr::Relational(rel) = &ast else { panic!("Expected relational, got {:?}", ast) };
+    let RelationalExpr::Equality { left, .. } = rel.as_ref() else { panic!("Expected equality, got {:?}", rel) };
+    assert!(matches!(left, ValueExpr::Concat(..)));
+    assert_eq!(left.span(), Span::new(0, 11, 1, 1));
+}
+
+#[test]
+fn test_concat_errors() {
+    // A concatenation is a value, not a predicate
+    assert!(parse("a || b").is_err());
+    assert!(parse("a || = 'x'").is_err());
+    assert!(parse("a | b = 'x'").is_err());
+    assert!(parse("ANY(tags) || 'x' = 'y'").is_err());
+}
+
+// ============================================================================
+// CASE EXPRESSIONS
+// ============================================================================
+
+#[test]
+fn test_case_display() {
+    let cases = [
+        (
+            "CASE WHEN tier = 'gold' THEN price * 0.9 ELSE price END < 100",
+            "CASE WHEN tier = 'gold' THEN (price * 0.9) ELSE price END < 100",
+        ),
+        (
+            "case when a and b then 1 when c then 2 end = 1",
+            "CASE WHEN (a AND b) THEN 1 WHEN c THEN 2 END = 1",
+        ),
+        // A simple CASE compares its operand with each WHEN value
+        (
+            "CASE status WHEN 'A' THEN 1 WHEN 'B' THEN 2 ELSE 0 END > 0",
+            "CASE status WHEN 'A' THEN 1 WHEN 'B' THEN 2 ELSE 0 END > 0",
+        ),
+        (
+            "case a + 1 when any(xs) then 'x' when b * 2 then 'y' end = 'x'",
+            "CASE (a + 1) WHEN ANY(xs) THEN 'x' WHEN (b * 2) THEN 'y' END = 'x'",
+        ),
+        (
+            "CASE WHEN a THEN CASE WHEN b THEN 'x' END ELSE 'y' END || '!' = 'x!'",
+            "(CASE WHEN a THEN CASE WHEN b THEN 'x' END ELSE 'y' END || '!') = 'x!'",
+        ),
+        ("2 * CASE WHEN a THEN 1 ELSE -1 END > 0", "(2 * CASE WHEN a THEN 1 ELSE -1 END) > 0"),
+    ];
+    for (input, expected) in cases {
+        let ast = parse(input).unwrap();
+        assert_eq!(ast.to_string(), expected, "{}", input);
+        assert_eq!(parse(expected).unwrap().to_string(), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_case_structure() {
+    let ast = parse("CASE n WHEN 1 THEN 'one' END IS NULL").unwrap();
+    assert_eq!(pretty::tree_string(&ast), "\
+Relational
+   IsNull: negated=false
+      Case
+         Operand
+            Variable: n
+         When
+            Literal: Integer(1)
+         Then
+            Literal: String(\"one\")
+");
+    let BooleanExpr::Relational(rel) = &ast else { panic!("Expected relational, got {:?}", ast) };
+    let RelationalExpr::IsNull { expr, .. } = rel.as_ref() else { panic!("Expected IS NULL, got {:?}", rel) };
+    let ValueExpr::Case { operand, branches, else_result, span } = expr else {
+        panic!("Expected CASE, got {:?}", expr)
+    };
+    assert!(matches!(operand.as_deref(), Some(ValueExpr::Variable(name, _)) if name == "n"));
+    assert!(matches!(
+        branches.as_slice(),
+        [CaseBranch { when: CaseWhen::Value(ValueExpr::Literal(..)), result: ValueExpr::Literal(..) }]
+    ));
+    assert!(else_result.is_none());
+    assert_eq!(*span, Span::new(0, 28, 1, 1));
+}
+
+#[test]
+fn test_nested_simple_case_operands() {
+    // Each operand is stored once, however many values it is compared with
+    let mut input = "x".to_string();
+    for _ in 0..30 {
+        input = format!("CASE {} WHEN 1 THEN 1 WHEN 2 THEN 2 END", input);
+    }
+    let input = format!("{} = 1", input);
+    let start = std::time::Instant::now();
+    let ast = parse(&input).unwrap();
+    assert!(start.elapsed() < std::time::Duration::from_secs(1), "took {:?}", start.elapsed());
+    assert_eq!(ast.to_string(), input);
+    assert_eq!(pretty::tree_string(&ast).lines().count(), 30 * 10 + 4);
+}
+
+#[test]
+fn test_case_depth() {
+    for input in [
+        "CASE WHEN a > 1 THEN b END = 1",
+        "CASE x WHEN 1 THEN 2 ELSE y + 1 END = 3",
+        "CASE WHEN a THEN CASE WHEN b THEN c END END = 1",
+    ] {
+        let depth = parse(input).unwrap().depth();
+        assert!(parse_with_options(input, &ParserOptions::new().max_depth(depth + 1)).is_ok(), "{}", input);
+        let err = parse_with_options(input, &ParserOptions::new().max_depth(depth - 1)).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::NestingTooDeep, "{}", input);
+    }
+    assert_eq!(parse("CASE WHEN a > 1 THEN b END = 1").unwrap().depth(), 4);
+
+    // Each part of a CASE is nested a level within it, and a condition two, so
+    // a CASE within a CASE is limited by its nesting more than by its depth
+    let input = "CASE WHEN a THEN CASE WHEN b THEN c END END = 1";
+    assert_eq!(parse(input).unwrap().depth(), 4);
+    assert!(parse_with_options(input, &ParserOptions::new().max_depth(5)).is_ok());
+    let err = parse_with_options(input, &ParserOptions::new().max_depth(4)).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::NestingTooDeep);
+    assert_eq!(&input[err.span.start..err.span.end], "b");
+}
+
+#[test]
+fn test_case_nesting_default_fits_in_thread_stack() {
+    let shapes: [fn(&str) -> String; 6] = [
+        |inner| format!("CASE WHEN {} > 0 THEN x END", inner),
+        |inner| format!("CASE WHEN ({} > 0) THEN x END", inner),
+        |inner| format!("CASE WHEN flag THEN {} END", inner),
+        |inner| format!("CASE WHEN flag THEN x ELSE {} END", inner),
+        |inner| format!("CASE {} WHEN 1 THEN x END", inner),
+        |inner| format!("CASE x WHEN {} THEN x END", inner),
+    ];
+    // The deepest nesting of each shape the default limits accept parses on the
+    // 2 MiB stack Rust gives spawned threads, even in a debug build
+    std::thread::Builder::new().stack_size(2 << 20).spawn(move || {
+        for shape in shapes {
+            let nested = |n: usize| format!("{} > 0", (0..n).fold("x".to_string(), |inner, _| shape(&inner)));
+            let deepest = (0..=ParserOptions::DEFAULT_MAX_DEPTH).rev().find(|&n| parse(&nested(n)).is_ok()).unwrap();
+            assert!(deepest >= ParserOptions::DEFAULT_MAX_DEPTH / 4, "{}", nested(1));
+            let err = parse(&nested(deepest + 1)).unwrap_err();
+            assert_eq!(err.kind, ParseErrorKind::NestingTooDeep, "{}", nested(1));
+        }
+    }).unwrap().join().unwrap();
+}
+
+#[test]
+fn test_case_literal_result_types() {
+    for input in [
+        "CASE WHEN a THEN 1 WHEN b THEN 2.5 ELSE -3 END > 0",
+        "CASE WHEN a THEN 'x' WHEN b THEN NULL ELSE name END = 'x'",
+        "CASE WHEN a THEN TRUE ELSE FALSE END = flag",
+        "CASE WHEN a THEN DATE '2024-01-01' ELSE TIMESTAMP '2024-01-01 12:00' END < now",
+    ] {
+        assert!(parse(input).is_ok(), "{}", input);
+    }
+
+    let input = "CASE WHEN a THEN 1 WHEN b THEN NULL ELSE 'x' END = 1";
+    let err = parse(input).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::CaseTypeMismatch);
+    assert_eq!(&input[err.span.start..err.span.end], "'x'");
+    assert_eq!(err.message, "CASE results must be of compatible types, found integer and string");
+    let err = parse("CASE n WHEN 1 THEN TRUE ELSE -2 END = 1").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::CaseTypeMismatch);
+}
+
+#[test]
+fn test_case_errors() {
+    let err = parse("CASE WHEN a THEN 1 = 1").unwrap_err();
+    assert_eq!(err.expected, vec!["WHEN".to_string(), "ELSE".to_string(), "END".to_string()]);
+    let err = parse("CASE WHEN a THEN 1 ELSE 2 = 1").unwrap_err();
+    assert_eq!(err.expected, vec!["END".to_string()]);
+    let err = parse("CASE WHEN a 1 END = 1").unwrap_err();
+    assert_eq!(err.expected, vec!["THEN".to_string()]);
+    let err = parse("CASE x END = 1").unwrap_err();
+    assert_eq!(err.expected, vec!["WHEN".to_string()]);
+
+    // A CASE is a value, not a predicate
+    assert!(parse("CASE WHEN a THEN TRUE ELSE FALSE END").is_err());
+    // Results are value expressions
+    assert!(parse("CASE WHEN a THEN b > 1 END = TRUE").is_err());
+    let err = parse("CASE WHEN a THEN ANY(x) END = 1").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::MisplacedQuantifier);
+}
+
+#[test]
+fn test_case_keywords_remain_identifiers() {
+    for input in [
+        "case",
+        "case = 1 AND end > 2",
+        "then IS NULL OR else <> 'x'",
+        "CASE WHEN case THEN else ELSE end END = when",
+        "CASE WHEN a THEN INTERVAL '1' DAY ELSE INTERVAL '2 days' END > gap",
+        "case ILIKE 'x%' AND case CONTAINS 1",
+    ] {
+        assert!(parse(input).is_ok(), "{}", input);
+    }
+    let ast = parse("CASE WHEN a THEN INTERVAL '1' DAY END > gap").unwrap();
+    assert_eq!(ast.to_string(), "CASE WHEN a THEN INTERVAL '1 day' END > gap");
+}
+
+#[test]
+fn test_nested_case_in_parentheses_parses_once() {
+    // Each parenthesized CASE is first tried as a boolean expression; a failed
+    // attempt is not repeated, so the time grows with the input, not with 2^depth
+    let nested = |n: usize| (0..n).fold("x > 0".to_string(), |inner, _| format!("(CASE WHEN {} THEN 1 END) = 1", inner));
+    let deepest = (0..=ParserOptions::DEFAULT_MAX_DEPTH).rev().find(|&n| parse(&nested(n)).is_ok()).unwrap();
+    assert!(deepest >= 16, "{}", deepest);
+    let start = std::time::Instant::now();
+    parse(&nested(deepest)).unwrap();
+    assert!(start.elapsed() < std::time::Duration::from_secs(1), "took {:?}", start.elapsed());
+
+    let options = ParserOptions::new().max_depth(1_000);
+    let input = nested(200);
+    let start = std::time::Instant::now();
+    std::thread::Builder::new().stack_size(64 << 20).spawn(move || {
+        parse_with_options(&input, &options).unwrap();
+    }).unwrap().join().unwrap();
+    assert!(start.elapsed() < std::time::Duration::from_secs(1), "took {:?}", start.elapsed());
+}
@@ -246,38 +246,42 @@ fn test_between_float_and_string() {
 
 #[test]
 fn test_between_variable_lower() {
+    // Non-literal bounds are checked at runtime
     let result = parse("x BETWEEN y AND 10");
-    if let Ok(r) = &result {
-        eprintln!("Expected error but found success: {}", r);
+    if let Err(e) = &result {
+        eprintln!("Parse error: {}", e);
     }
-    assert!(result.is_err(), "Expected error for variable in BETWEEN lower bound");
+    assert!(result.is_ok(), "Expected success for variable in BETWEEN lower bound");
 }
 
 #[test]
 fn test_between_variable_upper() {
+    // Non-literal bounds are checked at runtime
     let result = parse("x BETWEEN 1 AND y");
-    if let Ok(r) = &result {
-        eprintln!("Expected error but found success: {}", r);
+    if let Err(e) = &result {
+        eprintln!("Parse error: {}", e);
     }
-    assert!(result.is_err(), "Expected error for variable in BETWEEN upper bound");
+    assert!(result.is_ok(), "Expected success for variable in BETWEEN upper bound");
 }
 
 #[test]
 fn test_between_expression_lower() {
+    // Non-literal bounds are checked at runtime
     let result = parse("x BETWEEN (y + 5) AND 10");
-    if let Ok(r) = &result {
-        eprintln!("Expected error but found success: {}", r);
+    if let Err(e) = &result {
+        eprintln!("Parse error: {}", e);
     }
-    assert!(result.is_err(), "Expected error for expression in BETWEEN lower bound");
+    assert!(result.is_ok(), "Expected success for expression in BETWEEN lower bound");
 }
 
 #[test]
 fn test_between_expression_upper() {
+    // Non-literal bounds are checked at runtime
     let result = parse("x BETWEEN 1 AND (y * 2)");
-    if let Ok(r) = &result {
-        eprintln!("Expected error but found success: {}", r);
+    if let Err(e) = &result {
+        eprintln!("Parse error: {}", e);
     }
-    assert!(result.is_err(), "Expected error for expression in BETWEEN upper bound");
+    assert!(result.is_ok(), "Expected success for expression in BETWEEN upper bound");
 }
 
 #[test]
@@ -949,13 +953,14 @@ fn test_in_type_mismatch_error_message() {
 
 #[test]
 fn test_between_variable_error_message() {
-    let result = parse("x BETWEEN y AND 10");
+    // A literal bound is still checked when the other bound is a variable
+    let result = parse("x BETWEEN y AND NULL");
     assert!(result.is_err());
     if let Err(e) = result {
         let msg = e.message;
         assert!(
-            msg.contains("Variables") || msg.contains("literal"),
-            "Error should mention variables or literals, got: {}",
+            msg.contains("NULL is not allowed as upper bound"),
+            "Error should name the invalid bound, got: {}",
             msg
         );
     }